@@ -0,0 +1,130 @@
+// Rebuild src/wifi_functions/oui_vendors.csv, the vendor table compiled into
+// the app, from the IEEE MA-L, MA-M and MA-S registries (with the older IAB
+// blocks, which are 36-bit like MA-S).
+//
+//   npm run update-oui                 download the registries
+//   npm run update-oui -- oui.csv ...  use exports downloaded before
+//
+// Only the registry, assignment and organization name are kept; rows are
+// sorted the way `OuiDatabase::to_csv` writes them.
+
+import { readFile, writeFile } from 'node:fs/promises'
+import { dirname, join } from 'node:path'
+import { fileURLToPath } from 'node:url'
+
+const REGISTRIES = [
+  'https://standards-oui.ieee.org/oui/oui.csv',
+  'https://standards-oui.ieee.org/oui28/mam.csv',
+  'https://standards-oui.ieee.org/oui36/oui36.csv',
+  'https://standards-oui.ieee.org/iab/iab.csv',
+]
+
+const HEX_DIGITS = { 'MA-L': 6, 'MA-M': 7, 'MA-S': 9 }
+const ORDER = ['MA-L', 'MA-M', 'MA-S']
+
+const OUTPUT = join(
+  dirname(fileURLToPath(import.meta.url)),
+  '../src/wifi_functions/oui_vendors.csv',
+)
+
+async function fetchRegistry(url) {
+  // The IEEE site turns away requests without a browser-like user agent
+  const response = await fetch(url, { headers: { 'User-Agent': 'Mozilla/5.0' } })
+  if (!response.ok) {
+    throw new Error(`Failed to download ${url}: HTTP ${response.status}`)
+  }
+  return response.text()
+}
+
+/** Rows of a CSV export; quoted fields may hold commas, quotes and newlines. */
+function parseCsv(text) {
+  const rows = []
+  let row = []
+  let field = ''
+  let quoted = false
+  for (let i = 0; i < text.length; i++) {
+    const c = text[i]
+    if (quoted) {
+      if (c === '"' && text[i + 1] === '"') {
+        field += '"'
+        i++
+      } else if (c === '"') {
+        quoted = false
+      } else {
+        field += c
+      }
+    } else if (c === '"' && field.trim() === '') {
+      field = ''
+      quoted = true
+    } else if (c === ',') {
+      row.push(field)
+      field = ''
+    } else if (c === '\n' || c === '\r') {
+      if (c === '\r' && text[i + 1] === '\n') i++
+      row.push(field)
+      rows.push(row)
+      row = []
+      field = ''
+    } else {
+      field += c
+    }
+  }
+  if (quoted) throw new Error('unterminated quoted field')
+  if (field !== '' || row.length > 0) {
+    row.push(field)
+    rows.push(row)
+  }
+  return rows
+}
+
+function collect(entries, source, text) {
+  let added = 0
+  for (const [n, fields] of parseCsv(text.replace(/^\uFEFF/, '')).entries()) {
+    if (fields.length < 3 || fields[0] === 'Registry') continue
+    const registry = fields[0].trim() === 'IAB' ? 'MA-S' : fields[0].trim()
+    if (!(registry in HEX_DIGITS)) continue
+    const assignment = fields[1].trim().toUpperCase()
+    if (!new RegExp(`^[0-9A-F]{${HEX_DIGITS[registry]}}$`).test(assignment)) {
+      throw new Error(`${source} row ${n + 1}: bad ${registry} assignment '${assignment}'`)
+    }
+    const name = fields[2].replace(/\s+/g, ' ').trim()
+    if (name === '') continue
+    entries.set(`${registry},${assignment}`, { registry, assignment, name })
+    added++
+  }
+  return added
+}
+
+function csvField(value) {
+  return /[",]/.test(value) ? `"${value.replaceAll('"', '""')}"` : value
+}
+
+const sources = process.argv.slice(2)
+const entries = new Map()
+for (const source of sources.length > 0 ? sources : REGISTRIES) {
+  const text = /^https?:/.test(source)
+    ? await fetchRegistry(source)
+    : await readFile(source, 'utf8')
+  console.log(`${source}: ${collect(entries, source, text)} assignments`)
+}
+
+const counts = Object.fromEntries(ORDER.map((r) => [r, 0]))
+for (const { registry } of entries.values()) counts[registry]++
+const missing = ORDER.filter((r) => counts[r] === 0)
+if (missing.length > 0) {
+  console.error(`No ${missing.join(', ')} assignments found; ${OUTPUT} left as is`)
+  process.exit(1)
+}
+
+const rows = [...entries.values()].sort(
+  (a, b) =>
+    ORDER.indexOf(a.registry) - ORDER.indexOf(b.registry) ||
+    (a.assignment < b.assignment ? -1 : a.assignment > b.assignment ? 1 : 0),
+)
+const csv = [
+  'Registry,Assignment,Organization Name',
+  ...rows.map((e) => `${e.registry},${e.assignment},${csvField(e.name)}`),
+].join('\n')
+await writeFile(OUTPUT, `${csv}\n`)
+const summary = ORDER.map((r) => `${counts[r]} ${r}`).join(', ')
+console.log(`Wrote ${rows.length} assignments (${summary}) to ${OUTPUT}`)
@@ -0,0 +1,559 @@
+//! Delivery of threat alerts to the server.
+//!
+//! Alerts are queued in the history database first and posted from there by
+//! one background task, so an alert raised while offline goes out once the
+//! server can be reached again, even after a restart. Failed attempts are
+//! retried with exponential backoff and jitter, always with the alert's own
+//! `Idempotency-Key` so the server can drop repeats. A 401 pauses delivery
+//! until `set_credentials` brings a new token.
+
+use crate::history::{QueuedAlert, SharedHistory};
+use crate::structures::DetectedThreat;
+use chrono::{DateTime, Utc};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::Notify;
+use tokio::task::JoinHandle;
+
+const ALERT_PATH: &str = "/threats/alert";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Pause after the queue itself could not be read.
+const STORAGE_RETRY: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Copy)]
+pub struct RetryPolicy {
+    /// Delay after the first failure; doubled with every further one.
+    pub base: Duration,
+    pub max: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            base: Duration::from_secs(2),
+            max: Duration::from_secs(10 * 60),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Wait before retrying an alert that has failed `attempts` times
+    /// before: a random point in the upper half of the exponential step, so
+    /// alerts queued together do not all retry at the same moment.
+    pub fn delay(&self, attempts: u32, rng: &mut impl Rng) -> Duration {
+        let step = self
+            .base
+            .saturating_mul(2u32.saturating_pow(attempts))
+            .min(self.max);
+        rng.gen_range(step / 2..=step)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct AlertQueueStatus {
+    /// Alerts waiting to be delivered.
+    pub depth: u64,
+    /// Alerts the server refused outright; kept but not retried.
+    pub rejected: u64,
+    /// A server URL and token have been set.
+    pub configured: bool,
+    /// The server rejected the token; nothing is sent until a new one is set.
+    pub paused: bool,
+    /// Why the last attempt failed, cleared by the next delivery.
+    pub last_error: Option<String>,
+    pub last_delivered_at: Option<DateTime<Utc>>,
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Default)]
+struct Delivery {
+    server_url: String,
+    auth_token: String,
+    /// Bumped by every `set_credentials`, so a 401 for a token that has
+    /// already been replaced does not pause delivery.
+    generation: u64,
+    unauthorized: bool,
+    last_error: Option<String>,
+    last_delivered_at: Option<DateTime<Utc>>,
+}
+
+struct Credentials {
+    server_url: String,
+    auth_token: String,
+    generation: u64,
+}
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Delivered,
+    Unauthorized,
+    Retry(String),
+    Rejected(String),
+}
+
+fn classify(status: StatusCode) -> Outcome {
+    if status.is_success() {
+        Outcome::Delivered
+    } else if status == StatusCode::UNAUTHORIZED {
+        Outcome::Unauthorized
+    } else if status.is_client_error()
+        && status != StatusCode::REQUEST_TIMEOUT
+        && status != StatusCode::TOO_MANY_REQUESTS
+    {
+        Outcome::Rejected(format!("Server rejected the alert: HTTP {}", status))
+    } else {
+        Outcome::Retry(format!("Alert delivery failed: HTTP {}", status))
+    }
+}
+
+pub struct AlertQueue {
+    history: SharedHistory,
+    retry: RetryPolicy,
+    delivery: Mutex<Delivery>,
+    /// Woken by new alerts and new credentials.
+    wake: Notify,
+    worker: Mutex<Option<JoinHandle<()>>>,
+}
+
+pub type SharedAlertQueue = Arc<AlertQueue>;
+
+impl AlertQueue {
+    pub fn new(history: SharedHistory, retry: RetryPolicy) -> Self {
+        Self {
+            history,
+            retry,
+            delivery: Mutex::new(Delivery::default()),
+            wake: Notify::new(),
+            worker: Mutex::new(None),
+        }
+    }
+
+    /// Queue `threat` for delivery under a new idempotency key.
+    pub fn enqueue(&self, threat: &DetectedThreat) -> Result<(), String> {
+        let payload = serde_json::json!({
+            "threat_type": threat.threat_type,
+            "severity": threat.severity,
+            "network_ssid": threat.network_ssid,
+            "network_bssid": threat.network_bssid,
+            "details": threat.details,
+            "timestamp": threat.timestamp.to_rfc3339(),
+        });
+        let key = uuid::Uuid::new_v4().to_string();
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .enqueue_alert(&key, &payload.to_string(), Utc::now())?;
+        self.wake.notify_one();
+        Ok(())
+    }
+
+    /// Set where alerts go and the token to send, resuming delivery paused
+    /// by a 401. Starts the delivery task on the current tokio runtime if it
+    /// is not running yet.
+    pub fn set_credentials(self: &Arc<Self>, server_url: &str, auth_token: &str) {
+        {
+            let mut delivery = self.delivery.lock().unwrap_or_else(|e| e.into_inner());
+            delivery.server_url = server_url.trim_end_matches('/').to_string();
+            delivery.auth_token = auth_token.to_string();
+            delivery.generation += 1;
+            delivery.unauthorized = false;
+        }
+
+        let mut worker = self.worker.lock().unwrap_or_else(|e| e.into_inner());
+        if worker.as_ref().is_none_or(|w| w.is_finished()) {
+            *worker = Some(tokio::spawn(Arc::clone(self).deliver()));
+        }
+        drop(worker);
+        self.wake.notify_one();
+    }
+
+    pub fn status(&self) -> AlertQueueStatus {
+        let counts = self
+            .history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .alert_queue_counts();
+        let delivery = self.delivery.lock().unwrap_or_else(|e| e.into_inner());
+        let mut status = AlertQueueStatus {
+            configured: !delivery.server_url.is_empty(),
+            paused: delivery.unauthorized,
+            last_error: delivery.last_error.clone(),
+            last_delivered_at: delivery.last_delivered_at,
+            ..Default::default()
+        };
+        match counts {
+            Ok(counts) => {
+                status.depth = counts.pending;
+                status.rejected = counts.rejected;
+                status.next_attempt_at = counts.next_attempt_at;
+            }
+            Err(e) => status.last_error = Some(e),
+        }
+        status
+    }
+
+    fn credentials(&self) -> Option<Credentials> {
+        let delivery = self.delivery.lock().unwrap_or_else(|e| e.into_inner());
+        if delivery.server_url.is_empty() || delivery.unauthorized {
+            return None;
+        }
+        Some(Credentials {
+            server_url: delivery.server_url.clone(),
+            auth_token: delivery.auth_token.clone(),
+            generation: delivery.generation,
+        })
+    }
+
+    async fn deliver(self: Arc<Self>) {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .unwrap_or_default();
+        let mut rng = StdRng::from_entropy();
+
+        loop {
+            let Some(credentials) = self.credentials() else {
+                self.wake.notified().await;
+                continue;
+            };
+            let now = Utc::now();
+            let due = self
+                .history
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .next_due_alert(now);
+            let alert = match due {
+                Ok(Some(alert)) => alert,
+                Ok(None) => {
+                    self.idle(now).await;
+                    continue;
+                }
+                Err(e) => {
+                    eprintln!("Failed to read alert queue: {}", e);
+                    self.delivery
+                        .lock()
+                        .unwrap_or_else(|e| e.into_inner())
+                        .last_error = Some(e);
+                    tokio::time::sleep(STORAGE_RETRY).await;
+                    continue;
+                }
+            };
+
+            let outcome = send(&client, &credentials, &alert).await;
+            self.settle(&alert, outcome, credentials.generation, &mut rng);
+        }
+    }
+
+    /// Sleep until the next retry is due or something new arrives.
+    async fn idle(&self, now: DateTime<Utc>) {
+        let next = self
+            .history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .alert_queue_counts()
+            .ok()
+            .and_then(|counts| counts.next_attempt_at);
+        match next.and_then(|at| (at - now).to_std().ok()) {
+            Some(wait) => {
+                tokio::select! {
+                    _ = tokio::time::sleep(wait) => {}
+                    _ = self.wake.notified() => {}
+                }
+            }
+            None => self.wake.notified().await,
+        }
+    }
+
+    fn settle(&self, alert: &QueuedAlert, outcome: Outcome, generation: u64, rng: &mut impl Rng) {
+        let now = Utc::now();
+        let mut delivery = self.delivery.lock().unwrap_or_else(|e| e.into_inner());
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+
+        let stored = match outcome {
+            Outcome::Delivered => {
+                println!("Threat alert delivered: {}", alert.idempotency_key);
+                delivery.last_delivered_at = Some(now);
+                delivery.last_error = None;
+                history.remove_alert(alert.id)
+            }
+            Outcome::Unauthorized => {
+                // The alert itself is fine; it goes out with the next token
+                if delivery.generation == generation {
+                    let error = "Server rejected the auth token (HTTP 401); alert delivery paused until a new token is set";
+                    eprintln!("{}", error);
+                    delivery.unauthorized = true;
+                    delivery.last_error = Some(error.to_string());
+                }
+                Ok(())
+            }
+            Outcome::Retry(error) => {
+                let delay = self.retry.delay(alert.attempts, rng);
+                eprintln!("{}; retrying in {:.1}s", error, delay.as_secs_f64());
+                let next = now + chrono::Duration::from_std(delay).unwrap_or(chrono::Duration::MAX);
+                let stored = history.retry_alert(alert.id, next, &error);
+                delivery.last_error = Some(error);
+                stored
+            }
+            Outcome::Rejected(error) => {
+                eprintln!("{}; dropping it from the queue", error);
+                let stored = history.reject_alert(alert.id, &error);
+                delivery.last_error = Some(error);
+                stored
+            }
+        };
+        if let Err(e) = stored {
+            eprintln!("Failed to update alert queue: {}", e);
+            delivery.last_error = Some(e);
+        }
+    }
+}
+
+async fn send(client: &reqwest::Client, credentials: &Credentials, alert: &QueuedAlert) -> Outcome {
+    let url = format!("{}{}", credentials.server_url, ALERT_PATH);
+    let response = client
+        .post(&url)
+        .header(
+            "Authorization",
+            format!("Bearer {}", credentials.auth_token),
+        )
+        .header("Content-Type", "application/json")
+        .header("Idempotency-Key", &alert.idempotency_key)
+        .body(alert.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(res) => classify(res.status()),
+        Err(e) => Outcome::Retry(format!("Failed to reach {}: {}", url, e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{classify, AlertQueue, AlertQueueStatus, Outcome, RetryPolicy};
+    use crate::history::HistoryStore;
+    use crate::structures::DetectedThreat;
+    use chrono::Utc;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+    use reqwest::StatusCode;
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn queue() -> Arc<AlertQueue> {
+        let history = Arc::new(Mutex::new(HistoryStore::in_memory().unwrap()));
+        let retry = RetryPolicy {
+            base: Duration::from_millis(10),
+            max: Duration::from_millis(40),
+        };
+        Arc::new(AlertQueue::new(history, retry))
+    }
+
+    fn threat(threat_type: &str) -> DetectedThreat {
+        DetectedThreat {
+            threat_type: threat_type.to_string(),
+            severity: "Critical".to_string(),
+            network_ssid: "Bad".to_string(),
+            network_bssid: "66:77:88:99:aa:bb".to_string(),
+            details: String::new(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    async fn wait_for(
+        queue: &AlertQueue,
+        done: impl Fn(&AlertQueueStatus) -> bool,
+    ) -> AlertQueueStatus {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let status = queue.status();
+                if done(&status) {
+                    return status;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .expect("alert queue did not get there in time")
+    }
+
+    async fn keys(server: &MockServer) -> Vec<String> {
+        server
+            .received_requests()
+            .await
+            .unwrap()
+            .iter()
+            .map(|r| r.headers["idempotency-key"].to_str().unwrap().to_string())
+            .collect()
+    }
+
+    #[test]
+    fn test_backoff_grows_with_jitter_and_cap() {
+        let policy = RetryPolicy::default();
+        let mut rng = StdRng::seed_from_u64(7);
+        for attempts in 0..20 {
+            let step = (policy.base * 2u32.pow(attempts.min(10))).min(policy.max);
+            let delay = policy.delay(attempts, &mut rng);
+            assert!(
+                delay >= step / 2 && delay <= step,
+                "{:?} for {}",
+                delay,
+                attempts
+            );
+        }
+        let delays: HashSet<Duration> = (0..10).map(|_| policy.delay(3, &mut rng)).collect();
+        assert!(delays.len() > 1);
+    }
+
+    #[test]
+    fn test_classify_responses() {
+        assert_eq!(classify(StatusCode::CREATED), Outcome::Delivered);
+        assert_eq!(classify(StatusCode::UNAUTHORIZED), Outcome::Unauthorized);
+        assert!(matches!(
+            classify(StatusCode::SERVICE_UNAVAILABLE),
+            Outcome::Retry(_)
+        ));
+        assert!(matches!(
+            classify(StatusCode::TOO_MANY_REQUESTS),
+            Outcome::Retry(_)
+        ));
+        assert!(matches!(
+            classify(StatusCode::UNPROCESSABLE_ENTITY),
+            Outcome::Rejected(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_delivers_with_token_and_idempotency_key() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/threats/alert"))
+            .and(header("authorization", "Bearer secret"))
+            .respond_with(ResponseTemplate::new(201))
+            .expect(2)
+            .mount(&server)
+            .await;
+        let queue = queue();
+
+        queue.enqueue(&threat("evil_twin")).unwrap();
+        queue.enqueue(&threat("rf_jamming")).unwrap();
+        assert_eq!(queue.status().depth, 2);
+        queue.set_credentials(&format!("{}/", server.uri()), "secret");
+
+        let status = wait_for(&queue, |s| s.depth == 0).await;
+        assert!(status.last_delivered_at.is_some());
+        assert_eq!(status.last_error, None);
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["threat_type"], "evil_twin");
+        let keys = keys(&server).await;
+        assert_ne!(keys[0], keys[1]);
+    }
+
+    #[tokio::test]
+    async fn test_retries_with_same_key_until_accepted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let queue = queue();
+
+        queue.set_credentials(&server.uri(), "secret");
+        queue.enqueue(&threat("evil_twin")).unwrap();
+        wait_for(&queue, |s| s.depth == 0).await;
+
+        let keys = keys(&server).await;
+        assert_eq!(keys.len(), 3);
+        assert!(keys.iter().all(|k| *k == keys[0]));
+    }
+
+    #[tokio::test]
+    async fn test_pauses_on_401_until_new_token() {
+        let server = MockServer::start().await;
+        Mock::given(header("authorization", "Bearer expired"))
+            .respond_with(ResponseTemplate::new(401))
+            .mount(&server)
+            .await;
+        Mock::given(header("authorization", "Bearer fresh"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+        let queue = queue();
+
+        queue.set_credentials(&server.uri(), "expired");
+        queue.enqueue(&threat("evil_twin")).unwrap();
+        let status = wait_for(&queue, |s| s.paused).await;
+        assert_eq!(status.depth, 1);
+        assert!(status.last_error.unwrap().contains("HTTP 401"));
+
+        // New alerts wait too instead of hammering the server
+        queue.enqueue(&threat("rf_jamming")).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(server.received_requests().await.unwrap().len(), 1);
+
+        queue.set_credentials(&server.uri(), "fresh");
+        let status = wait_for(&queue, |s| s.depth == 0).await;
+        assert!(!status.paused);
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_offline_alerts_survive_restart() {
+        let queue = queue();
+        // Nothing listens on the discard port
+        queue.set_credentials("http://127.0.0.1:9", "secret");
+        queue.enqueue(&threat("evil_twin")).unwrap();
+        let status = wait_for(&queue, |s| s.last_error.is_some()).await;
+        assert_eq!(status.depth, 1);
+        assert!(status.next_attempt_at.is_some());
+        assert!(status
+            .last_error
+            .unwrap()
+            .starts_with("Failed to reach http://127.0.0.1:9"));
+
+        // The app restarts with the same database and the server is back
+        queue.worker.lock().unwrap().take().unwrap().abort();
+        let restarted = Arc::new(AlertQueue::new(Arc::clone(&queue.history), queue.retry));
+        assert_eq!(restarted.status().depth, 1);
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+        restarted.set_credentials(&server.uri(), "secret");
+        wait_for(&restarted, |s| s.depth == 0).await;
+    }
+
+    #[tokio::test]
+    async fn test_rejected_alert_is_kept_but_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let queue = queue();
+
+        queue.set_credentials(&server.uri(), "secret");
+        queue.enqueue(&threat("evil_twin")).unwrap();
+        let status = wait_for(&queue, |s| s.rejected == 1).await;
+        assert_eq!(status.depth, 0);
+        assert!(status.last_error.unwrap().contains("HTTP 400"));
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
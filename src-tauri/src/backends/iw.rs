@@ -0,0 +1,101 @@
+use crate::backends::{NmcliBackend, WlanBackend};
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use crate::wifi_functions::{parse_iw_associated, parse_iw_scan};
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Linux backend reading nl80211 scan results through `iw`.
+///
+/// `iw` only sees the radio, so profile handling and connect/disconnect are
+/// passed on to NetworkManager.
+pub struct IwBackend {
+    interface: Option<String>,
+    control: NmcliBackend,
+}
+
+impl IwBackend {
+    /// `interface` pins the wireless device; `None` picks the first one `iw dev` lists.
+    pub fn new(interface: Option<String>) -> Self {
+        Self {
+            interface,
+            control: NmcliBackend,
+        }
+    }
+
+    fn interface(&self) -> Result<String, String> {
+        if let Some(interface) = &self.interface {
+            return Ok(interface.clone());
+        }
+
+        let output = Command::new("iw")
+            .arg("dev")
+            .output()
+            .map_err(|e| format!("Failed to list wireless interfaces: {}", e))?;
+
+        String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .find_map(|line| line.trim().strip_prefix("Interface ").map(|i| i.trim().to_string()))
+            .ok_or_else(|| "No WiFi adapter found. Please ensure your WiFi adapter is installed and enabled.".to_string())
+    }
+
+    fn scan_dump(&self, interface: &str) -> Result<String, String> {
+        let output = Command::new("iw")
+            .args(["dev", interface, "scan", "dump"])
+            .output()
+            .map_err(|e| format!("Failed to scan WiFi networks: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to read scan results on {}: {}",
+                interface,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(String::from_utf8_lossy(&output.stdout).to_string())
+    }
+}
+
+impl WlanBackend for IwBackend {
+    fn name(&self) -> &'static str {
+        "iw"
+    }
+
+    fn scan(&self) -> Result<Vec<WifiNetwork>, String> {
+        let interface = self.interface()?;
+
+        // Triggering needs CAP_NET_ADMIN; without it the dump still holds the
+        // kernel's cached results from the last scan NetworkManager ran.
+        let trigger = Command::new("iw").args(["dev", &interface, "scan", "trigger"]).output();
+        if let Ok(output) = trigger {
+            if output.status.success() {
+                thread::sleep(Duration::from_secs(2));
+            }
+        }
+
+        Ok(parse_iw_scan(&self.scan_dump(&interface)?))
+    }
+
+    fn active_network(&self) -> Result<Vec<WifiNetwork>, String> {
+        let interface = self.interface()?;
+        Ok(parse_iw_associated(&self.scan_dump(&interface)?))
+    }
+
+    fn stored_profile(&self, ssid: &str) -> Result<(AuthSuite, Cipher), String> {
+        self.control.stored_profile(ssid)
+    }
+
+    fn connect(
+        &self,
+        ssid: &str,
+        password: Option<&str>,
+        authentication: Option<&str>,
+    ) -> Result<String, String> {
+        self.control.connect(ssid, password, authentication)
+    }
+
+    fn disconnect(&self) -> Result<String, String> {
+        self.control.disconnect()
+    }
+}
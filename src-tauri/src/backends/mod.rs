@@ -0,0 +1,88 @@
+mod iw;
+mod netsh;
+mod nmcli;
+mod replay;
+#[cfg(windows)]
+mod win32_scan;
+// Only the Win32 scan path reads these buffers, but the conversion is
+// compiled and tested everywhere.
+#[cfg_attr(not(windows), allow(dead_code))]
+mod wlan_bss;
+
+pub use iw::IwBackend;
+pub use netsh::NetshBackend;
+pub use nmcli::NmcliBackend;
+pub use replay::ReplayBackend;
+
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use std::sync::Arc;
+
+/// Source of scan results and connection control for the Tauri commands.
+///
+/// Implementations return networks without `risk` or `is_evil_twin` filled in;
+/// scoring is done by the commands so every backend is judged the same way.
+pub trait WlanBackend: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Scan for nearby access points, one entry per BSSID.
+    fn scan(&self) -> Result<Vec<WifiNetwork>, String>;
+
+    /// The network the adapter is currently associated with.
+    fn active_network(&self) -> Result<Vec<WifiNetwork>, String>;
+
+    /// Authentication and cipher of a saved profile, as `(auth, cipher)`.
+    fn stored_profile(&self, ssid: &str) -> Result<(AuthSuite, Cipher), String>;
+
+    fn connect(
+        &self,
+        ssid: &str,
+        password: Option<&str>,
+        authentication: Option<&str>,
+    ) -> Result<String, String>;
+
+    fn disconnect(&self) -> Result<String, String>;
+}
+
+pub type SharedBackend = Arc<dyn WlanBackend>;
+
+/// Pick the backend for this run. `WIDS_BACKEND` overrides the platform default;
+/// the `iw` backend also honours `WIDS_IW_INTERFACE`, and `replay` reads
+/// snapshots from `WIDS_REPLAY_PATH`.
+pub fn select_backend() -> SharedBackend {
+    let requested = std::env::var("WIDS_BACKEND").unwrap_or_default();
+
+    let backend: SharedBackend = match requested.trim().to_lowercase().as_str() {
+        "netsh" => Arc::new(NetshBackend),
+        "nmcli" => Arc::new(NmcliBackend),
+        "iw" => Arc::new(IwBackend::new(std::env::var("WIDS_IW_INTERFACE").ok())),
+        "replay" => {
+            let path = std::env::var("WIDS_REPLAY_PATH").unwrap_or_default();
+            match ReplayBackend::from_path(std::path::Path::new(&path)) {
+                Ok(replay) => {
+                    println!("Replaying {} scan snapshots from {}", replay.snapshot_count(), path);
+                    Arc::new(replay)
+                }
+                Err(e) => {
+                    eprintln!("Replay backend unavailable: {}", e);
+                    default_backend()
+                }
+            }
+        }
+        "" => default_backend(),
+        other => {
+            eprintln!("Unknown WIDS_BACKEND '{}', using platform default", other);
+            default_backend()
+        }
+    };
+
+    println!("Using WLAN backend: {}", backend.name());
+    backend
+}
+
+fn default_backend() -> SharedBackend {
+    if cfg!(target_os = "linux") {
+        Arc::new(NmcliBackend)
+    } else {
+        Arc::new(NetshBackend)
+    }
+}
@@ -0,0 +1,371 @@
+use crate::backends::WlanBackend;
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use crate::wifi_functions::netsh_labels::{field_of, normalize_security, split_line, NetshField};
+use crate::wifi_functions::{parse_active_interface, parse_interface_status, parse_network_scan};
+use std::fs::File;
+use std::io::Write;
+use std::process::Command;
+use std::thread;
+use std::time::Duration;
+
+/// Windows backend driving `netsh wlan` and the Win32 WLAN API.
+pub struct NetshBackend;
+
+impl WlanBackend for NetshBackend {
+    fn name(&self) -> &'static str {
+        "netsh"
+    }
+
+    fn scan(&self) -> Result<Vec<WifiNetwork>, String> {
+        let interface_output = Command::new("netsh")
+            .args(["wlan", "show", "interfaces"])
+            .output()
+            .map_err(|e| format!("Failed to check WiFi adapter state: {}", e))?;
+
+        let status = parse_interface_status(&decode_output(&interface_output.stdout));
+
+        if !status.present {
+            return Err("No WiFi adapter found. Please ensure your WiFi adapter is installed and enabled.".to_string());
+        }
+
+        if status.radio_off {
+            return Err("WiFi adapter is turned off. Please enable WiFi in Windows Settings or use the WiFi toggle in the system tray.".to_string());
+        }
+
+        #[cfg(windows)]
+        match super::win32_scan::scan_networks() {
+            Ok(networks) => return Ok(networks),
+            Err(e) => eprintln!("Native WLAN scan failed, falling back to netsh: {}", e),
+        }
+
+        #[cfg(windows)]
+        super::win32_scan::trigger_scan();
+        thread::sleep(Duration::from_secs(2));
+
+        let output = Command::new("netsh")
+            .args(["wlan", "show", "networks", "mode=bssid"])
+            .output()
+            .map_err(|e| format!("Failed to scan WiFi networks: {}", e))?;
+
+        let result = decode_output(&output.stdout);
+
+        // A powered-down or busy adapter makes netsh exit with an error,
+        // whose message is in the system language, so only the status is checked
+        if !output.status.success() {
+            return Err(format!(
+                "WiFi adapter is not ready for scanning ({}). Please enable WiFi in Windows Settings.",
+                result.trim()
+            ));
+        }
+
+        Ok(parse_network_scan(&result))
+    }
+
+    fn active_network(&self) -> Result<Vec<WifiNetwork>, String> {
+        let output = Command::new("netsh")
+            .args(["wlan", "show", "interfaces"])
+            .output()
+            .map_err(|e| format!("Failed to query active interface: {}", e))?;
+
+        let result = decode_output(&output.stdout);
+        Ok(parse_active_interface(&result))
+    }
+
+    fn stored_profile(&self, ssid: &str) -> Result<(AuthSuite, Cipher), String> {
+        let output = Command::new("netsh")
+            .args([
+                "wlan",
+                "show",
+                "profile",
+                &format!("name={}", ssid),
+                "key=clear",
+            ])
+            .output()
+            .map_err(|e| format!("Failed to read profile details: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to fetch profile '{}': {}",
+                ssid,
+                String::from_utf8_lossy(&output.stderr)
+            ));
+        }
+
+        let output_str = decode_output(&output.stdout);
+
+        let mut auth = None;
+        let mut encryption = None;
+
+        for line in output_str.lines() {
+            if let Some((key, value)) = split_line(line) {
+                match field_of(key) {
+                    Some(NetshField::Authentication) if auth.is_none() => {
+                        auth = Some(AuthSuite::parse(&normalize_security(value)))
+                    }
+                    Some(NetshField::Cipher) if encryption.is_none() => {
+                        encryption = Some(Cipher::parse(&normalize_security(value)))
+                    }
+                    _ => {}
+                }
+            }
+
+            if auth.is_some() && encryption.is_some() {
+                break;
+            }
+        }
+
+        match (auth, encryption) {
+            (Some(a), Some(e)) => Ok((a, e)),
+            _ => Err("Failed to parse authentication or encryption from stored profile".to_string()),
+        }
+    }
+
+    fn connect(
+        &self,
+        ssid: &str,
+        password: Option<&str>,
+        authentication: Option<&str>,
+    ) -> Result<String, String> {
+        let requested_auth = authentication.map(AuthSuite::parse);
+        let is_open = requested_auth == Some(AuthSuite::Open);
+
+        let known_profiles_output = Command::new("netsh")
+            .args(["wlan", "show", "profiles"])
+            .output()
+            .map_err(|e| format!("Failed to get profiles: {}", e))?;
+
+        // Profile lines are "<label> : <name>" with a localized label, so
+        // only the name is compared.
+        let known_profiles = decode_output(&known_profiles_output.stdout);
+        let is_known = known_profiles
+            .lines()
+            .filter_map(split_line)
+            .any(|(_, name)| name == ssid);
+
+        if is_known {
+            match self.stored_profile(ssid) {
+                Ok((stored_auth, stored_encryption)) => {
+                    let current_auth = requested_auth.clone().unwrap_or_default();
+                    let current_encryption = if is_open { Cipher::None } else { Cipher::Ccmp };
+
+                    let mismatch = stored_auth != current_auth;
+
+                    if mismatch {
+                        println!(
+                            "Profile mismatch detected. Stored: [{}, {}], Current: [{}, {}]. Deleting profile to recreate.",
+                            stored_auth, stored_encryption, current_auth, current_encryption
+                        );
+                        delete_profile(ssid);
+                    } else {
+                        let attempt = try_connect(ssid)?;
+                        if attempt.success {
+                            return Ok(format!("Successfully connected to known network: {}", ssid));
+                        }
+
+                        if password.is_none() && !is_open {
+                            return Err(format!(
+                                "Connection to saved network '{}' failed. Password may have changed.",
+                                ssid
+                            ));
+                        }
+                    }
+                }
+                Err(err) => {
+                    println!(
+                        "Failed to get stored profile details for '{}': {}. Deleting profile as fallback.",
+                        ssid, err
+                    );
+                    delete_profile(ssid);
+                }
+            }
+        }
+
+        let profile = if is_open {
+            generate_open_profile_xml(ssid)
+        } else {
+            let pass =
+                password.ok_or_else(|| "Password is required for secured networks.".to_string())?;
+            generate_profile_xml(ssid, pass)
+        };
+
+        let path = std::env::temp_dir().join("wifi_profile.xml");
+
+        let mut file =
+            File::create(&path).map_err(|e| format!("Failed to create XML file: {}", e))?;
+        file.write_all(profile.as_bytes())
+            .map_err(|e| format!("Failed to write XML: {}", e))?;
+
+        if is_known {
+            delete_profile(ssid);
+        }
+
+        let add_output = Command::new("netsh")
+            .args([
+                "wlan",
+                "add",
+                "profile",
+                &format!("filename={}", path.display()),
+            ])
+            .output()
+            .map_err(|e| format!("Failed to add profile: {}", e))?;
+
+        if !add_output.status.success() {
+            return Err(format!(
+                "Failed to add profile: {}",
+                String::from_utf8_lossy(&add_output.stderr)
+            ));
+        }
+
+        let connect = try_connect(ssid)?;
+        if connect.success {
+            Ok(format!("Connected successfully to '{}'", ssid))
+        } else {
+            Err(format!(
+                "Failed to connect to '{}': {}",
+                ssid,
+                connect.error.unwrap_or_else(|| "Unknown error".to_string())
+            ))
+        }
+    }
+
+    fn disconnect(&self) -> Result<String, String> {
+        let output = Command::new("netsh")
+            .args(["wlan", "disconnect"])
+            .output()
+            .map_err(|e| format!("Failed to execute netsh disconnect: {}", e))?;
+
+        if output.status.success() {
+            Ok("Disconnected from Wi-Fi".to_string())
+        } else {
+            Err(format!(
+                "Failed to disconnect: {}",
+                String::from_utf8_lossy(&output.stderr)
+            ))
+        }
+    }
+}
+
+/// netsh writes in the console's OEM code page (CP850, CP866, ...), which
+/// mangles non-ASCII labels when read as UTF-8.
+#[cfg(windows)]
+fn decode_output(bytes: &[u8]) -> String {
+    use windows::Win32::Globalization::{MultiByteToWideChar, CP_OEMCP, MULTI_BYTE_TO_WIDE_CHAR_FLAGS};
+
+    if let Ok(text) = std::str::from_utf8(bytes) {
+        return text.to_string();
+    }
+
+    unsafe {
+        let flags = MULTI_BYTE_TO_WIDE_CHAR_FLAGS(0);
+        let len = MultiByteToWideChar(CP_OEMCP, flags, bytes, None);
+        if len <= 0 {
+            return String::from_utf8_lossy(bytes).to_string();
+        }
+
+        let mut wide = vec![0u16; len as usize];
+        MultiByteToWideChar(CP_OEMCP, flags, bytes, Some(&mut wide));
+        String::from_utf16_lossy(&wide)
+    }
+}
+
+#[cfg(not(windows))]
+fn decode_output(bytes: &[u8]) -> String {
+    String::from_utf8_lossy(bytes).to_string()
+}
+
+fn delete_profile(ssid: &str) {
+    let _ = Command::new("netsh")
+        .args(["wlan", "delete", "profile", &format!("name={}", ssid)])
+        .output();
+}
+
+fn try_connect(ssid: &str) -> Result<ConnectResult, String> {
+    Command::new("netsh")
+        .args(["wlan", "connect", &format!("name={}", ssid.trim())])
+        .output()
+        .map_err(|e| format!("Failed to execute netsh connect: {}", e))?;
+
+    thread::sleep(Duration::from_secs(5));
+
+    let status_output = Command::new("netsh")
+        .args(["wlan", "show", "interfaces"])
+        .output()
+        .map_err(|e| format!("Failed to check current Wi-Fi connection: {}", e))?;
+
+    let status = parse_interface_status(&decode_output(&status_output.stdout));
+
+    if status.connected && status.ssid == ssid.trim() {
+        Ok(ConnectResult {
+            success: true,
+            error: None,
+        })
+    } else {
+        Ok(ConnectResult {
+            success: false,
+            error: Some(
+                "Connected command accepted, but system is not connected (possibly wrong password)"
+                    .to_string(),
+            ),
+        })
+    }
+}
+
+fn generate_profile_xml(ssid: &str, password: &str) -> String {
+    format!(
+        r#"<?xml version="1.0"?>
+<WLANProfile xmlns="http://www.microsoft.com/networking/WLAN/profile/v1">
+  <name>{ssid}</name>
+  <SSIDConfig>
+    <SSID>
+      <name>{ssid}</name>
+    </SSID>
+  </SSIDConfig>
+  <connectionType>ESS</connectionType>
+  <connectionMode>manual</connectionMode>
+  <MSM>
+    <security>
+      <authEncryption>
+        <authentication>WPA2PSK</authentication>
+        <encryption>AES</encryption>
+        <useOneX>false</useOneX>
+      </authEncryption>
+      <sharedKey>
+        <keyType>passPhrase</keyType>
+        <protected>false</protected>
+        <keyMaterial>{password}</keyMaterial>
+      </sharedKey>
+    </security>
+  </MSM>
+</WLANProfile>"#
+    )
+}
+
+fn generate_open_profile_xml(ssid: &str) -> String {
+    format!(
+        r#"<?xml version="1.0"?>
+<WLANProfile xmlns="http://www.microsoft.com/networking/WLAN/profile/v1">
+  <name>{ssid}</name>
+  <SSIDConfig>
+    <SSID>
+      <name>{ssid}</name>
+    </SSID>
+  </SSIDConfig>
+  <connectionType>ESS</connectionType>
+  <connectionMode>manual</connectionMode>
+  <MSM>
+    <security>
+      <authEncryption>
+        <authentication>open</authentication>
+        <encryption>none</encryption>
+        <useOneX>false</useOneX>
+      </authEncryption>
+    </security>
+  </MSM>
+</WLANProfile>"#
+    )
+}
+
+struct ConnectResult {
+    success: bool,
+    error: Option<String>,
+}
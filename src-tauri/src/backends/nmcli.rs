@@ -0,0 +1,159 @@
+use crate::backends::WlanBackend;
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use crate::wifi_functions::{parse_nmcli_active, parse_nmcli_scan, NMCLI_SCAN_FIELDS};
+use std::process::{Command, Output};
+
+/// Linux backend driving NetworkManager through `nmcli`.
+pub struct NmcliBackend;
+
+impl WlanBackend for NmcliBackend {
+    fn name(&self) -> &'static str {
+        "nmcli"
+    }
+
+    fn scan(&self) -> Result<Vec<WifiNetwork>, String> {
+        let radio = run_nmcli(&["radio", "wifi"])
+            .map_err(|e| format!("Failed to check WiFi adapter state: {}", e))?;
+        if String::from_utf8_lossy(&radio.stdout).trim() == "disabled" {
+            return Err("WiFi radio is turned off. Please enable it with `nmcli radio wifi on`.".to_string());
+        }
+
+        let output = run_nmcli(&["-t", "-f", NMCLI_SCAN_FIELDS, "dev", "wifi", "list", "--rescan", "yes"])
+            .map_err(|e| format!("Failed to scan WiFi networks: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to scan WiFi networks: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        Ok(parse_nmcli_scan(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn active_network(&self) -> Result<Vec<WifiNetwork>, String> {
+        let fields = format!("ACTIVE,{}", NMCLI_SCAN_FIELDS);
+        let output = run_nmcli(&["-t", "-f", &fields, "dev", "wifi", "list", "--rescan", "no"])
+            .map_err(|e| format!("Failed to query active interface: {}", e))?;
+
+        Ok(parse_nmcli_active(&String::from_utf8_lossy(&output.stdout)))
+    }
+
+    fn stored_profile(&self, ssid: &str) -> Result<(AuthSuite, Cipher), String> {
+        let output = run_nmcli(&[
+            "-t",
+            "-f",
+            "802-11-wireless-security.key-mgmt,802-11-wireless-security.pairwise",
+            "connection",
+            "show",
+            "id",
+            ssid,
+        ])
+        .map_err(|e| format!("Failed to read profile details: {}", e))?;
+
+        if !output.status.success() {
+            return Err(format!(
+                "Failed to fetch profile '{}': {}",
+                ssid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ));
+        }
+
+        let mut key_mgmt = String::new();
+        let mut pairwise = String::new();
+        for line in String::from_utf8_lossy(&output.stdout).lines() {
+            if let Some((key, value)) = line.split_once(':') {
+                match key {
+                    "802-11-wireless-security.key-mgmt" => key_mgmt = value.trim().to_string(),
+                    "802-11-wireless-security.pairwise" => pairwise = value.trim().to_string(),
+                    _ => {}
+                }
+            }
+        }
+
+        let auth = match key_mgmt.as_str() {
+            "" | "none" => AuthSuite::Open,
+            other => AuthSuite::parse(other),
+        };
+        let encryption = if pairwise.contains("tkip") && !pairwise.contains("ccmp") {
+            Cipher::Tkip
+        } else if key_mgmt.is_empty() || key_mgmt == "none" {
+            Cipher::None
+        } else {
+            Cipher::Ccmp
+        };
+
+        Ok((auth, encryption))
+    }
+
+    fn connect(
+        &self,
+        ssid: &str,
+        password: Option<&str>,
+        authentication: Option<&str>,
+    ) -> Result<String, String> {
+        let requested_auth = authentication.map(AuthSuite::parse);
+        let is_open = requested_auth == Some(AuthSuite::Open);
+
+        if let Ok((stored_auth, _)) = self.stored_profile(ssid) {
+            let current_auth = requested_auth.unwrap_or_default();
+            if stored_auth != current_auth {
+                println!(
+                    "Profile mismatch detected. Stored: {}, Current: {}. Deleting profile to recreate.",
+                    stored_auth, current_auth
+                );
+                let _ = run_nmcli(&["connection", "delete", "id", ssid]);
+            }
+        }
+
+        let mut args = vec!["dev", "wifi", "connect", ssid];
+        if let Some(pass) = password.filter(|_| !is_open) {
+            args.extend(["password", pass]);
+        }
+
+        let output = run_nmcli(&args).map_err(|e| format!("Failed to execute nmcli connect: {}", e))?;
+
+        if output.status.success() {
+            Ok(format!("Connected successfully to '{}'", ssid))
+        } else if password.is_none() && !is_open {
+            Err(format!(
+                "Connection to saved network '{}' failed. Password may have changed.",
+                ssid
+            ))
+        } else {
+            Err(format!(
+                "Failed to connect to '{}': {}",
+                ssid,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+
+    fn disconnect(&self) -> Result<String, String> {
+        let devices = run_nmcli(&["-t", "-f", "DEVICE,TYPE,STATE", "dev"])
+            .map_err(|e| format!("Failed to list network devices: {}", e))?;
+
+        let device = String::from_utf8_lossy(&devices.stdout)
+            .lines()
+            .map(|line| line.split(':').collect::<Vec<_>>())
+            .find(|f| f.len() >= 3 && f[1] == "wifi" && f[2] == "connected")
+            .map(|f| f[0].to_string())
+            .ok_or_else(|| "No connected WiFi device found".to_string())?;
+
+        let output = run_nmcli(&["dev", "disconnect", &device])
+            .map_err(|e| format!("Failed to execute nmcli disconnect: {}", e))?;
+
+        if output.status.success() {
+            Ok("Disconnected from Wi-Fi".to_string())
+        } else {
+            Err(format!(
+                "Failed to disconnect: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            ))
+        }
+    }
+}
+
+fn run_nmcli(args: &[&str]) -> std::io::Result<Output> {
+    Command::new("nmcli").args(args).output()
+}
@@ -0,0 +1,307 @@
+use crate::backends::WlanBackend;
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use crate::wifi_functions::{
+    parse_iw_associated, parse_iw_scan, parse_network_scan, parse_nmcli_scan,
+};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+use std::sync::Mutex;
+
+/// Backend that plays back recorded scans instead of touching a radio.
+///
+/// Each call to `scan` returns the next snapshot, wrapping around at the end;
+/// `active_network` answers from the snapshot returned last.
+pub struct ReplayBackend {
+    snapshots: Vec<Snapshot>,
+    cursor: Mutex<usize>,
+}
+
+/// One recorded scan in the raw form it was captured in.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(tag = "format", rename_all = "lowercase")]
+pub enum SnapshotSource {
+    Netsh { output: String },
+    Nmcli { output: String },
+    Iw { output: String },
+    Networks { networks: Vec<WifiNetwork> },
+}
+
+#[derive(Deserialize, Debug, Clone)]
+struct Snapshot {
+    #[serde(flatten)]
+    source: SnapshotSource,
+    #[serde(default)]
+    active_bssid: Option<String>,
+}
+
+impl Snapshot {
+    fn networks(&self) -> Vec<WifiNetwork> {
+        let mut networks = match &self.source {
+            SnapshotSource::Netsh { output } => parse_network_scan(output),
+            SnapshotSource::Nmcli { output } => parse_nmcli_scan(output),
+            SnapshotSource::Iw { output } => parse_iw_scan(output),
+            SnapshotSource::Networks { networks } => networks.clone(),
+        };
+
+        // Recorded lists may carry the verdicts of the run they came from.
+        for net in networks.iter_mut() {
+            net.risk = String::new();
+            net.risk_assessment = None;
+            net.is_evil_twin = false;
+        }
+
+        networks
+    }
+
+    fn active(&self) -> Vec<WifiNetwork> {
+        match (&self.active_bssid, &self.source) {
+            (Some(bssid), _) => self
+                .networks()
+                .into_iter()
+                .filter(|n| n.bssid.eq_ignore_ascii_case(bssid))
+                .take(1)
+                .collect(),
+            (None, SnapshotSource::Iw { output }) => parse_iw_associated(output),
+            (None, _) => Vec::new(),
+        }
+    }
+}
+
+impl ReplayBackend {
+    /// Load snapshots from a JSON-lines file or from a directory with one
+    /// snapshot per file (`.netsh`, `.nmcli`, `.iw` or `.json`), played in
+    /// file-name order.
+    pub fn from_path(path: &Path) -> Result<Self, String> {
+        let snapshots = if path.is_dir() {
+            load_directory(path)?
+        } else {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read replay file {}: {}", path.display(), e))?;
+            parse_jsonl(&content)?
+        };
+
+        if snapshots.is_empty() {
+            return Err(format!("No scan snapshots found in {}", path.display()));
+        }
+
+        Ok(Self {
+            snapshots,
+            cursor: Mutex::new(0),
+        })
+    }
+
+    pub fn snapshot_count(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    fn current(&self) -> &Snapshot {
+        let cursor = *self.cursor.lock().unwrap();
+        &self.snapshots[cursor.saturating_sub(1) % self.snapshots.len()]
+    }
+}
+
+impl WlanBackend for ReplayBackend {
+    fn name(&self) -> &'static str {
+        "replay"
+    }
+
+    fn scan(&self) -> Result<Vec<WifiNetwork>, String> {
+        let mut cursor = self.cursor.lock().unwrap();
+        let snapshot = &self.snapshots[*cursor % self.snapshots.len()];
+        *cursor += 1;
+
+        Ok(snapshot.networks())
+    }
+
+    fn active_network(&self) -> Result<Vec<WifiNetwork>, String> {
+        Ok(self.current().active())
+    }
+
+    fn stored_profile(&self, ssid: &str) -> Result<(AuthSuite, Cipher), String> {
+        Err(format!("No stored profile for '{}' in replay mode", ssid))
+    }
+
+    fn connect(
+        &self,
+        ssid: &str,
+        _password: Option<&str>,
+        _authentication: Option<&str>,
+    ) -> Result<String, String> {
+        Err(format!("Cannot connect to '{}' in replay mode", ssid))
+    }
+
+    fn disconnect(&self) -> Result<String, String> {
+        Err("Cannot disconnect in replay mode".to_string())
+    }
+}
+
+fn parse_jsonl(content: &str) -> Result<Vec<Snapshot>, String> {
+    content
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| format!("Invalid replay record on line {}: {}", i + 1, e))
+        })
+        .collect()
+}
+
+fn load_directory(dir: &Path) -> Result<Vec<Snapshot>, String> {
+    let mut paths: Vec<_> = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read replay directory {}: {}", dir.display(), e))?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| path.is_file())
+        .collect();
+    paths.sort();
+
+    paths
+        .iter()
+        .map(|path| {
+            let content = fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read snapshot {}: {}", path.display(), e))?;
+
+            let source = match path.extension().and_then(|e| e.to_str()) {
+                Some("netsh") => SnapshotSource::Netsh { output: content },
+                Some("nmcli") => SnapshotSource::Nmcli { output: content },
+                Some("iw") => SnapshotSource::Iw { output: content },
+                Some("json") => SnapshotSource::Networks {
+                    networks: serde_json::from_str(&content)
+                        .map_err(|e| format!("Invalid snapshot {}: {}", path.display(), e))?,
+                },
+                _ => return Err(format!("Unknown snapshot format: {}", path.display())),
+            };
+
+            Ok(Snapshot {
+                source,
+                active_bssid: None,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReplayBackend;
+    use crate::backends::WlanBackend;
+    use crate::baseline::BaselineStore;
+    use crate::rules::RuleSet;
+    use crate::structures::WifiNetwork;
+    use crate::wifi_functions::{score_scan, KarmaDetector, RiskPolicy, ScoringContext};
+    use std::path::Path;
+
+    fn fixture(name: &str) -> ReplayBackend {
+        let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures/replay")
+            .join(name);
+        ReplayBackend::from_path(&path).unwrap()
+    }
+
+    fn score(scan: Vec<WifiNetwork>) -> Vec<WifiNetwork> {
+        let context = ScoringContext {
+            policy: &RiskPolicy::default(),
+            rules: &RuleSet::default(),
+            baseline: &BaselineStore::default(),
+            whitelisted: &[],
+        };
+        score_scan(scan, &context, &mut KarmaDetector::default())
+    }
+
+    #[test]
+    fn test_jsonl_snapshots_play_in_order() {
+        let backend = fixture("session.jsonl");
+        assert_eq!(backend.snapshot_count(), 2);
+
+        let first = backend.scan().unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].signal.map(|s| s.percent), Some(82));
+
+        let second = backend.scan().unwrap();
+        assert_eq!(second.len(), 2);
+
+        // Wraps around for demos that run longer than the recording.
+        assert_eq!(backend.scan().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_active_network_follows_last_scan() {
+        let backend = fixture("session.jsonl");
+
+        // Before the first scan the first snapshot is current.
+        assert_eq!(
+            backend.active_network().unwrap()[0].bssid,
+            "f4:92:bf:12:34:56"
+        );
+
+        backend.scan().unwrap();
+        assert_eq!(
+            backend.active_network().unwrap()[0].bssid,
+            "f4:92:bf:12:34:56"
+        );
+
+        backend.scan().unwrap();
+        assert!(backend.active_network().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_directory_mixes_formats() {
+        let backend = fixture("session");
+        assert_eq!(backend.snapshot_count(), 3);
+
+        assert_eq!(backend.scan().unwrap()[0].ssid, "HomeNet");
+        assert_eq!(backend.scan().unwrap().len(), 10);
+
+        let recorded = backend.scan().unwrap();
+        assert_eq!(recorded.len(), 2);
+        // Verdicts stored with the recording are discarded.
+        assert!(recorded
+            .iter()
+            .all(|n| !n.is_evil_twin && n.risk.is_empty()));
+    }
+
+    #[test]
+    fn test_pipeline_regression_open_clone() {
+        let backend = fixture("session.jsonl");
+        backend.scan().unwrap();
+
+        let networks = score(backend.scan().unwrap());
+        let clone = networks
+            .iter()
+            .find(|n| n.bssid == "02:13:37:00:00:01")
+            .unwrap();
+        let genuine = networks
+            .iter()
+            .find(|n| n.bssid == "f4:92:bf:12:34:56")
+            .unwrap();
+
+        assert!(clone.is_evil_twin);
+        assert!(!genuine.is_evil_twin);
+    }
+
+    #[test]
+    fn test_mesh_nodes_on_different_bands_are_not_twins() {
+        let backend = fixture("session");
+        backend.scan().unwrap();
+        backend.scan().unwrap();
+
+        // Two nodes of one mesh system on different bands, recorded when the
+        // old per-SSID heuristic flagged the closer node as a twin.
+        let networks = score(backend.scan().unwrap());
+        let near_node = networks
+            .iter()
+            .find(|n| n.bssid == "a0:40:a0:10:20:30")
+            .unwrap();
+
+        assert!(!near_node.is_evil_twin);
+        assert_eq!(near_node.risk, "L");
+    }
+
+    #[test]
+    fn test_invalid_record_reports_line() {
+        let err =
+            super::parse_jsonl("{\"format\":\"netsh\",\"output\":\"\"}\n{\"format\":\"pcap\"}\n")
+                .unwrap_err();
+        assert!(err.contains("line 2"));
+    }
+}
@@ -0,0 +1,210 @@
+use super::wlan_bss::networks_from_wlan_lists;
+use crate::structures::WifiNetwork;
+use std::ffi::c_void;
+use std::ptr::{null, null_mut};
+use std::sync::{Condvar, Mutex};
+use std::time::Duration;
+use windows::core::GUID;
+use windows::{Win32::Foundation::*, Win32::NetworkManagement::WiFi::*};
+
+/// Drivers normally finish a scan in 2-4 seconds.
+const SCAN_TIMEOUT: Duration = Duration::from_secs(6);
+
+/// Interfaces whose scan has completed or failed, shared with the callback.
+struct ScanWait {
+    finished: Mutex<Vec<GUID>>,
+    signal: Condvar,
+}
+
+pub fn trigger_scan() {
+    unsafe {
+        let mut client_handle = HANDLE(null_mut());
+        let mut negotiated_version: u32 = 0;
+
+        let result = WlanOpenHandle(2, Some(null()), &mut negotiated_version, &mut client_handle);
+        if result != ERROR_SUCCESS.0 {
+            println!("Failed to open WLAN handle.");
+            return;
+        }
+
+        let mut iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST = null_mut();
+
+        let result = WlanEnumInterfaces(client_handle, Some(null()), &mut iface_list_ptr);
+        if result != ERROR_SUCCESS.0 {
+            println!("Failed to enumerate interfaces.");
+            WlanCloseHandle(client_handle, Some(null()));
+            return;
+        }
+
+        let iface_list = &*iface_list_ptr;
+        for i in 0..iface_list.dwNumberOfItems {
+            let iface_info = iface_list.InterfaceInfo[i as usize];
+            println!(
+                "Triggering scan on interface: {:?}",
+                iface_info.strInterfaceDescription
+            );
+
+            let result = WlanScan(
+                client_handle,
+                &iface_info.InterfaceGuid,
+                None,
+                None,
+                Some(null()),
+            );
+
+            if result != ERROR_SUCCESS.0 {
+                println!("WlanScan failed.");
+            }
+        }
+
+        WlanFreeMemory(iface_list_ptr as *mut c_void);
+
+        WlanCloseHandle(client_handle, Some(null()));
+    }
+}
+
+unsafe extern "system" fn on_notification(data: *mut L2_NOTIFICATION_DATA, context: *mut c_void) {
+    if data.is_null() || context.is_null() {
+        return;
+    }
+
+    let data = &*data;
+    let code = data.NotificationCode as i32;
+    if code == wlan_notification_acm_scan_complete.0 || code == wlan_notification_acm_scan_fail.0 {
+        let wait = &*(context as *const ScanWait);
+        wait.finished.lock().unwrap().push(data.InterfaceGuid);
+        wait.signal.notify_all();
+    }
+}
+
+/// Scan every interface, wait for the ACM scan-complete notification and read
+/// the BSS and available-network lists straight from the WLAN API.
+pub fn scan_networks() -> Result<Vec<WifiNetwork>, String> {
+    unsafe {
+        let mut client_handle = HANDLE(null_mut());
+        let mut negotiated_version: u32 = 0;
+
+        let result = WlanOpenHandle(2, Some(null()), &mut negotiated_version, &mut client_handle);
+        if result != ERROR_SUCCESS.0 {
+            return Err(format!("Failed to open WLAN handle: error {}", result));
+        }
+
+        let wait = ScanWait {
+            finished: Mutex::new(Vec::new()),
+            signal: Condvar::new(),
+        };
+
+        let result = WlanRegisterNotification(
+            client_handle,
+            WLAN_NOTIFICATION_SOURCE_ACM,
+            true,
+            Some(on_notification),
+            Some(&wait as *const ScanWait as *const c_void),
+            Some(null()),
+            None,
+        );
+        if result != ERROR_SUCCESS.0 {
+            WlanCloseHandle(client_handle, Some(null()));
+            return Err(format!("Failed to register for WLAN notifications: error {}", result));
+        }
+
+        let networks = scan_interfaces(client_handle, &wait);
+
+        // Unregister before `wait` goes out of scope; the callback holds a
+        // pointer to it.
+        WlanRegisterNotification(
+            client_handle,
+            WLAN_NOTIFICATION_SOURCE_NONE,
+            true,
+            None,
+            None,
+            Some(null()),
+            None,
+        );
+        WlanCloseHandle(client_handle, Some(null()));
+
+        networks
+    }
+}
+
+unsafe fn scan_interfaces(client_handle: HANDLE, wait: &ScanWait) -> Result<Vec<WifiNetwork>, String> {
+    let mut iface_list_ptr: *mut WLAN_INTERFACE_INFO_LIST = null_mut();
+
+    let result = WlanEnumInterfaces(client_handle, Some(null()), &mut iface_list_ptr);
+    if result != ERROR_SUCCESS.0 {
+        return Err(format!("Failed to enumerate interfaces: error {}", result));
+    }
+
+    let iface_list = &*iface_list_ptr;
+    let guids: Vec<GUID> = (0..iface_list.dwNumberOfItems as usize)
+        .map(|i| (*iface_list.InterfaceInfo.as_ptr().add(i)).InterfaceGuid)
+        .collect();
+    WlanFreeMemory(iface_list_ptr as *mut c_void);
+
+    if guids.is_empty() {
+        return Err("No WiFi adapter found. Please ensure your WiFi adapter is installed and enabled.".to_string());
+    }
+
+    let mut networks = Vec::new();
+    for guid in guids {
+        let result = WlanScan(client_handle, &guid, None, None, Some(null()));
+        if result == ERROR_SUCCESS.0 {
+            let finished = wait.finished.lock().unwrap();
+            let (_, timeout) = wait
+                .signal
+                .wait_timeout_while(finished, SCAN_TIMEOUT, |done| !done.contains(&guid))
+                .unwrap();
+            if timeout.timed_out() {
+                println!("Scan did not complete within {:?}; reading cached results.", SCAN_TIMEOUT);
+            }
+        } else {
+            println!("WlanScan failed with error {}; reading cached results.", result);
+        }
+
+        let bss_list = read_bss_list(client_handle, &guid)?;
+        let available_list = read_available_list(client_handle, &guid)?;
+        networks.extend(networks_from_wlan_lists(&bss_list, &available_list)?);
+    }
+
+    Ok(networks)
+}
+
+unsafe fn read_bss_list(client_handle: HANDLE, guid: &GUID) -> Result<Vec<u8>, String> {
+    let mut list_ptr: *mut WLAN_BSS_LIST = null_mut();
+
+    let result = WlanGetNetworkBssList(
+        client_handle,
+        guid,
+        None,
+        dot11_BSS_type_any,
+        false,
+        Some(null()),
+        &mut list_ptr,
+    );
+    if result != ERROR_SUCCESS.0 {
+        return Err(format!("WlanGetNetworkBssList failed: error {}", result));
+    }
+
+    let size = (*list_ptr).dwTotalSize as usize;
+    let bytes = std::slice::from_raw_parts(list_ptr as *const u8, size).to_vec();
+    WlanFreeMemory(list_ptr as *mut c_void);
+
+    Ok(bytes)
+}
+
+unsafe fn read_available_list(client_handle: HANDLE, guid: &GUID) -> Result<Vec<u8>, String> {
+    let mut list_ptr: *mut WLAN_AVAILABLE_NETWORK_LIST = null_mut();
+
+    let result = WlanGetAvailableNetworkList(client_handle, guid, 0, Some(null()), &mut list_ptr);
+    if result != ERROR_SUCCESS.0 {
+        return Err(format!("WlanGetAvailableNetworkList failed: error {}", result));
+    }
+
+    // Unlike the BSS list this one carries no total size.
+    let count = (*list_ptr).dwNumberOfItems as usize;
+    let size = 8 + count * std::mem::size_of::<WLAN_AVAILABLE_NETWORK>();
+    let bytes = std::slice::from_raw_parts(list_ptr as *const u8, size).to_vec();
+    WlanFreeMemory(list_ptr as *mut c_void);
+
+    Ok(bytes)
+}
@@ -0,0 +1,455 @@
+//! Conversion of the buffers returned by `WlanGetNetworkBssList` and
+//! `WlanGetAvailableNetworkList` into `WifiNetwork`s.
+//!
+//! The buffers are read by offset rather than through the `windows` crate
+//! types so the conversion builds and is tested on every platform.
+
+use crate::ie;
+use crate::structures::{AuthSuite, Cipher, CipherSuites, NetworkType, RadioType, Signal, WifiNetwork};
+use crate::wifi_functions::{
+    band_from_frequency, channel_from_frequency, iw_suites_to_auth_encryption,
+};
+
+// WLAN_BSS_LIST: dwTotalSize, dwNumberOfItems, then WLAN_BSS_ENTRY[].
+const BSS_LIST_HEADER: usize = 8;
+const BSS_ENTRY_SIZE: usize = 360;
+
+// Offsets inside WLAN_BSS_ENTRY.
+const BSS_SSID_LEN: usize = 0;
+const BSS_SSID: usize = 4;
+const BSS_BSSID: usize = 40;
+const BSS_TYPE: usize = 48;
+const BSS_PHY_TYPE: usize = 52;
+const BSS_RSSI: usize = 56;
+const BSS_LINK_QUALITY: usize = 60;
+const BSS_BEACON_PERIOD: usize = 66;
+const BSS_CAPABILITY: usize = 88;
+const BSS_CENTER_FREQUENCY: usize = 92;
+const BSS_RATE_COUNT: usize = 96;
+const BSS_RATES: usize = 100;
+const BSS_IE_OFFSET: usize = 352;
+const BSS_IE_SIZE: usize = 356;
+
+// WLAN_AVAILABLE_NETWORK_LIST: dwNumberOfItems, dwIndex, then
+// WLAN_AVAILABLE_NETWORK[].
+const AVAILABLE_LIST_HEADER: usize = 8;
+const AVAILABLE_ENTRY_SIZE: usize = 628;
+
+// Offsets inside WLAN_AVAILABLE_NETWORK.
+const AVAILABLE_SSID_LEN: usize = 512;
+const AVAILABLE_SSID: usize = 516;
+const AVAILABLE_BSS_TYPE: usize = 548;
+const AVAILABLE_AUTH: usize = 612;
+const AVAILABLE_CIPHER: usize = 616;
+
+const DOT11_SSID_MAX: usize = 32;
+const MAX_RATES: usize = 126;
+
+/// Security the available-network list reports for one SSID.
+#[derive(Debug, Clone, PartialEq)]
+struct AvailableNetwork {
+    ssid: Vec<u8>,
+    bss_type: u32,
+    authentication: AuthSuite,
+    encryption: Cipher,
+}
+
+/// Build one `WifiNetwork` per BSS entry, taking authentication and cipher
+/// from the matching SSID in the available-network list.
+pub fn networks_from_wlan_lists(
+    bss_list: &[u8],
+    available_list: &[u8],
+) -> Result<Vec<WifiNetwork>, String> {
+    let available = read_available_networks(available_list)?;
+    let count = read_u32(bss_list, 4).ok_or("BSS list is truncated")? as usize;
+
+    let mut networks = Vec::new();
+    for i in 0..count {
+        let start = BSS_LIST_HEADER + i * BSS_ENTRY_SIZE;
+        let entry = bss_list
+            .get(start..start + BSS_ENTRY_SIZE)
+            .ok_or_else(|| format!("BSS entry {} lies outside the buffer", i))?;
+
+        let ssid = read_ssid(entry, BSS_SSID_LEN, BSS_SSID)?;
+        if ssid.is_empty() {
+            continue;
+        }
+
+        let bss_type = read_u32(entry, BSS_TYPE).unwrap_or_default();
+        let security = available
+            .iter()
+            .find(|a| a.ssid == ssid && a.bss_type == bss_type);
+
+        networks.push(convert_entry(bss_list, start, entry, &ssid, security)?);
+    }
+
+    Ok(networks)
+}
+
+fn convert_entry(
+    buffer: &[u8],
+    start: usize,
+    entry: &[u8],
+    ssid: &[u8],
+    security: Option<&AvailableNetwork>,
+) -> Result<WifiNetwork, String> {
+    let bssid = entry[BSS_BSSID..BSS_BSSID + 6]
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":");
+
+    // ulIeOffset is relative to the start of the entry; the IEs themselves
+    // are stored after the entry array.
+    let ie_offset = read_u32(entry, BSS_IE_OFFSET).unwrap_or_default() as usize;
+    let ie_size = read_u32(entry, BSS_IE_SIZE).unwrap_or_default() as usize;
+    let information_elements = if ie_size == 0 {
+        Vec::new()
+    } else {
+        buffer
+            .get(start + ie_offset..start + ie_offset + ie_size)
+            .ok_or_else(|| format!("Information elements of {} lie outside the buffer", bssid))?
+            .to_vec()
+    };
+
+    let (basic_rates, other_rates) = read_rates(entry);
+    let frequency_mhz = read_u32(entry, BSS_CENTER_FREQUENCY).map(|khz| khz / 1000);
+    let link_quality = read_u32(entry, BSS_LINK_QUALITY)
+        .unwrap_or_default()
+        .min(100) as u8;
+
+    let ies = ie::parse(&information_elements);
+    let capability = read_u16(entry, BSS_CAPABILITY);
+
+    // SSIDs missing from the available-network list are judged from their
+    // own RSN/WPA elements.
+    let (authentication, encryption) = match security {
+        Some(s) => (s.authentication.clone(), s.encryption.clone()),
+        None => {
+            let privacy = capability.map(|c| c & 0x0010 != 0).unwrap_or(false);
+            let rsn = ies.rsn.as_ref().map(CipherSuites::from);
+            let wpa = ies.wpa.as_ref().map(CipherSuites::from);
+            iw_suites_to_auth_encryption(rsn.as_ref(), wpa.as_ref(), privacy)
+        }
+    };
+
+    let mut network = WifiNetwork {
+        ssid: String::from_utf8_lossy(ssid).to_string(),
+        authentication,
+        encryption,
+        bssid,
+        signal: read_u32(entry, BSS_RSSI).map(|rssi| Signal::new(link_quality, rssi as i32)),
+        risk: String::new(),
+        is_evil_twin: false,
+        frequency_mhz,
+        channel: frequency_mhz.and_then(channel_from_frequency),
+        band: frequency_mhz.and_then(band_from_frequency),
+        beacon_interval: read_u16(entry, BSS_BEACON_PERIOD),
+        capability,
+        radio_type: phy_type_to_radio_type(read_u32(entry, BSS_PHY_TYPE).unwrap_or_default()),
+        network_type: bss_type_to_network_type(read_u32(entry, BSS_TYPE).unwrap_or_default()),
+        basic_rates,
+        other_rates,
+        information_elements,
+        ..Default::default()
+    };
+    network.fill_from_ies(&ies);
+
+    Ok(network)
+}
+
+fn read_available_networks(buffer: &[u8]) -> Result<Vec<AvailableNetwork>, String> {
+    let count = read_u32(buffer, 0).ok_or("Available network list is truncated")? as usize;
+
+    (0..count)
+        .map(|i| {
+            let start = AVAILABLE_LIST_HEADER + i * AVAILABLE_ENTRY_SIZE;
+            let entry = buffer
+                .get(start..start + AVAILABLE_ENTRY_SIZE)
+                .ok_or_else(|| format!("Available network {} lies outside the buffer", i))?;
+
+            Ok(AvailableNetwork {
+                ssid: read_ssid(entry, AVAILABLE_SSID_LEN, AVAILABLE_SSID)?,
+                bss_type: read_u32(entry, AVAILABLE_BSS_TYPE).unwrap_or_default(),
+                authentication: auth_algorithm(read_u32(entry, AVAILABLE_AUTH).unwrap_or_default()),
+                encryption: cipher_algorithm(read_u32(entry, AVAILABLE_CIPHER).unwrap_or_default()),
+            })
+        })
+        .collect()
+}
+
+fn read_ssid(entry: &[u8], len_offset: usize, ssid_offset: usize) -> Result<Vec<u8>, String> {
+    let len = read_u32(entry, len_offset).unwrap_or_default() as usize;
+    if len > DOT11_SSID_MAX {
+        return Err(format!(
+            "SSID length {} exceeds {} bytes",
+            len, DOT11_SSID_MAX
+        ));
+    }
+    Ok(entry[ssid_offset..ssid_offset + len].to_vec())
+}
+
+/// Rates are in 500 kbps units; the high bit marks a basic rate.
+fn read_rates(entry: &[u8]) -> (Vec<f32>, Vec<f32>) {
+    let count = (read_u32(entry, BSS_RATE_COUNT).unwrap_or_default() as usize / 2).min(MAX_RATES);
+
+    let mut basic = Vec::new();
+    let mut other = Vec::new();
+    for i in 0..count {
+        let Some(rate) = read_u16(entry, BSS_RATES + i * 2) else {
+            break;
+        };
+        let mbps = (rate & 0x7fff) as f32 / 2.0;
+        if rate & 0x8000 != 0 {
+            basic.push(mbps);
+        } else {
+            other.push(mbps);
+        }
+    }
+
+    (basic, other)
+}
+
+fn read_u16(buffer: &[u8], offset: usize) -> Option<u16> {
+    let bytes = buffer.get(offset..offset + 2)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+fn read_u32(buffer: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buffer.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// DOT11_PHY_TYPE.
+fn phy_type_to_radio_type(phy_type: u32) -> Option<RadioType> {
+    match phy_type {
+        4 => Some(RadioType::Dot11a),
+        5 => Some(RadioType::Dot11b),
+        6 => Some(RadioType::Dot11g),
+        7 => Some(RadioType::Dot11n),
+        8 => Some(RadioType::Dot11ac),
+        10 => Some(RadioType::Dot11ax),
+        11 => Some(RadioType::Dot11be),
+        _ => None,
+    }
+}
+
+/// DOT11_BSS_TYPE.
+fn bss_type_to_network_type(bss_type: u32) -> Option<NetworkType> {
+    match bss_type {
+        1 => Some(NetworkType::Infrastructure),
+        2 => Some(NetworkType::AdHoc),
+        _ => None,
+    }
+}
+
+/// DOT11_AUTH_ALGORITHM, named the way netsh prints it.
+fn auth_algorithm(algorithm: u32) -> AuthSuite {
+    match algorithm {
+        1 => AuthSuite::Open,
+        2 => AuthSuite::Shared,
+        3 => AuthSuite::WpaEnterprise,
+        4 => AuthSuite::WpaPersonal,
+        6 => AuthSuite::Wpa2Enterprise,
+        7 => AuthSuite::Wpa2Personal,
+        8 | 11 => AuthSuite::Wpa3Enterprise,
+        9 => AuthSuite::Wpa3Personal,
+        10 => AuthSuite::Owe,
+        _ => AuthSuite::default(),
+    }
+}
+
+/// DOT11_CIPHER_ALGORITHM.
+fn cipher_algorithm(algorithm: u32) -> Cipher {
+    match algorithm {
+        0x00 => Cipher::None,
+        0x01 | 0x05 | 0x101 => Cipher::Wep,
+        0x02 => Cipher::Tkip,
+        0x04 | 0x0a => Cipher::Ccmp,
+        0x08 | 0x09 => Cipher::Gcmp,
+        _ => Cipher::default(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Bss<'a> {
+        ssid: &'a str,
+        bssid: [u8; 6],
+        phy_type: u32,
+        rssi: i32,
+        link_quality: u32,
+        frequency_khz: u32,
+        rates: &'a [u16],
+        ies: &'a [u8],
+    }
+
+    fn put_u16(buf: &mut [u8], offset: usize, value: u16) {
+        buf[offset..offset + 2].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+        buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn bss_list(entries: &[Bss]) -> Vec<u8> {
+        let ies_start = BSS_LIST_HEADER + entries.len() * BSS_ENTRY_SIZE;
+        let mut buf = vec![0u8; ies_start];
+        put_u32(&mut buf, 4, entries.len() as u32);
+
+        for (i, bss) in entries.iter().enumerate() {
+            let start = BSS_LIST_HEADER + i * BSS_ENTRY_SIZE;
+            let ie_position = buf.len();
+            buf.extend_from_slice(bss.ies);
+
+            let entry = &mut buf[start..start + BSS_ENTRY_SIZE];
+            put_u32(entry, BSS_SSID_LEN, bss.ssid.len() as u32);
+            entry[BSS_SSID..BSS_SSID + bss.ssid.len()].copy_from_slice(bss.ssid.as_bytes());
+            entry[BSS_BSSID..BSS_BSSID + 6].copy_from_slice(&bss.bssid);
+            put_u32(entry, BSS_TYPE, 1);
+            put_u32(entry, BSS_PHY_TYPE, bss.phy_type);
+            put_u32(entry, BSS_RSSI, bss.rssi as u32);
+            put_u32(entry, BSS_LINK_QUALITY, bss.link_quality);
+            put_u16(entry, BSS_BEACON_PERIOD, 100);
+            put_u16(entry, BSS_CAPABILITY, 0x0411);
+            put_u32(entry, BSS_CENTER_FREQUENCY, bss.frequency_khz);
+            put_u32(entry, BSS_RATE_COUNT, (bss.rates.len() * 2) as u32);
+            for (j, rate) in bss.rates.iter().enumerate() {
+                put_u16(entry, BSS_RATES + j * 2, *rate);
+            }
+            put_u32(entry, BSS_IE_OFFSET, (ie_position - start) as u32);
+            put_u32(entry, BSS_IE_SIZE, bss.ies.len() as u32);
+        }
+
+        let total = buf.len() as u32;
+        put_u32(&mut buf, 0, total);
+        buf
+    }
+
+    fn available_list(entries: &[(&str, u32, u32)]) -> Vec<u8> {
+        let mut buf = vec![0u8; AVAILABLE_LIST_HEADER + entries.len() * AVAILABLE_ENTRY_SIZE];
+        put_u32(&mut buf, 0, entries.len() as u32);
+
+        for (i, (ssid, auth, cipher)) in entries.iter().enumerate() {
+            let start = AVAILABLE_LIST_HEADER + i * AVAILABLE_ENTRY_SIZE;
+            let entry = &mut buf[start..start + AVAILABLE_ENTRY_SIZE];
+            put_u32(entry, AVAILABLE_SSID_LEN, ssid.len() as u32);
+            entry[AVAILABLE_SSID..AVAILABLE_SSID + ssid.len()].copy_from_slice(ssid.as_bytes());
+            put_u32(entry, AVAILABLE_BSS_TYPE, 1);
+            put_u32(entry, AVAILABLE_AUTH, *auth);
+            put_u32(entry, AVAILABLE_CIPHER, *cipher);
+        }
+
+        buf
+    }
+
+    fn home() -> Bss<'static> {
+        Bss {
+            ssid: "HomeNet",
+            bssid: [0xf4, 0x92, 0xbf, 0x12, 0x34, 0x56],
+            phy_type: 7,
+            rssi: -58,
+            link_quality: 84,
+            frequency_khz: 2_437_000,
+            rates: &[0x8002, 0x8004, 0x800b, 0x8016, 0x000c, 0x006c],
+            ies: &[0x00, 0x07, b'H', b'o', b'm', b'e', b'N', b'e', b't'],
+        }
+    }
+
+    #[test]
+    fn test_converts_bss_entry() {
+        let networks =
+            networks_from_wlan_lists(&bss_list(&[home()]), &available_list(&[("HomeNet", 7, 4)]))
+                .unwrap();
+        assert_eq!(networks.len(), 1);
+
+        let net = &networks[0];
+        assert_eq!(net.ssid, "HomeNet");
+        assert_eq!(net.bssid, "f4:92:bf:12:34:56");
+        assert_eq!(net.authentication, "WPA2-Personal");
+        assert_eq!(net.encryption, "CCMP");
+        assert_eq!(net.signal, Some(Signal::new(84, -58)));
+        assert_eq!(net.frequency_mhz, Some(2437));
+        assert_eq!(net.channel, Some(6));
+        assert_eq!(net.radio_type, Some(RadioType::Dot11n));
+        assert_eq!(net.network_type, Some(NetworkType::Infrastructure));
+        assert_eq!(net.beacon_interval, Some(100));
+        assert_eq!(net.capability, Some(0x0411));
+        assert_eq!(net.basic_rates, vec![1.0, 2.0, 5.5, 11.0]);
+        assert_eq!(net.other_rates, vec![6.0, 54.0]);
+        assert_eq!(net.information_elements, home().ies);
+    }
+
+    #[test]
+    fn test_each_entry_gets_its_own_ies() {
+        let office = Bss {
+            ssid: "Office",
+            bssid: [0x00, 0x1a, 0x2b, 0x3c, 0x4d, 0x5e],
+            phy_type: 8,
+            rssi: -71,
+            link_quality: 58,
+            frequency_khz: 5_220_000,
+            rates: &[],
+            ies: &[0x00, 0x06, b'O', b'f', b'f', b'i', b'c', b'e', 0xdd, 0x00],
+        };
+        let available = available_list(&[("HomeNet", 7, 4), ("Office", 6, 4)]);
+        let networks = networks_from_wlan_lists(&bss_list(&[home(), office]), &available).unwrap();
+
+        assert_eq!(networks[1].authentication, "WPA2-Enterprise");
+        assert_eq!(networks[1].channel, Some(44));
+        assert_eq!(networks[1].radio_type, Some(RadioType::Dot11ac));
+        assert_eq!(networks[1].information_elements.len(), 10);
+        assert_eq!(networks[0].information_elements.len(), 9);
+    }
+
+    #[test]
+    fn test_hidden_ssid_is_skipped() {
+        let hidden = Bss { ssid: "", ..home() };
+        let networks =
+            networks_from_wlan_lists(&bss_list(&[hidden, home()]), &available_list(&[])).unwrap();
+
+        assert_eq!(networks.len(), 1);
+        assert_eq!(networks[0].ssid, "HomeNet");
+    }
+
+    #[test]
+    fn test_security_falls_back_to_ies() {
+        let mut ies = vec![0x00, 0x04, b'L', b'a', b'b', b'3'];
+        // RSN: CCMP, SAE, MFP required + capable
+        ies.extend([48, 20, 1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 4]);
+        ies.extend([1, 0, 0x00, 0x0f, 0xac, 8, 0xc0, 0]);
+        ies.extend([45, 2, 0, 0, 255, 2, 35, 0]);
+        let lab = Bss {
+            ssid: "Lab3",
+            ies: &ies,
+            ..home()
+        };
+
+        let networks = networks_from_wlan_lists(&bss_list(&[lab]), &available_list(&[])).unwrap();
+        let net = &networks[0];
+
+        assert_eq!(net.authentication, "WPA3-Personal");
+        assert_eq!(net.encryption, "CCMP");
+        assert_eq!(net.rsn.as_ref().unwrap().akm_suites, vec!["SAE"]);
+        assert_eq!(net.pmf_required, Some(true));
+        assert_eq!(net.he_capabilities, Some(true));
+        assert_eq!(net.vht_capabilities, Some(false));
+    }
+
+    #[test]
+    fn test_truncated_buffers_are_rejected() {
+        let full = bss_list(&[home()]);
+        assert!(networks_from_wlan_lists(&full[..200], &available_list(&[])).is_err());
+        assert!(networks_from_wlan_lists(&full, &[1, 0]).is_err());
+
+        let mut bad_ies = full.clone();
+        put_u32(&mut bad_ies[BSS_LIST_HEADER..], BSS_IE_SIZE, 4096);
+        assert!(networks_from_wlan_lists(&bad_ies, &available_list(&[])).is_err());
+
+        let mut bad_ssid = full;
+        put_u32(&mut bad_ssid[BSS_LIST_HEADER..], BSS_SSID_LEN, 40);
+        assert!(networks_from_wlan_lists(&bad_ssid, &available_list(&[])).is_err());
+    }
+}
@@ -0,0 +1,199 @@
+use super::{BaselineStore, SecurityProfile};
+use crate::structures::{RiskFactorKind, RiskGrade, WifiNetwork};
+use crate::wifi_functions::RiskPolicy;
+
+/// How far, in dB, a known access point may be above the strongest level it
+/// was learned at before the difference counts.
+const SIGNAL_MARGIN_DB: i32 = 15;
+
+impl BaselineStore {
+    /// Compare every network advertising a trusted SSID with its baseline.
+    ///
+    /// Critical: security differs from what was learned, or an unknown BSSID
+    ///           with another finding
+    /// High:     an unknown BSSID
+    /// Medium:   a known BSSID much stronger than it ever was
+    pub fn check(&self, networks: &mut [WifiNetwork], policy: &RiskPolicy) {
+        for net in networks.iter_mut() {
+            let Some(baseline) = self.ssids.get(&net.ssid) else {
+                continue;
+            };
+
+            let mut reasons = Vec::new();
+            let known = baseline.access_points.get(&net.bssid.to_ascii_lowercase());
+            if known.is_none() {
+                reasons.push(format!("unknown access point {}", net.bssid));
+            }
+
+            let security = SecurityProfile::of(net);
+            let security_differs = !baseline.security.contains(&security);
+            if security_differs {
+                let learned: Vec<String> = baseline
+                    .security
+                    .iter()
+                    .map(|s| format!("{}/{}", s.authentication, s.encryption))
+                    .collect();
+                reasons.push(format!(
+                    "{}/{} instead of {}",
+                    security.authentication,
+                    security.encryption,
+                    learned.join(" or ")
+                ));
+            }
+
+            let mut other_findings = 0;
+            if let Some(vendor) = &net.vendor {
+                if !baseline.vendors.is_empty() && !baseline.vendors.contains(vendor) {
+                    other_findings += 1;
+                    reasons.push(format!("made by {}", vendor));
+                }
+            }
+            if let Some(channel) = net.channel {
+                if !baseline.channels.is_empty() && !baseline.channels.contains(&channel) {
+                    other_findings += 1;
+                    reasons.push(format!("on channel {}", channel));
+                }
+            }
+            let mut too_strong = false;
+            if let (Some(range), Some(signal)) = (known.and_then(|ap| ap.signal), net.signal) {
+                if signal.dbm > range.max_dbm + SIGNAL_MARGIN_DB {
+                    too_strong = true;
+                    reasons.push(format!(
+                        "signal {} dBm while it was learned at {} to {} dBm",
+                        signal.dbm, range.min_dbm, range.max_dbm
+                    ));
+                }
+            }
+
+            let grade = if security_differs || (known.is_none() && other_findings > 0) {
+                RiskGrade::Critical
+            } else if known.is_none() {
+                RiskGrade::High
+            } else if too_strong {
+                RiskGrade::Medium
+            } else {
+                continue;
+            };
+
+            if known.is_none() || security_differs {
+                net.is_evil_twin = true;
+            }
+            net.escalate_risk(
+                grade,
+                policy.score_floor(grade),
+                RiskFactorKind::Baseline,
+                format!(
+                    "Does not match the trusted baseline for \"{}\": {}",
+                    net.ssid,
+                    reasons.join(", ")
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests::ap;
+    use super::BaselineStore;
+    use crate::structures::{AuthSuite, Cipher, RiskFactorKind, WifiNetwork};
+    use crate::wifi_functions::RiskPolicy;
+    use chrono::Utc;
+
+    fn office() -> BaselineStore {
+        let mut store = BaselineStore::default();
+        store
+            .learn(
+                "Office",
+                &[
+                    ap("Office", "f4:92:bf:00:00:01", 1, -55),
+                    ap("Office", "f4:92:bf:00:00:02", 36, -65),
+                ],
+                Utc::now(),
+            )
+            .unwrap();
+        store
+    }
+
+    fn checked(store: &BaselineStore, mut networks: Vec<WifiNetwork>) -> Vec<WifiNetwork> {
+        store.check(&mut networks, &RiskPolicy::default());
+        networks
+    }
+
+    #[test]
+    fn test_known_access_points_pass() {
+        let networks = checked(
+            &office(),
+            vec![
+                ap("Office", "F4:92:BF:00:00:01", 1, -60),
+                ap("Office", "f4:92:bf:00:00:02", 36, -52),
+                ap("Elsewhere", "a0:40:a0:00:00:01", 11, -30),
+            ],
+        );
+
+        assert!(networks
+            .iter()
+            .all(|n| !n.is_evil_twin && n.risk.is_empty()));
+    }
+
+    #[test]
+    fn test_impostor_caught_while_real_ap_out_of_range() {
+        // Only the rogue is in the scan, with the same security as the real
+        // APs, so there is nothing to compare it with but the baseline.
+        let networks = checked(&office(), vec![ap("Office", "f4:92:bf:00:00:99", 1, -40)]);
+
+        assert!(networks[0].is_evil_twin);
+        assert_eq!(networks[0].risk, "H");
+    }
+
+    #[test]
+    fn test_impostor_from_other_vendor_is_critical() {
+        let networks = checked(&office(), vec![ap("Office", "a0:40:a0:00:00:01", 11, -40)]);
+
+        assert!(networks[0].is_evil_twin);
+        assert_eq!(networks[0].risk, "C");
+    }
+
+    #[test]
+    fn test_security_change_is_critical() {
+        let mut spoofed = ap("Office", "f4:92:bf:00:00:01", 1, -55);
+        spoofed.authentication = AuthSuite::Open;
+        spoofed.encryption = Cipher::None;
+
+        let networks = checked(&office(), vec![spoofed]);
+
+        assert!(networks[0].is_evil_twin);
+        assert_eq!(networks[0].risk, "C");
+    }
+
+    #[test]
+    fn test_known_bssid_far_too_strong() {
+        let mut net = ap("Office", "f4:92:bf:00:00:02", 36, -35);
+        let policy = RiskPolicy::default();
+        let assessment = crate::wifi_functions::calculate_risk(
+            &policy,
+            &net.authentication,
+            &net.encryption,
+            net.signal,
+            &net.ssid,
+        );
+        net.set_risk(assessment);
+
+        let networks = checked(&office(), vec![net]);
+
+        assert!(!networks[0].is_evil_twin);
+        assert_eq!(networks[0].risk, "M");
+        let factor = networks[0]
+            .risk_assessment
+            .as_ref()
+            .unwrap()
+            .factors
+            .last()
+            .unwrap();
+        assert_eq!(factor.kind, RiskFactorKind::Baseline);
+        assert_eq!(
+            factor.reason,
+            "Does not match the trusted baseline for \"Office\": signal -35 dBm while it was learned at -65 to -65 dBm"
+        );
+    }
+}
@@ -0,0 +1,462 @@
+//! Trusted per-SSID baseline: what the access points of an office or home
+//! SSID are known to look like.
+//!
+//! A baseline is only ever extended by an explicit `learn_baseline` while the
+//! genuine access points are in range; scans never learn on their own, so a
+//! rogue AP cannot teach itself in. Every scan is then compared against it
+//! (see `check`), which catches an impostor even when the real AP is out of
+//! range and there is nothing in the scan to compare it with.
+
+mod check;
+
+use crate::structures::{AuthSuite, Cipher, WifiNetwork};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+pub const BASELINE_FILE: &str = "trusted_baseline.json";
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct SecurityProfile {
+    pub authentication: AuthSuite,
+    pub encryption: Cipher,
+}
+
+impl SecurityProfile {
+    fn of(net: &WifiNetwork) -> Self {
+        Self {
+            authentication: net.authentication.clone(),
+            encryption: net.encryption.clone(),
+        }
+    }
+}
+
+/// Signal levels one access point was learned at.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub struct SignalRange {
+    pub min_dbm: i32,
+    pub max_dbm: i32,
+    pub mean_dbm: f64,
+    pub samples: u32,
+}
+
+impl SignalRange {
+    fn new(dbm: i32) -> Self {
+        Self {
+            min_dbm: dbm,
+            max_dbm: dbm,
+            mean_dbm: f64::from(dbm),
+            samples: 1,
+        }
+    }
+
+    fn add(&mut self, dbm: i32) {
+        self.min_dbm = self.min_dbm.min(dbm);
+        self.max_dbm = self.max_dbm.max(dbm);
+        self.samples += 1;
+        self.mean_dbm += (f64::from(dbm) - self.mean_dbm) / f64::from(self.samples);
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct KnownAccessPoint {
+    pub signal: Option<SignalRange>,
+    pub last_seen: DateTime<Utc>,
+}
+
+/// Known good fingerprint of one trusted SSID.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct SsidBaseline {
+    pub ssid: String,
+    /// Keyed by lowercase BSSID.
+    pub access_points: BTreeMap<String, KnownAccessPoint>,
+    pub vendors: BTreeSet<String>,
+    pub channels: BTreeSet<u32>,
+    pub security: BTreeSet<SecurityProfile>,
+    pub learned_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Number of scans learned from.
+    pub scans: u32,
+}
+
+impl SsidBaseline {
+    fn new(ssid: &str, now: DateTime<Utc>) -> Self {
+        Self {
+            ssid: ssid.to_string(),
+            access_points: BTreeMap::new(),
+            vendors: BTreeSet::new(),
+            channels: BTreeSet::new(),
+            security: BTreeSet::new(),
+            learned_at: now,
+            updated_at: now,
+            scans: 0,
+        }
+    }
+
+    fn record(&mut self, net: &WifiNetwork, now: DateTime<Utc>) {
+        let dbm = net.signal.map(|s| s.dbm);
+        self.access_points
+            .entry(net.bssid.to_ascii_lowercase())
+            .and_modify(|ap| {
+                match (&mut ap.signal, dbm) {
+                    (Some(range), Some(dbm)) => range.add(dbm),
+                    (range @ None, Some(dbm)) => *range = Some(SignalRange::new(dbm)),
+                    (_, None) => {}
+                }
+                ap.last_seen = now;
+            })
+            .or_insert_with(|| KnownAccessPoint {
+                signal: dbm.map(SignalRange::new),
+                last_seen: now,
+            });
+        if let Some(vendor) = &net.vendor {
+            self.vendors.insert(vendor.clone());
+        }
+        if let Some(channel) = net.channel {
+            self.channels.insert(channel);
+        }
+        self.security.insert(SecurityProfile::of(net));
+    }
+}
+
+/// All trusted baselines, saved as JSON in the app data dir.
+#[derive(Debug, Default)]
+pub struct BaselineStore {
+    path: Option<PathBuf>,
+    ssids: BTreeMap<String, SsidBaseline>,
+    /// Why the saved baselines could not be loaded.
+    problem: Option<String>,
+    /// The unreadable file is still in place; saving would overwrite it.
+    read_only: bool,
+}
+
+/// What `get_baseline` shows: the trusted SSIDs, and why there may be fewer
+/// of them than the user learned.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BaselineReview {
+    pub baselines: Vec<SsidBaseline>,
+    pub problem: Option<String>,
+}
+
+pub type SharedBaseline = Arc<Mutex<BaselineStore>>;
+
+impl BaselineStore {
+    /// Open the store in `dir`; no file means nothing is trusted yet.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(BASELINE_FILE);
+        let mut store = Self {
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+        if !path.exists() {
+            return Ok(store);
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        let baselines: Vec<SsidBaseline> = serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid trusted baseline {}: {}", path.display(), e))?;
+        store.ssids = baselines.into_iter().map(|b| (b.ssid.clone(), b)).collect();
+        Ok(store)
+    }
+
+    /// An empty store in place of the unreadable one in `dir`, which is
+    /// renamed aside first so the next save cannot overwrite it.
+    fn recover(dir: &Path, error: String, now: DateTime<Utc>) -> Self {
+        let path = dir.join(BASELINE_FILE);
+        let aside = dir.join(format!(
+            "{}.corrupt-{}",
+            BASELINE_FILE,
+            now.format("%Y%m%dT%H%M%SZ")
+        ));
+        let (problem, read_only) = match std::fs::rename(&path, &aside) {
+            Ok(()) => (
+                format!(
+                    "{}; it was moved to {} and its SSIDs are not trusted until learned again",
+                    error,
+                    aside.display()
+                ),
+                false,
+            ),
+            Err(e) => (
+                format!(
+                    "{}; it could not be moved aside ({}), so nothing is saved until the baseline is reset",
+                    error, e
+                ),
+                true,
+            ),
+        };
+        eprintln!("{}", problem);
+        Self {
+            path: Some(path),
+            ssids: BTreeMap::new(),
+            problem: Some(problem),
+            read_only,
+        }
+    }
+
+    fn check_writable(&self) -> Result<(), String> {
+        match &self.problem {
+            Some(problem) if self.read_only => Err(problem.clone()),
+            _ => Ok(()),
+        }
+    }
+
+    fn save(&self) -> Result<(), String> {
+        self.check_writable()?;
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        }
+        let content = serde_json::to_string_pretty(&self.baselines())
+            .map_err(|e| format!("Failed to serialize trusted baseline: {}", e))?;
+        std::fs::write(path, content)
+            .map_err(|e| format!("Failed to write {}: {}", path.display(), e))
+    }
+
+    pub fn baselines(&self) -> Vec<SsidBaseline> {
+        self.ssids.values().cloned().collect()
+    }
+
+    pub fn review(&self) -> BaselineReview {
+        BaselineReview {
+            baselines: self.baselines(),
+            problem: self.problem.clone(),
+        }
+    }
+
+    pub fn get(&self, ssid: &str) -> Option<&SsidBaseline> {
+        self.ssids.get(ssid)
+    }
+
+    /// Names of the trusted SSIDs.
+    pub fn ssids(&self) -> impl Iterator<Item = &str> {
+        self.ssids.keys().map(String::as_str)
+    }
+
+    /// Add every access point advertising `ssid` in `networks` to its
+    /// baseline, creating the baseline on first use, and save.
+    pub fn learn(
+        &mut self,
+        ssid: &str,
+        networks: &[WifiNetwork],
+        now: DateTime<Utc>,
+    ) -> Result<SsidBaseline, String> {
+        if ssid.trim().is_empty() {
+            return Err("Hidden networks cannot be trusted by name".to_string());
+        }
+        self.check_writable()?;
+        let seen: Vec<&WifiNetwork> = networks.iter().filter(|n| n.ssid == ssid).collect();
+        if seen.is_empty() {
+            return Err(format!(
+                "\"{}\" is not in range; learn it while one of its access points is visible",
+                ssid
+            ));
+        }
+
+        let baseline = self
+            .ssids
+            .entry(ssid.to_string())
+            .or_insert_with(|| SsidBaseline::new(ssid, now));
+        for net in seen {
+            baseline.record(net, now);
+        }
+        baseline.scans += 1;
+        baseline.updated_at = now;
+        let learned = baseline.clone();
+
+        self.save()?;
+        Ok(learned)
+    }
+
+    /// Forget `ssid`, or every baseline when `None`. Returns how many were
+    /// removed. Resetting everything also replaces a file that could not be
+    /// loaded.
+    pub fn reset(&mut self, ssid: Option<&str>) -> Result<usize, String> {
+        let removed = match ssid {
+            Some(ssid) => {
+                self.check_writable()?;
+                if self.ssids.remove(ssid).is_none() {
+                    return Err(format!("No trusted baseline for \"{}\"", ssid));
+                }
+                1
+            }
+            None => {
+                self.problem = None;
+                self.read_only = false;
+                std::mem::take(&mut self.ssids).len()
+            }
+        };
+        self.save()?;
+        Ok(removed)
+    }
+}
+
+/// Open the store in `dir`. An unreadable file is moved aside and the store
+/// starts empty, with the problem shown by `get_baseline`.
+pub fn load_baseline(dir: &Path) -> SharedBaseline {
+    let store =
+        BaselineStore::load(dir).unwrap_or_else(|e| BaselineStore::recover(dir, e, Utc::now()));
+    Arc::new(Mutex::new(store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_baseline, BaselineStore, SecurityProfile, BASELINE_FILE};
+    use crate::structures::{AuthSuite, Cipher, Signal, WifiNetwork};
+    use chrono::{TimeZone, Utc};
+
+    pub(super) fn ap(ssid: &str, bssid: &str, channel: u32, dbm: i32) -> WifiNetwork {
+        let mut net = WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            authentication: AuthSuite::Wpa2Personal,
+            encryption: Cipher::Ccmp,
+            signal: Some(Signal::from_dbm(dbm)),
+            channel: Some(channel),
+            ..Default::default()
+        };
+        net.fill_from_bssid();
+        net
+    }
+
+    #[test]
+    fn test_learn_merges_scans() {
+        let mut store = BaselineStore::default();
+        let t0 = Utc.with_ymd_and_hms(2026, 1, 5, 9, 0, 0).unwrap();
+        let t1 = Utc.with_ymd_and_hms(2026, 1, 6, 9, 0, 0).unwrap();
+
+        store
+            .learn(
+                "Office",
+                &[
+                    ap("Office", "F4:92:BF:00:00:01", 1, -50),
+                    ap("Guest", "f4:92:bf:00:00:09", 1, -50),
+                ],
+                t0,
+            )
+            .unwrap();
+        let learned = store
+            .learn(
+                "Office",
+                &[
+                    ap("Office", "f4:92:bf:00:00:01", 1, -60),
+                    ap("Office", "f4:92:bf:00:00:02", 36, -70),
+                ],
+                t1,
+            )
+            .unwrap();
+
+        assert_eq!(learned.scans, 2);
+        assert_eq!(learned.learned_at, t0);
+        assert_eq!(learned.updated_at, t1);
+        assert_eq!(
+            learned.access_points.keys().collect::<Vec<_>>(),
+            ["f4:92:bf:00:00:01", "f4:92:bf:00:00:02"]
+        );
+        let range = learned.access_points["f4:92:bf:00:00:01"].signal.unwrap();
+        assert_eq!((range.min_dbm, range.max_dbm, range.samples), (-60, -50, 2));
+        assert_eq!(range.mean_dbm, -55.0);
+        assert_eq!(
+            learned.channels.iter().copied().collect::<Vec<_>>(),
+            [1, 36]
+        );
+        assert_eq!(learned.vendors.iter().collect::<Vec<_>>(), ["Ubiquiti Inc"]);
+        assert_eq!(
+            learned.security.iter().collect::<Vec<_>>(),
+            [&SecurityProfile {
+                authentication: AuthSuite::Wpa2Personal,
+                encryption: Cipher::Ccmp
+            }]
+        );
+        assert!(store.get("Guest").is_none());
+    }
+
+    #[test]
+    fn test_learn_requires_ssid_in_range() {
+        let mut store = BaselineStore::default();
+        let err = store
+            .learn(
+                "Office",
+                &[ap("Home", "00:11:22:33:44:55", 6, -40)],
+                Utc::now(),
+            )
+            .unwrap_err();
+        assert!(err.contains("not in range"));
+        assert!(store.learn("", &[], Utc::now()).is_err());
+    }
+
+    #[test]
+    fn test_persists_and_resets() {
+        let dir = std::env::temp_dir().join(format!("wids-baseline-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut store = BaselineStore::load(&dir).unwrap();
+        store
+            .learn(
+                "Office",
+                &[ap("Office", "f4:92:bf:00:00:01", 1, -50)],
+                Utc::now(),
+            )
+            .unwrap();
+        store
+            .learn(
+                "Home",
+                &[ap("Home", "a0:40:a0:00:00:01", 6, -40)],
+                Utc::now(),
+            )
+            .unwrap();
+
+        let mut reopened = BaselineStore::load(&dir).unwrap();
+        assert_eq!(reopened.baselines(), store.baselines());
+
+        assert_eq!(reopened.reset(Some("Home")).unwrap(), 1);
+        assert!(reopened.reset(Some("Home")).is_err());
+        assert_eq!(BaselineStore::load(&dir).unwrap().baselines().len(), 1);
+        assert_eq!(reopened.reset(None).unwrap(), 1);
+        assert!(BaselineStore::load(&dir).unwrap().baselines().is_empty());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_corrupt_file_is_kept() {
+        let dir = std::env::temp_dir().join(format!("wids-baseline-bad-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let corrupt = r#"[{"ssid": "Office", "access_points": "#;
+        std::fs::write(dir.join(BASELINE_FILE), corrupt).unwrap();
+
+        let shared = load_baseline(&dir);
+        let mut store = shared.lock().unwrap();
+        let problem = store.review().problem.unwrap();
+        assert!(problem.contains("Invalid trusted baseline"), "{}", problem);
+        store
+            .learn(
+                "Home",
+                &[ap("Home", "a0:40:a0:00:00:01", 6, -40)],
+                Utc::now(),
+            )
+            .unwrap();
+
+        let kept: Vec<_> = std::fs::read_dir(&dir)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .filter(|path| path.to_string_lossy().contains(".corrupt-"))
+            .collect();
+        assert_eq!(kept.len(), 1);
+        assert_eq!(std::fs::read_to_string(&kept[0]).unwrap(), corrupt);
+        let reopened = BaselineStore::load(&dir).unwrap();
+        assert_eq!(reopened.ssids().collect::<Vec<_>>(), ["Home"]);
+        // Still reported until the user starts over
+        assert!(store.review().problem.is_some());
+        store.reset(None).unwrap();
+        assert_eq!(store.review().problem, None);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
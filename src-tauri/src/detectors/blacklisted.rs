@@ -0,0 +1,78 @@
+//! Networks on the user's BSSID blacklist.
+
+use super::{no_settings_schema, parse_config, Detector, NoSettings, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+
+pub struct BlacklistedNetwork;
+
+impl Detector for BlacklistedNetwork {
+    fn id(&self) -> &'static str {
+        "blacklisted_network"
+    }
+
+    fn description(&self) -> &'static str {
+        "A network on the BSSID blacklist"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        no_settings_schema()
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        parse_config::<NoSettings>(self.id(), config).map(|_| ())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        scan.networks
+            .iter()
+            .filter(|network| scan.blacklist.contains(&network.bssid.to_lowercase()))
+            .map(|network| {
+                let bssid = network.bssid.to_lowercase();
+                DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "Critical".to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: bssid.clone(),
+                    details: format!(
+                        "Blacklisted network detected: {} (BSSID: {}), avoid connecting",
+                        network.ssid, bssid
+                    ),
+                    timestamp: scan.at,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BlacklistedNetwork;
+    use crate::detectors::tests::{net, run_scans};
+    use crate::detectors::Detector;
+
+    #[test]
+    fn test_reports_blacklisted_bssid_each_scan() {
+        let scans = vec![
+            vec![net("Home", "AA:BB:CC:00:00:01", 80)],
+            vec![
+                net("Home", "AA:BB:CC:00:00:01", 80),
+                net("Bad", "66:77:88:99:AA:BB", 60),
+            ],
+            vec![net("Bad", "66:77:88:99:AA:BB", 60)],
+        ];
+
+        let threats = run_scans(BlacklistedNetwork, &scans, &[], &["66:77:88:99:aa:bb"]);
+
+        assert_eq!(threats.iter().map(Vec::len).collect::<Vec<_>>(), [0, 1, 1]);
+        assert_eq!(threats[1][0].severity, "Critical");
+        assert_eq!(threats[1][0].network_bssid, "66:77:88:99:aa:bb");
+    }
+
+    #[test]
+    fn test_takes_no_settings() {
+        let err = BlacklistedNetwork
+            .configure(&serde_json::json!({ "strict": true }))
+            .unwrap_err();
+        assert!(err.starts_with("Invalid settings for blacklisted_network: unknown field `strict`"));
+    }
+}
@@ -0,0 +1,410 @@
+//! Forced disconnects from one access point in quick succession, the mark of
+//! a deauthentication or disassociation flood.
+//!
+//! Link events are fed through `DeauthTracker`, a state machine per BSSID:
+//! quiet, then suspect while forced disconnects pile up within
+//! `window_seconds`, then under attack once there are `min_disconnects` of
+//! them. The attack is reported when it starts and not again until a whole
+//! window passes without a forced disconnect and the BSSID is quiet again.
+
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, LinkEvent, LinkEventKind, MonitoringState};
+use chrono::{DateTime, Duration, Utc};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct DeauthAttack {
+    pub window_seconds: u64,
+    /// Forced disconnects or refused connections within the window before
+    /// it is reported.
+    pub min_disconnects: usize,
+}
+
+impl Default for DeauthAttack {
+    fn default() -> Self {
+        Self {
+            window_seconds: 30,
+            min_disconnects: 4,
+        }
+    }
+}
+
+impl DeauthAttack {
+    fn window(&self) -> Duration {
+        Duration::seconds(self.window_seconds as i64)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum LinkState {
+    /// Forced disconnects within the window, oldest first, not yet enough.
+    Suspect(Vec<DateTime<Utc>>),
+    UnderAttack {
+        since: DateTime<Utc>,
+        last: DateTime<Utc>,
+        disconnects: usize,
+    },
+}
+
+/// Per-BSSID state of `DeauthAttack`, carried in `MonitoringState`. BSSIDs
+/// without an entry are quiet.
+#[derive(Debug, Clone, Default)]
+pub struct DeauthTracker {
+    links: HashMap<String, LinkState>,
+    /// SSIDs learned from association events, by lowercase BSSID.
+    ssids: HashMap<String, String>,
+    /// The BSSID the station associated with last, which events that do
+    /// not name one are pinned on.
+    last_associated: Option<String>,
+}
+
+impl DeauthTracker {
+    /// Take in one event; returns a threat when it starts an attack.
+    pub fn observe(
+        &mut self,
+        event: &LinkEvent,
+        settings: &DeauthAttack,
+    ) -> Option<DetectedThreat> {
+        let bssid = match &event.bssid {
+            Some(bssid) => bssid.to_lowercase(),
+            None => self.attribute(event)?,
+        };
+        match event.kind {
+            LinkEventKind::Associating | LinkEventKind::Connected => {
+                if let Some(ssid) = event.ssid.as_ref().filter(|s| !s.is_empty()) {
+                    self.ssids.insert(bssid.clone(), ssid.clone());
+                }
+                self.last_associated = Some(bssid);
+                return None;
+            }
+            LinkEventKind::Disconnected | LinkEventKind::ConnectionFailed => {}
+        }
+        if event.locally_generated {
+            return None;
+        }
+
+        let window = settings.window();
+        let mut recent = match self.links.remove(&bssid) {
+            Some(LinkState::UnderAttack {
+                since,
+                last,
+                disconnects,
+            }) if event.at - last <= window => {
+                self.links.insert(
+                    bssid,
+                    LinkState::UnderAttack {
+                        since,
+                        last: last.max(event.at),
+                        disconnects: disconnects + 1,
+                    },
+                );
+                return None;
+            }
+            Some(LinkState::Suspect(recent)) => recent,
+            // Quiet, or an attack that died down before this event
+            _ => Vec::new(),
+        };
+        recent.retain(|at| event.at - *at <= window);
+        recent.push(event.at);
+
+        if recent.len() < settings.min_disconnects.max(1) {
+            self.links.insert(bssid, LinkState::Suspect(recent));
+            return None;
+        }
+        let since = recent.iter().min().copied().unwrap_or(event.at);
+        self.links.insert(
+            bssid.clone(),
+            LinkState::UnderAttack {
+                since,
+                last: event.at,
+                disconnects: recent.len(),
+            },
+        );
+
+        let ssid = event
+            .ssid
+            .clone()
+            .or_else(|| self.ssids.get(&bssid).cloned())
+            .unwrap_or_default();
+        // A refused association carries a status code, not a reason code
+        let code_kind = match event.kind {
+            LinkEventKind::ConnectionFailed => "status",
+            _ => "reason",
+        };
+        let reason = match (event.code, &event.reason) {
+            (Some(code), Some(reason)) => format!(" (last {} {}: {})", code_kind, code, reason),
+            (Some(code), None) => format!(" (last {} {})", code_kind, code),
+            (None, Some(reason)) => format!(" (last reason: {})", reason),
+            (None, None) => String::new(),
+        };
+        Some(DetectedThreat {
+            threat_type: "deauth_attack".to_string(),
+            severity: "High".to_string(),
+            network_ssid: ssid,
+            network_bssid: bssid.clone(),
+            details: format!(
+                "{} forced disconnects or refused connections from {} within {} seconds{}, possible deauthentication attack",
+                recent.len(),
+                bssid,
+                (event.at - since).num_seconds(),
+                reason
+            ),
+            timestamp: event.at,
+        })
+    }
+
+    /// The BSSID an event without one most likely came from: the last one
+    /// associated with, unless the event names another SSID.
+    fn attribute(&self, event: &LinkEvent) -> Option<String> {
+        let bssid = self.last_associated.as_ref()?;
+        match &event.ssid {
+            Some(ssid) if self.ssids.get(bssid) != Some(ssid) => None,
+            _ => Some(bssid.clone()),
+        }
+    }
+
+    /// Forget disconnects that fell out of the window by `now`, and attacks
+    /// quiet for a whole window.
+    pub fn expire(&mut self, now: DateTime<Utc>, settings: &DeauthAttack) {
+        let window = settings.window();
+        self.links.retain(|_, state| match state {
+            LinkState::Suspect(recent) => {
+                recent.retain(|at| now - *at <= window);
+                !recent.is_empty()
+            }
+            LinkState::UnderAttack { last, .. } => now - *last <= window,
+        });
+    }
+
+    /// Whether `bssid` is in an attack that has been reported.
+    #[cfg(test)]
+    fn under_attack(&self, bssid: &str) -> bool {
+        matches!(self.links.get(bssid), Some(LinkState::UnderAttack { .. }))
+    }
+}
+
+impl Detector for DeauthAttack {
+    fn id(&self) -> &'static str {
+        "deauth_attack"
+    }
+
+    fn description(&self) -> &'static str {
+        "Repeated forced disconnects from the same access point"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "window_seconds": { "type": "integer", "minimum": 1, "default": 30 },
+                "min_disconnects": { "type": "integer", "minimum": 1, "default": 4 }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let settings: Self = parse_config(self.id(), config)?;
+        if settings.window_seconds == 0 || settings.min_disconnects == 0 {
+            return Err(
+                "Invalid settings for deauth_attack: window_seconds and min_disconnects must be at least 1"
+                    .to_string(),
+            );
+        }
+        *self = settings;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let mut events: Vec<&LinkEvent> = scan.link_events.iter().collect();
+        events.sort_by_key(|e| e.at);
+        let threats = events
+            .into_iter()
+            .filter_map(|event| state.deauth.observe(event, self))
+            .collect();
+        state.deauth.expire(scan.at, self);
+        threats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DeauthAttack, DeauthTracker};
+    use crate::detectors::{Detector, Scan};
+    use crate::structures::{LinkEvent, LinkEventKind, MonitoringState};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+    use std::collections::HashSet;
+
+    const AP: &str = "aa:bb:cc:00:00:01";
+
+    fn at(seconds: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap() + Duration::seconds(seconds)
+    }
+
+    fn event(kind: LinkEventKind, bssid: &str, seconds: i64) -> LinkEvent {
+        LinkEvent {
+            at: at(seconds),
+            kind,
+            bssid: Some(bssid.to_string()),
+            ssid: None,
+            code: Some(7),
+            reason: Some("Class 3 frame received from nonassociated STA".to_string()),
+            locally_generated: false,
+        }
+    }
+
+    fn kicked(bssid: &str, seconds: i64) -> LinkEvent {
+        event(LinkEventKind::Disconnected, bssid, seconds)
+    }
+
+    #[test]
+    fn test_burst_is_reported_once() {
+        let settings = DeauthAttack::default();
+        let mut tracker = DeauthTracker::default();
+        let mut associating = event(LinkEventKind::Associating, AP, 0);
+        associating.ssid = Some("Office".to_string());
+        assert!(tracker.observe(&associating, &settings).is_none());
+
+        let reported: Vec<_> = (1..=6)
+            .filter_map(|i| tracker.observe(&kicked(AP, i * 5), &settings))
+            .collect();
+        assert_eq!(reported.len(), 1);
+        let threat = &reported[0];
+        assert_eq!(threat.threat_type, "deauth_attack");
+        assert_eq!(threat.network_ssid, "Office");
+        assert_eq!(threat.network_bssid, AP);
+        assert_eq!(threat.timestamp, at(20));
+        assert!(
+            threat.details.starts_with("4 forced disconnects or refused connections from aa:bb:cc:00:00:01 within 15 seconds (last reason 7: Class 3"),
+            "{}",
+            threat.details
+        );
+        assert!(tracker.under_attack(AP));
+    }
+
+    #[test]
+    fn test_ignores_own_and_spread_out_disconnects() {
+        let settings = DeauthAttack::default();
+        let mut tracker = DeauthTracker::default();
+        for i in 0..10 {
+            let mut own = kicked(AP, i);
+            own.locally_generated = true;
+            assert!(tracker.observe(&own, &settings).is_none());
+        }
+        // One every 20 seconds never gets 4 into a 30 second window
+        for i in 0..10 {
+            assert!(tracker
+                .observe(&kicked(AP, 100 + i * 20), &settings)
+                .is_none());
+        }
+        // Events without a BSSID cannot be pinned on an access point yet
+        let mut anonymous = kicked(AP, 400);
+        anonymous.bssid = None;
+        anonymous.ssid = Some("Office".to_string());
+        for _ in 0..5 {
+            assert!(tracker.observe(&anonymous, &settings).is_none());
+        }
+        tracker.expire(at(1000), &settings);
+        assert!(tracker.links.is_empty());
+    }
+
+    #[test]
+    fn test_events_without_bssid_go_to_last_association() {
+        let settings = DeauthAttack::default();
+        let mut tracker = DeauthTracker::default();
+        let mut associating = event(LinkEventKind::Associating, AP, 0);
+        associating.ssid = Some("Office".to_string());
+        tracker.observe(&associating, &settings);
+
+        let failed = |ssid: &str, seconds| LinkEvent {
+            bssid: None,
+            ssid: Some(ssid.to_string()),
+            code: Some(17),
+            reason: None,
+            ..event(LinkEventKind::ConnectionFailed, AP, seconds)
+        };
+        // Failures on another SSID are not this access point's
+        assert!((1..=4).all(|i| tracker.observe(&failed("Guest", i), &settings).is_none()));
+        let reported: Vec<_> = (5..=8)
+            .filter_map(|i| tracker.observe(&failed("Office", i), &settings))
+            .collect();
+        assert_eq!(reported.len(), 1);
+        assert_eq!(reported[0].network_bssid, AP);
+        assert_eq!(reported[0].network_ssid, "Office");
+        assert!(
+            reported[0].details.contains("(last status 17)"),
+            "{}",
+            reported[0].details
+        );
+    }
+
+    #[test]
+    fn test_new_burst_after_quiet_window() {
+        let settings = DeauthAttack::default();
+        let mut tracker = DeauthTracker::default();
+        let burst = |tracker: &mut DeauthTracker, start: i64| {
+            (0..4)
+                .filter_map(|i| {
+                    let kind = if i % 2 == 0 {
+                        LinkEventKind::Disconnected
+                    } else {
+                        LinkEventKind::ConnectionFailed
+                    };
+                    tracker.observe(&event(kind, AP, start + i), &settings)
+                })
+                .count()
+        };
+        assert_eq!(burst(&mut tracker, 0), 1);
+        // Still the same attack while events keep coming within the window
+        assert_eq!(burst(&mut tracker, 20), 0);
+        tracker.expire(at(40), &settings);
+        assert!(tracker.under_attack(AP));
+
+        tracker.expire(at(60), &settings);
+        assert!(!tracker.under_attack(AP));
+        assert_eq!(burst(&mut tracker, 100), 1);
+
+        assert_eq!(burst(&mut tracker, 101), 0);
+        // Died down without an `expire` in between: still a new attack
+        assert_eq!(burst(&mut tracker, 200), 1);
+
+        // Another access point is tracked on its own
+        let other: usize = (0..4)
+            .filter(|i| {
+                tracker
+                    .observe(&kicked("aa:bb:cc:00:00:02", 300 + i), &settings)
+                    .is_some()
+            })
+            .count();
+        assert_eq!(other, 1);
+    }
+
+    #[test]
+    fn test_detector_reads_scan_events() {
+        let mut detector = DeauthAttack::default();
+        detector
+            .configure(&serde_json::json!({ "min_disconnects": 2 }))
+            .unwrap();
+        assert!(detector
+            .configure(&serde_json::json!({ "window_seconds": 0 }))
+            .is_err());
+
+        let mut state = MonitoringState::default();
+        let empty = HashSet::new();
+        // Out of order, as two sources may deliver them
+        let events = [kicked(AP, 3), kicked(AP, 1)];
+        let scan = Scan {
+            networks: &[],
+            karma: &[],
+            whitelist: &empty,
+            blacklist: &empty,
+            link_events: &events,
+            at: at(5),
+        };
+        let threats = detector.observe(&scan, &mut state);
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].timestamp, at(3));
+    }
+}
@@ -0,0 +1,157 @@
+//! Many networks appearing or vanishing between two scans, as beacon flood
+//! tools cause when they start or stop.
+
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+use serde::Deserialize;
+use std::collections::HashSet;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct FloodAttack {
+    pub max_new_networks: usize,
+    pub max_vanished_networks: usize,
+}
+
+impl Default for FloodAttack {
+    fn default() -> Self {
+        Self {
+            max_new_networks: 10,
+            max_vanished_networks: 10,
+        }
+    }
+}
+
+impl Detector for FloodAttack {
+    fn id(&self) -> &'static str {
+        "flood_attack"
+    }
+
+    fn description(&self) -> &'static str {
+        "Many networks appearing or disappearing between two scans"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "max_new_networks": { "type": "integer", "minimum": 0, "default": 10 },
+                "max_vanished_networks": { "type": "integer", "minimum": 0, "default": 10 }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        *self = parse_config(self.id(), config)?;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let mut threats = Vec::new();
+
+        // The first scan has nothing to compare with
+        if state.previous_networks.is_empty() {
+            return threats;
+        }
+
+        let prev_bssids: HashSet<String> = state
+            .previous_networks
+            .iter()
+            .map(|n| n.bssid.to_lowercase())
+            .collect();
+        let curr_bssids: HashSet<String> = scan
+            .networks
+            .iter()
+            .map(|n| n.bssid.to_lowercase())
+            .collect();
+
+        let appeared = curr_bssids.difference(&prev_bssids).count();
+        if appeared > self.max_new_networks {
+            threats.push(DetectedThreat {
+                threat_type: self.id().to_string(),
+                severity: "High".to_string(),
+                network_ssid: "Multiple".to_string(),
+                network_bssid: "Multiple".to_string(),
+                details: format!(
+                    "Rapid network appearance detected: {} new networks appeared in single scan, possible flood attack",
+                    appeared
+                ),
+                timestamp: scan.at,
+            });
+        }
+
+        let disappeared = prev_bssids.difference(&curr_bssids).count();
+        if disappeared > self.max_vanished_networks {
+            threats.push(DetectedThreat {
+                threat_type: self.id().to_string(),
+                severity: "Medium".to_string(),
+                network_ssid: "Multiple".to_string(),
+                network_bssid: "Multiple".to_string(),
+                details: format!(
+                    "Rapid network disappearance detected: {} networks disappeared in single scan",
+                    disappeared
+                ),
+                timestamp: scan.at,
+            });
+        }
+
+        threats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::FloodAttack;
+    use crate::detectors::tests::{net, run_scans};
+    use crate::detectors::Detector;
+    use crate::structures::WifiNetwork;
+
+    fn crowd(count: usize) -> Vec<WifiNetwork> {
+        (0..count)
+            .map(|i| {
+                net(
+                    &format!("Net{}", i),
+                    &format!("00:11:22:33:44:{:02x}", i),
+                    50,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_appearance_then_disappearance() {
+        let lone = vec![net("Lone", "00:11:22:33:55:00", 50)];
+        let scans = vec![crowd(12), lone.clone(), crowd(12), lone];
+
+        let threats = run_scans(FloodAttack::default(), &scans, &[], &[]);
+
+        // Nothing to compare the first scan with
+        assert!(threats[0].is_empty());
+        assert_eq!(threats[1].len(), 1);
+        assert_eq!(threats[1][0].severity, "Medium");
+        assert!(threats[1][0].details.contains("12 networks disappeared"));
+        assert_eq!(threats[2].len(), 1);
+        assert_eq!(threats[2][0].severity, "High");
+        assert!(threats[2][0].details.contains("12 new networks"));
+    }
+
+    #[test]
+    fn test_steady_scans_and_thresholds() {
+        let threats = run_scans(
+            FloodAttack::default(),
+            &[crowd(12), crowd(12), crowd(12)],
+            &[],
+            &[],
+        );
+        assert!(threats.iter().all(Vec::is_empty));
+
+        let mut strict = FloodAttack::default();
+        strict
+            .configure(&serde_json::json!({ "max_new_networks": 2 }))
+            .unwrap();
+        assert_eq!(strict.max_vanished_networks, 10);
+        let threats = run_scans(strict, &[crowd(1), crowd(4)], &[], &[]);
+        assert_eq!(threats[1].len(), 1);
+    }
+}
@@ -0,0 +1,159 @@
+//! One BSSID under several SSIDs, or one SSID behind unusually many BSSIDs.
+
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct MacSpoof {
+    /// BSSIDs one SSID may have in a scan before it is reported.
+    pub max_bssids_per_ssid: usize,
+}
+
+impl Default for MacSpoof {
+    fn default() -> Self {
+        Self {
+            max_bssids_per_ssid: 3,
+        }
+    }
+}
+
+impl Detector for MacSpoof {
+    fn id(&self) -> &'static str {
+        "mac_spoof"
+    }
+
+    fn description(&self) -> &'static str {
+        "A BSSID seen with several SSIDs, or an SSID with too many BSSIDs"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "max_bssids_per_ssid": { "type": "integer", "minimum": 1, "default": 3 }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let settings: Self = parse_config(self.id(), config)?;
+        if settings.max_bssids_per_ssid == 0 {
+            return Err(
+                "Invalid settings for mac_spoof: max_bssids_per_ssid must be at least 1"
+                    .to_string(),
+            );
+        }
+        *self = settings;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let mut threats = Vec::new();
+        let mut bssid_to_ssids: BTreeMap<String, BTreeSet<&str>> = BTreeMap::new();
+        let mut ssid_to_bssids: BTreeMap<&str, BTreeSet<String>> = BTreeMap::new();
+
+        for network in scan.networks {
+            let bssid = network.bssid.to_lowercase();
+            if network.ssid.trim().is_empty() || bssid.trim().is_empty() {
+                continue;
+            }
+            bssid_to_ssids
+                .entry(bssid.clone())
+                .or_default()
+                .insert(&network.ssid);
+            ssid_to_bssids
+                .entry(&network.ssid)
+                .or_default()
+                .insert(bssid);
+        }
+
+        // Same BSSID with different SSIDs
+        for (bssid, ssids) in bssid_to_ssids {
+            if ssids.len() > 1 {
+                let ssids: Vec<&str> = ssids.into_iter().collect();
+                threats.push(DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "Critical".to_string(),
+                    network_ssid: ssids[0].to_string(),
+                    network_bssid: bssid.clone(),
+                    details: format!(
+                        "BSSID {} appears with multiple SSIDs: {}",
+                        bssid,
+                        ssids.join(", ")
+                    ),
+                    timestamp: scan.at,
+                });
+            }
+        }
+
+        // Same SSID behind too many BSSIDs
+        for (ssid, bssids) in ssid_to_bssids {
+            if bssids.len() > self.max_bssids_per_ssid {
+                threats.push(DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "High".to_string(),
+                    network_ssid: ssid.to_string(),
+                    network_bssid: bssids.first().cloned().unwrap_or_default(),
+                    details: format!(
+                        "SSID {} has {} different BSSIDs, possible MAC spoofing attack",
+                        ssid,
+                        bssids.len()
+                    ),
+                    timestamp: scan.at,
+                });
+            }
+        }
+
+        threats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::MacSpoof;
+    use crate::detectors::tests::{net, run_scans};
+    use crate::detectors::Detector;
+
+    #[test]
+    fn test_bssid_with_two_ssids() {
+        let scans = vec![
+            vec![net("Home", "00:11:22:33:44:55", 70)],
+            vec![
+                net("Home", "00:11:22:33:44:55", 70),
+                net("Free", "00:11:22:33:44:55", 70),
+            ],
+        ];
+
+        let threats = run_scans(MacSpoof::default(), &scans, &[], &[]);
+
+        assert!(threats[0].is_empty());
+        assert_eq!(threats[1].len(), 1);
+        assert_eq!(threats[1][0].severity, "Critical");
+        assert_eq!(
+            threats[1][0].details,
+            "BSSID 00:11:22:33:44:55 appears with multiple SSIDs: Free, Home"
+        );
+    }
+
+    #[test]
+    fn test_ssid_limit_is_configurable() {
+        let campus: Vec<_> = (1..=4)
+            .map(|i| net("Campus", &format!("00:11:22:33:44:0{}", i), 60))
+            .collect();
+
+        let threats = run_scans(MacSpoof::default(), std::slice::from_ref(&campus), &[], &[]);
+        assert_eq!(threats[0].len(), 1);
+        assert_eq!(threats[0][0].network_bssid, "00:11:22:33:44:01");
+        assert!(threats[0][0].details.contains("has 4 different BSSIDs"));
+
+        let mut roomy = MacSpoof::default();
+        roomy
+            .configure(&serde_json::json!({ "max_bssids_per_ssid": 8 }))
+            .unwrap();
+        assert!(run_scans(roomy, &[campus], &[], &[])[0].is_empty());
+    }
+}
@@ -0,0 +1,396 @@
+//! Threat detectors run by the continuous monitor on every scan.
+//!
+//! Each detector reports one threat type, named by its id, and can be
+//! switched on and configured through the monitor's `enabled_threat_types`
+//! setting, either as a list of ids:
+//!
+//! ```json
+//! ["evil_twin", "rf_jamming"]
+//! ```
+//!
+//! or as a map from id to that detector's settings:
+//!
+//! ```json
+//! { "evil_twin": {}, "rf_jamming": { "z_threshold": 3.5 } }
+//! ```
+//!
+//! An empty list or map runs every detector with its defaults.
+
+mod blacklisted;
+mod deauth;
+mod flood;
+mod mac_spoof;
+mod probe_anomaly;
+mod rf_jamming;
+mod scan_findings;
+mod signal_model;
+mod signal_spike;
+mod unauthorized_client;
+
+pub use deauth::DeauthTracker;
+pub use signal_model::SignalModel;
+
+use crate::structures::{DetectedThreat, LinkEvent, MonitoringState, NetworkHistory, WifiNetwork};
+use crate::wifi_functions::KarmaFinding;
+use chrono::{DateTime, Duration, Utc};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashSet};
+
+/// How long a BSSID's history is kept after it was last seen.
+pub const HISTORY_MINUTES: i64 = 5;
+/// Signal measurements kept per BSSID.
+const HISTORY_SAMPLES: usize = 10;
+
+/// One monitoring scan, after the scan pipeline has graded it.
+pub struct Scan<'a> {
+    pub networks: &'a [WifiNetwork],
+    /// KARMA radios the pipeline's sliding window holds at this scan.
+    pub karma: &'a [KarmaFinding],
+    /// Lowercase BSSIDs.
+    pub whitelist: &'a HashSet<String>,
+    pub blacklist: &'a HashSet<String>,
+    /// Link events reported since the scan before.
+    pub link_events: &'a [LinkEvent],
+    pub at: DateTime<Utc>,
+}
+
+pub trait Detector: Send + Sync {
+    /// Stable id, also the `threat_type` of everything it reports.
+    fn id(&self) -> &'static str;
+
+    fn description(&self) -> &'static str;
+
+    /// JSON Schema of the settings `configure` accepts.
+    fn config_schema(&self) -> serde_json::Value;
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String>;
+
+    /// Look at one scan. `state` holds the history up to and including it,
+    /// and `previous_networks` from the scan before.
+    fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat>;
+}
+
+/// What the UI needs to offer a detector in its settings.
+#[derive(Serialize, Debug, Clone)]
+pub struct DetectorInfo {
+    pub id: &'static str,
+    pub description: &'static str,
+    pub config_schema: serde_json::Value,
+}
+
+/// The `enabled_threat_types` setting.
+#[derive(Deserialize, Debug, Clone)]
+#[serde(untagged)]
+pub enum EnabledThreatTypes {
+    Ids(Vec<String>),
+    Configured(BTreeMap<String, serde_json::Value>),
+}
+
+impl Default for EnabledThreatTypes {
+    fn default() -> Self {
+        Self::Ids(Vec::new())
+    }
+}
+
+fn builtin() -> Vec<Box<dyn Detector>> {
+    vec![
+        Box::new(scan_findings::EvilTwin),
+        Box::new(scan_findings::LookalikeSsid),
+        Box::new(scan_findings::KarmaAp),
+        Box::new(mac_spoof::MacSpoof::default()),
+        Box::new(flood::FloodAttack::default()),
+        Box::new(unauthorized_client::UnauthorizedClient),
+        Box::new(probe_anomaly::ProbeAnomaly::default()),
+        Box::new(rf_jamming::RfJamming::default()),
+        Box::new(signal_spike::SignalSpike::default()),
+        Box::new(blacklisted::BlacklistedNetwork),
+        Box::new(deauth::DeauthAttack::default()),
+    ]
+}
+
+/// Parse a detector's settings, rejecting unknown keys.
+fn parse_config<T: DeserializeOwned>(id: &str, config: &serde_json::Value) -> Result<T, String> {
+    serde_json::from_value(config.clone())
+        .map_err(|e| format!("Invalid settings for {}: {}", id, e))
+}
+
+/// Settings of a detector that has none to offer.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NoSettings {}
+
+fn no_settings_schema() -> serde_json::Value {
+    serde_json::json!({ "type": "object", "properties": {}, "additionalProperties": false })
+}
+
+/// The detectors the monitor runs, in a fixed order.
+pub struct DetectorRegistry {
+    detectors: Vec<Box<dyn Detector>>,
+}
+
+impl Default for DetectorRegistry {
+    /// Every detector with its defaults.
+    fn default() -> Self {
+        Self {
+            detectors: builtin(),
+        }
+    }
+}
+
+impl DetectorRegistry {
+    /// Every built-in detector, for the settings UI.
+    pub fn available() -> Vec<DetectorInfo> {
+        builtin()
+            .iter()
+            .map(|d| DetectorInfo {
+                id: d.id(),
+                description: d.description(),
+                config_schema: d.config_schema(),
+            })
+            .collect()
+    }
+
+    pub fn from_settings(settings: &EnabledThreatTypes) -> Result<Self, String> {
+        let wanted: Vec<(&str, Option<&serde_json::Value>)> = match settings {
+            EnabledThreatTypes::Ids(ids) => ids.iter().map(|id| (id.as_str(), None)).collect(),
+            EnabledThreatTypes::Configured(map) => {
+                map.iter().map(|(id, c)| (id.as_str(), Some(c))).collect()
+            }
+        };
+        if wanted.is_empty() {
+            return Ok(Self::default());
+        }
+
+        let mut available = builtin();
+        for (id, _) in &wanted {
+            if !available.iter().any(|d| d.id() == *id) {
+                let known: Vec<&str> = available.iter().map(|d| d.id()).collect();
+                return Err(format!(
+                    "Unknown threat type \"{}\"; expected one of {}",
+                    id,
+                    known.join(", ")
+                ));
+            }
+        }
+        available.retain(|d| wanted.iter().any(|(id, _)| d.id() == *id));
+        for detector in available.iter_mut() {
+            let config = wanted
+                .iter()
+                .find(|(id, _)| detector.id() == *id)
+                .and_then(|(_, c)| *c);
+            if let Some(config) = config.filter(|c| !c.is_null()) {
+                detector.configure(config)?;
+            }
+        }
+        Ok(Self {
+            detectors: available,
+        })
+    }
+
+    pub fn ids(&self) -> Vec<&'static str> {
+        self.detectors.iter().map(|d| d.id()).collect()
+    }
+
+    /// Record `scan` in `state` and run every detector over it.
+    pub fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        update_network_history(scan.networks, state, scan.at);
+
+        let threats = self
+            .detectors
+            .iter()
+            .flat_map(|d| d.observe(scan, state))
+            .collect();
+
+        state.previous_networks = scan.networks.to_vec();
+        state.scan_count += 1;
+        state.last_scan_time = Some(scan.at);
+        threats
+    }
+}
+
+/// Add a scan from an earlier session to `state`'s history without running
+/// any detector over it.
+pub fn warm_up(state: &mut MonitoringState, networks: &[WifiNetwork], at: DateTime<Utc>) {
+    update_network_history(networks, state, at);
+}
+
+/// Link quality in percent, 0 when the backend reported none.
+fn signal_percent(network: &WifiNetwork) -> i32 {
+    network.signal.map_or(0, |s| i32::from(s.percent))
+}
+
+fn update_network_history(
+    networks: &[WifiNetwork],
+    state: &mut MonitoringState,
+    now: DateTime<Utc>,
+) {
+    for network in networks {
+        let bssid = network.bssid.to_lowercase();
+        let signal_strength = signal_percent(network);
+
+        let history = state
+            .network_history
+            .entry(bssid.clone())
+            .or_insert_with(|| NetworkHistory {
+                ssid: network.ssid.clone(),
+                signal_strength,
+                last_seen: now,
+                appearance_count: 0,
+                previous_signals: Vec::new(),
+            });
+
+        history.last_seen = now;
+        history.appearance_count += 1;
+        history.signal_strength = signal_strength;
+        history.ssid = network.ssid.clone();
+
+        history.previous_signals.push(signal_strength);
+        if history.previous_signals.len() > HISTORY_SAMPLES {
+            history.previous_signals.remove(0);
+        }
+        let model = state.signal_models.entry(bssid).or_default();
+        match network.signal {
+            Some(signal) => model.update(f64::from(signal.dbm)),
+            None => model.skip(),
+        }
+    }
+
+    let oldest = now - Duration::minutes(HISTORY_MINUTES);
+    state
+        .network_history
+        .retain(|_, history| history.last_seen > oldest);
+    let known: HashSet<&String> = state.network_history.keys().collect();
+    state.signal_models.retain(|bssid, _| known.contains(bssid));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Detector, DetectorRegistry, EnabledThreatTypes, Scan};
+    use crate::structures::{DetectedThreat, MonitoringState, Signal, WifiNetwork};
+    use crate::wifi_functions::KarmaFinding;
+    use chrono::{Duration, TimeZone, Utc};
+    use rand::rngs::StdRng;
+    use rand::Rng;
+    use std::collections::HashSet;
+
+    pub(super) fn net(ssid: &str, bssid: &str, percent: u8) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            signal: Some(Signal::from_percent(percent)),
+            ..Default::default()
+        }
+    }
+
+    pub(super) fn net_dbm(ssid: &str, bssid: &str, dbm: i32) -> WifiNetwork {
+        WifiNetwork {
+            signal: Some(Signal::from_dbm(dbm)),
+            ..net(ssid, bssid, 0)
+        }
+    }
+
+    /// Whole dB of noise, uniform in `-spread..=spread`.
+    pub(super) fn noise(rng: &mut StdRng, spread: i32) -> i32 {
+        rng.gen_range(-spread..=spread)
+    }
+
+    /// Feed `scans`, 30 seconds apart, through a registry holding only
+    /// `detector`; returns the threats of each scan.
+    pub(super) fn run_scans(
+        detector: impl Detector + 'static,
+        scans: &[Vec<WifiNetwork>],
+        whitelist: &[&str],
+        blacklist: &[&str],
+    ) -> Vec<Vec<DetectedThreat>> {
+        let registry = DetectorRegistry {
+            detectors: vec![Box::new(detector)],
+        };
+        let whitelist: HashSet<String> = whitelist.iter().map(|b| b.to_string()).collect();
+        let blacklist: HashSet<String> = blacklist.iter().map(|b| b.to_string()).collect();
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let mut state = MonitoringState::default();
+
+        scans
+            .iter()
+            .enumerate()
+            .map(|(i, networks)| {
+                let karma: &[KarmaFinding] = &[];
+                let scan = Scan {
+                    networks,
+                    karma,
+                    whitelist: &whitelist,
+                    blacklist: &blacklist,
+                    link_events: &[],
+                    at: t0 + Duration::seconds(30 * i as i64),
+                };
+                registry.observe(&scan, &mut state)
+            })
+            .collect()
+    }
+
+    fn settings(json: &str) -> EnabledThreatTypes {
+        serde_json::from_str(json).unwrap()
+    }
+
+    #[test]
+    fn test_ids_are_unique() {
+        let ids = DetectorRegistry::default().ids();
+        assert_eq!(ids.len(), 11);
+        assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+
+        let available = DetectorRegistry::available();
+        assert!(available
+            .iter()
+            .all(|d| d.config_schema["type"] == "object"));
+    }
+
+    #[test]
+    fn test_settings_pick_and_configure_detectors() {
+        assert_eq!(
+            DetectorRegistry::from_settings(&settings("[]"))
+                .unwrap()
+                .ids()
+                .len(),
+            11
+        );
+        assert_eq!(
+            DetectorRegistry::from_settings(&settings(r#"["rf_jamming", "evil_twin"]"#))
+                .unwrap()
+                .ids(),
+            ["evil_twin", "rf_jamming"]
+        );
+        assert_eq!(
+            DetectorRegistry::from_settings(&settings(r#"{"probe_anomaly": {"max_hidden": 2}}"#))
+                .unwrap()
+                .ids(),
+            ["probe_anomaly"]
+        );
+        // The example in the module docs
+        assert_eq!(
+            DetectorRegistry::from_settings(&settings(
+                r#"{ "evil_twin": {}, "rf_jamming": { "z_threshold": 3.5 } }"#
+            ))
+            .unwrap()
+            .ids(),
+            ["evil_twin", "rf_jamming"]
+        );
+    }
+
+    #[test]
+    fn test_settings_errors() {
+        let err = DetectorRegistry::from_settings(&settings(r#"["deauth"]"#))
+            .err()
+            .unwrap();
+        assert!(err.starts_with("Unknown threat type \"deauth\"; expected one of evil_twin"));
+
+        let err = DetectorRegistry::from_settings(&settings(r#"{"rf_jamming": {"drop": 1}}"#))
+            .err()
+            .unwrap();
+        assert!(
+            err.starts_with("Invalid settings for rf_jamming: unknown field `drop`"),
+            "{}",
+            err
+        );
+    }
+}
@@ -0,0 +1,96 @@
+//! An unusual number of hidden networks in one scan.
+
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct ProbeAnomaly {
+    pub max_hidden: usize,
+}
+
+impl Default for ProbeAnomaly {
+    fn default() -> Self {
+        Self { max_hidden: 5 }
+    }
+}
+
+impl Detector for ProbeAnomaly {
+    fn id(&self) -> &'static str {
+        "probe_anomaly"
+    }
+
+    fn description(&self) -> &'static str {
+        "More hidden networks in one scan than usual"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": {
+                "max_hidden": { "type": "integer", "minimum": 0, "default": 5 }
+            },
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        *self = parse_config(self.id(), config)?;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let hidden_count = scan
+            .networks
+            .iter()
+            .filter(|n| n.ssid.trim().is_empty() || n.ssid == "-")
+            .count();
+
+        if hidden_count <= self.max_hidden {
+            return Vec::new();
+        }
+        vec![DetectedThreat {
+            threat_type: self.id().to_string(),
+            severity: "Medium".to_string(),
+            network_ssid: "Hidden".to_string(),
+            network_bssid: "Multiple".to_string(),
+            details: format!(
+                "Excessive hidden network probes detected: {} hidden networks, possible probe flood",
+                hidden_count
+            ),
+            timestamp: scan.at,
+        }]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ProbeAnomaly;
+    use crate::detectors::tests::{net, run_scans};
+    use crate::structures::WifiNetwork;
+
+    fn hidden(count: usize) -> Vec<WifiNetwork> {
+        (0..count)
+            .map(|i| {
+                net(
+                    if i % 2 == 0 { "" } else { "-" },
+                    &format!("00:11:22:33:44:{:02x}", i),
+                    50,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_hidden_networks_over_limit() {
+        let threats = run_scans(ProbeAnomaly::default(), &[hidden(5), hidden(6)], &[], &[]);
+
+        assert!(threats[0].is_empty());
+        assert_eq!(threats[1].len(), 1);
+        assert!(threats[1][0].details.contains("6 hidden networks"));
+
+        let threats = run_scans(ProbeAnomaly { max_hidden: 2 }, &[hidden(3)], &[], &[]);
+        assert_eq!(threats[0].len(), 1);
+    }
+}
@@ -0,0 +1,237 @@
+//! Signal collapsing below what a BSSID's signal model expects, on one
+//! network or on several at once.
+//!
+//! A drop on one network may be someone standing in the way; moderate drops
+//! on many networks in the same scan are what a jammer near the adapter
+//! looks like, so those are counted against a lower bar.
+
+use super::signal_model::AnomalySettings;
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct RfJamming {
+    /// Samples a network's model needs before its signal is judged.
+    pub warm_up: u32,
+    /// Standard deviations below the mean that make a drop on its own.
+    pub z_threshold: f64,
+    /// Noise floor for the standard deviation, in dB.
+    pub min_std_dev: f64,
+    /// Standard deviations below the mean that count towards a
+    /// simultaneous drop.
+    pub correlated_z: f64,
+    /// Networks dropping in the same scan before it is reported as jamming.
+    pub max_simultaneous_drops: usize,
+}
+
+impl Default for RfJamming {
+    fn default() -> Self {
+        let anomaly = AnomalySettings::default();
+        Self {
+            warm_up: anomaly.warm_up,
+            z_threshold: anomaly.z_threshold,
+            min_std_dev: anomaly.min_std_dev,
+            correlated_z: 2.5,
+            max_simultaneous_drops: 3,
+        }
+    }
+}
+
+impl RfJamming {
+    fn anomaly(&self) -> AnomalySettings {
+        AnomalySettings {
+            warm_up: self.warm_up,
+            z_threshold: self.z_threshold,
+            min_std_dev: self.min_std_dev,
+        }
+    }
+}
+
+impl Detector for RfJamming {
+    fn id(&self) -> &'static str {
+        "rf_jamming"
+    }
+
+    fn description(&self) -> &'static str {
+        "Signal drops far outside a network's usual range, on one network or on several at once"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        let mut properties = AnomalySettings::schema_properties();
+        properties["correlated_z"] =
+            serde_json::json!({ "type": "number", "exclusiveMinimum": 0, "default": 2.5 });
+        properties["max_simultaneous_drops"] =
+            serde_json::json!({ "type": "integer", "minimum": 0, "default": 3 });
+        serde_json::json!({
+            "type": "object",
+            "properties": properties,
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let settings: Self = parse_config(self.id(), config)?;
+        settings.anomaly().validate(self.id())?;
+        if !(settings.correlated_z > 0.0 && settings.correlated_z <= settings.z_threshold) {
+            return Err(
+                "Invalid settings for rf_jamming: correlated_z must be positive and at most z_threshold"
+                    .to_string(),
+            );
+        }
+        *self = settings;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let anomaly = self.anomaly();
+        let mut threats = Vec::new();
+        let mut networks_with_drops = 0;
+
+        for network in scan.networks {
+            let bssid = network.bssid.to_lowercase();
+            let Some((z, reading)) = state
+                .signal_models
+                .get(&bssid)
+                .and_then(|model| anomaly.z_score(model))
+            else {
+                continue;
+            };
+
+            if z <= -self.correlated_z {
+                networks_with_drops += 1;
+            }
+            if z <= -self.z_threshold {
+                threats.push(DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "High".to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: bssid,
+                    details: format!(
+                        "Sudden signal drop to {:.0} dBm from a usual {:.0} ± {:.1} dBm (z = {:.1}), possible RF jamming",
+                        reading.dbm, reading.mean, reading.std_dev, z
+                    ),
+                    timestamp: scan.at,
+                });
+            }
+        }
+
+        if networks_with_drops > self.max_simultaneous_drops {
+            threats.push(DetectedThreat {
+                threat_type: self.id().to_string(),
+                severity: "Critical".to_string(),
+                network_ssid: "Multiple".to_string(),
+                network_bssid: "Multiple".to_string(),
+                details: format!(
+                    "Multiple networks ({}) showing simultaneous signal drops, likely RF jamming attack",
+                    networks_with_drops
+                ),
+                timestamp: scan.at,
+            });
+        }
+
+        threats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RfJamming;
+    use crate::detectors::tests::{net_dbm, noise, run_scans};
+    use crate::detectors::Detector;
+    use crate::structures::WifiNetwork;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const APS: usize = 5;
+
+    fn bssid(i: usize) -> String {
+        format!("00:11:22:33:44:0{}", i)
+    }
+
+    /// `count` scans of `APS` networks around -60 dBm, give or take 3 dB.
+    fn noisy_scans(rng: &mut StdRng, count: usize) -> Vec<Vec<WifiNetwork>> {
+        (0..count)
+            .map(|_| {
+                (0..APS)
+                    .map(|i| net_dbm("Net", &bssid(i), -60 + noise(rng, 3)))
+                    .collect()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_noise_alone_is_quiet() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let threats = run_scans(RfJamming::default(), &noisy_scans(&mut rng, 200), &[], &[]);
+        assert!(threats.iter().all(Vec::is_empty), "{:?}", threats);
+    }
+
+    #[test]
+    fn test_drop_on_one_network() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let mut scans = noisy_scans(&mut rng, 30);
+        // 20 dB down: 80% -> 40% in link quality, which halving the average
+        // would not have caught
+        scans.push(vec![net_dbm("Net", &bssid(0), -80)]);
+
+        let threats = run_scans(RfJamming::default(), &scans, &[], &[]);
+
+        assert!(threats[..30].iter().all(Vec::is_empty));
+        assert_eq!(threats[30].len(), 1);
+        assert_eq!(threats[30][0].severity, "High");
+        assert!(
+            threats[30][0]
+                .details
+                .starts_with("Sudden signal drop to -80 dBm from a usual"),
+            "{}",
+            threats[30][0].details
+        );
+    }
+
+    #[test]
+    fn test_simultaneous_moderate_drops() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut scans: Vec<Vec<WifiNetwork>> = (0..40)
+            .map(|_| {
+                (0..APS)
+                    .map(|i| net_dbm("Net", &bssid(i), -60 + noise(&mut rng, 1)))
+                    .collect()
+            })
+            .collect();
+        // Each drop on its own is below the alert bar, together they are not
+        scans.push((0..APS).map(|i| net_dbm("Net", &bssid(i), -66)).collect());
+
+        let threats = run_scans(RfJamming::default(), &scans, &[], &[]);
+
+        let last = threats.last().unwrap();
+        assert_eq!(last.len(), 1, "{:?}", last);
+        assert_eq!(last[0].severity, "Critical");
+        assert_eq!(last[0].network_bssid, "Multiple");
+    }
+
+    #[test]
+    fn test_respects_warm_up() {
+        let drop = |warm_up: u32| {
+            let mut detector = RfJamming::default();
+            detector
+                .configure(&serde_json::json!({ "warm_up": warm_up }))
+                .unwrap();
+            let mut scans = vec![vec![net_dbm("Net", &bssid(0), -60)]; 3];
+            scans.push(vec![net_dbm("Net", &bssid(0), -90)]);
+            run_scans(detector, &scans, &[], &[])[3].len()
+        };
+        assert_eq!(drop(5), 0);
+        assert_eq!(drop(3), 1);
+
+        let err = RfJamming::default().configure(&serde_json::json!({ "correlated_z": 5.0 }));
+        assert_eq!(
+            err.unwrap_err(),
+            "Invalid settings for rf_jamming: correlated_z must be positive and at most z_threshold"
+        );
+        assert!(RfJamming::default()
+            .configure(&serde_json::json!({ "drop_ratio": 0.5 }))
+            .is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Evil twins, look-alike names and KARMA radios the scan pipeline already
+//! flagged, reported as threats.
+
+use super::{no_settings_schema, parse_config, Detector, NoSettings, Scan};
+use crate::structures::{DetectedThreat, MonitoringState, RiskGrade, WifiNetwork};
+
+/// Severity and verdict from the network's assessment.
+fn assessed(network: &WifiNetwork) -> (&'static str, String) {
+    match &network.risk_assessment {
+        Some(a) => (a.grade.severity(), a.verdict.clone()),
+        None => (RiskGrade::High.severity(), String::new()),
+    }
+}
+
+pub struct EvilTwin;
+
+impl Detector for EvilTwin {
+    fn id(&self) -> &'static str {
+        "evil_twin"
+    }
+
+    fn description(&self) -> &'static str {
+        "A network posing as one already in range"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        no_settings_schema()
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        parse_config::<NoSettings>(self.id(), config).map(|_| ())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        scan.networks
+            .iter()
+            .filter(|network| network.is_evil_twin)
+            .map(|network| {
+                let (severity, details) = assessed(network);
+                DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: severity.to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: network.bssid.to_lowercase(),
+                    details,
+                    timestamp: scan.at,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct LookalikeSsid;
+
+impl Detector for LookalikeSsid {
+    fn id(&self) -> &'static str {
+        "lookalike_ssid"
+    }
+
+    fn description(&self) -> &'static str {
+        "A name imitating a whitelisted or trusted network"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        no_settings_schema()
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        parse_config::<NoSettings>(self.id(), config).map(|_| ())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        scan.networks
+            .iter()
+            .filter_map(|network| {
+                let imitates = network.imitates.as_ref()?;
+                let (severity, verdict) = assessed(network);
+                Some(DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: severity.to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: network.bssid.to_lowercase(),
+                    details: format!("Imitates \"{}\". {}", imitates, verdict),
+                    timestamp: scan.at,
+                })
+            })
+            .collect()
+    }
+}
+
+/// The window and SSID limit are those of the scan pipeline's KARMA
+/// detector, which `scan_wifi` shares.
+pub struct KarmaAp;
+
+impl Detector for KarmaAp {
+    fn id(&self) -> &'static str {
+        "karma_ap"
+    }
+
+    fn description(&self) -> &'static str {
+        "One radio answering under many SSIDs (KARMA/MANA)"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        no_settings_schema()
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        parse_config::<NoSettings>(self.id(), config).map(|_| ())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        scan.karma
+            .iter()
+            .map(|finding| finding.threat(scan.at))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{EvilTwin, KarmaAp, LookalikeSsid};
+    use crate::detectors::tests::{net, run_scans};
+    use crate::detectors::{Detector, Scan};
+    use crate::structures::MonitoringState;
+    use crate::wifi_functions::KarmaDetector;
+    use chrono::{Duration, Utc};
+    use std::collections::HashSet;
+
+    #[test]
+    fn test_evil_twin_reported_each_scan() {
+        let mut twin = net("Office", "02:00:00:00:00:01", 95);
+        twin.is_evil_twin = true;
+        let scans = vec![
+            vec![net("Office", "00:11:22:33:44:55", 70)],
+            vec![net("Office", "00:11:22:33:44:55", 70), twin.clone()],
+            vec![net("Office", "00:11:22:33:44:55", 70), twin],
+        ];
+
+        let threats = run_scans(EvilTwin, &scans, &[], &[]);
+
+        assert_eq!(threats.iter().map(Vec::len).collect::<Vec<_>>(), [0, 1, 1]);
+        assert_eq!(threats[1][0].network_bssid, "02:00:00:00:00:01");
+        assert_eq!(threats[1][0].severity, "High");
+    }
+
+    #[test]
+    fn test_lookalike_names_imitated_network() {
+        let mut imitator = net("0ffice", "02:00:00:00:00:02", 95);
+        imitator.imitates = Some("Office".to_string());
+
+        let threats = run_scans(
+            LookalikeSsid,
+            &[vec![net("Office", "00:11:22:33:44:55", 70), imitator]],
+            &[],
+            &[],
+        );
+
+        assert_eq!(threats[0].len(), 1);
+        assert_eq!(threats[0][0].threat_type, "lookalike_ssid");
+        assert!(threats[0][0].details.starts_with("Imitates \"Office\""));
+    }
+
+    #[test]
+    fn test_karma_findings_become_threats() {
+        let mut karma = KarmaDetector::default();
+        let t0 = Utc::now();
+        let mut findings = Vec::new();
+        for (i, ssid) in ["a", "b", "c", "d", "e", "f"].iter().enumerate() {
+            let at = t0 + Duration::seconds(30 * i as i64);
+            findings = karma.observe(&[net(ssid, "02:00:00:00:00:0a", 80)], at);
+        }
+        let none = HashSet::new();
+        let scan = Scan {
+            networks: &[],
+            karma: &findings,
+            whitelist: &none,
+            blacklist: &none,
+            link_events: &[],
+            at: t0,
+        };
+
+        let threats = KarmaAp.observe(&scan, &mut MonitoringState::default());
+
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].threat_type, "karma_ap");
+        assert_eq!(threats[0].network_bssid, "02:00:00:00:00:0a");
+        assert_eq!(threats[0].severity, "Critical");
+    }
+}
@@ -0,0 +1,146 @@
+//! What one BSSID's signal usually looks like: an exponentially weighted
+//! mean and variance of its RSSI, so detectors can tell a real jump or drop
+//! from the few dB a signal wanders anyway.
+
+/// Weight of the newest sample; the model mostly reflects the last ten or
+/// so scans.
+const ALPHA: f64 = 0.2;
+
+/// The latest sample and the model as it stood before it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub dbm: f64,
+    pub mean: f64,
+    pub std_dev: f64,
+    /// Samples the model had taken before this one.
+    pub samples: u32,
+}
+
+impl Reading {
+    /// How many standard deviations the sample is from the mean, taking
+    /// the deviation as at least `min_std_dev` so a signal that happened to
+    /// hold perfectly steady does not make every dB count.
+    pub fn z_score(&self, min_std_dev: f64) -> f64 {
+        (self.dbm - self.mean) / self.std_dev.max(min_std_dev)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SignalModel {
+    mean: f64,
+    variance: f64,
+    samples: u32,
+    latest: Option<Reading>,
+}
+
+impl SignalModel {
+    /// Take in a sample in dBm, after noting how it compares to the model
+    /// so far.
+    pub fn update(&mut self, dbm: f64) {
+        if self.samples == 0 {
+            self.mean = dbm;
+            self.variance = 0.0;
+            self.latest = None;
+        } else {
+            self.latest = Some(Reading {
+                dbm,
+                mean: self.mean,
+                std_dev: self.variance.sqrt(),
+                samples: self.samples,
+            });
+            let diff = dbm - self.mean;
+            let increment = ALPHA * diff;
+            self.mean += increment;
+            self.variance = (1.0 - ALPHA) * (self.variance + diff * increment);
+        }
+        self.samples = self.samples.saturating_add(1);
+    }
+
+    /// The network was seen without a signal; there is no new reading.
+    pub fn skip(&mut self) {
+        self.latest = None;
+    }
+
+    /// The reading of this scan, if the model had one to compare it with.
+    pub fn latest(&self) -> Option<&Reading> {
+        self.latest.as_ref()
+    }
+}
+
+/// How the detectors that judge readings are tuned.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnomalySettings {
+    /// Samples a BSSID's model needs before its readings are judged.
+    pub warm_up: u32,
+    /// Standard deviations from the mean a reading has to be to count.
+    pub z_threshold: f64,
+    /// Noise floor for the standard deviation, in dB.
+    pub min_std_dev: f64,
+}
+
+impl Default for AnomalySettings {
+    fn default() -> Self {
+        Self {
+            warm_up: 5,
+            z_threshold: 4.0,
+            min_std_dev: 2.0,
+        }
+    }
+}
+
+impl AnomalySettings {
+    /// The z-score of `model`'s latest reading, once it is warmed up.
+    pub fn z_score(&self, model: &SignalModel) -> Option<(f64, Reading)> {
+        let reading = model.latest()?;
+        (reading.samples >= self.warm_up).then(|| (reading.z_score(self.min_std_dev), *reading))
+    }
+
+    pub fn validate(&self, id: &str) -> Result<(), String> {
+        if self.warm_up == 0 {
+            return Err(format!(
+                "Invalid settings for {}: warm_up must be at least 1",
+                id
+            ));
+        }
+        if !(self.z_threshold > 0.0 && self.min_std_dev > 0.0) {
+            return Err(format!(
+                "Invalid settings for {}: z_threshold and min_std_dev must be positive",
+                id
+            ));
+        }
+        Ok(())
+    }
+
+    pub fn schema_properties() -> serde_json::Value {
+        serde_json::json!({
+            "warm_up": { "type": "integer", "minimum": 1, "default": 5 },
+            "z_threshold": { "type": "number", "exclusiveMinimum": 0, "default": 4.0 },
+            "min_std_dev": { "type": "number", "exclusiveMinimum": 0, "default": 2.0 }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignalModel;
+
+    #[test]
+    fn test_tracks_mean_and_spread() {
+        let mut model = SignalModel::default();
+        model.update(-60.0);
+        assert_eq!(model.latest(), None);
+
+        for i in 0..50 {
+            model.update(if i % 2 == 0 { -57.0 } else { -63.0 });
+        }
+        let reading = *model.latest().unwrap();
+        assert_eq!(reading.samples, 50);
+        assert!((reading.mean + 60.0).abs() < 1.0, "{:?}", reading);
+        assert!((2.0..4.0).contains(&reading.std_dev), "{:?}", reading);
+
+        model.update(-90.0);
+        assert!(model.latest().unwrap().z_score(2.0) < -7.0);
+        model.skip();
+        assert_eq!(model.latest(), None);
+    }
+}
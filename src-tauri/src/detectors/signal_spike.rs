@@ -0,0 +1,155 @@
+//! An access point suddenly much stronger than its signal model expects.
+//! Access points do not move, so one that seems to have come much closer is
+//! typically a spoof of it transmitting nearby.
+
+use super::signal_model::AnomalySettings;
+use super::{parse_config, Detector, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+use serde::Deserialize;
+
+#[derive(Deserialize, Debug, Clone)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignalSpike {
+    /// Samples a network's model needs before its signal is judged.
+    pub warm_up: u32,
+    /// Standard deviations above the mean that make a jump.
+    pub z_threshold: f64,
+    /// Noise floor for the standard deviation, in dB.
+    pub min_std_dev: f64,
+}
+
+impl Default for SignalSpike {
+    fn default() -> Self {
+        let anomaly = AnomalySettings::default();
+        Self {
+            warm_up: anomaly.warm_up,
+            z_threshold: anomaly.z_threshold,
+            min_std_dev: anomaly.min_std_dev,
+        }
+    }
+}
+
+impl SignalSpike {
+    fn anomaly(&self) -> AnomalySettings {
+        AnomalySettings {
+            warm_up: self.warm_up,
+            z_threshold: self.z_threshold,
+            min_std_dev: self.min_std_dev,
+        }
+    }
+}
+
+impl Detector for SignalSpike {
+    fn id(&self) -> &'static str {
+        "signal_spike"
+    }
+
+    fn description(&self) -> &'static str {
+        "Access points suddenly much stronger than usual, as a spoof nearby would be"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        serde_json::json!({
+            "type": "object",
+            "properties": AnomalySettings::schema_properties(),
+            "additionalProperties": false
+        })
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        let settings: Self = parse_config(self.id(), config)?;
+        settings.anomaly().validate(self.id())?;
+        *self = settings;
+        Ok(())
+    }
+
+    fn observe(&self, scan: &Scan, state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        let anomaly = self.anomaly();
+        scan.networks
+            .iter()
+            .filter_map(|network| {
+                let bssid = network.bssid.to_lowercase();
+                let (z, reading) = anomaly.z_score(state.signal_models.get(&bssid)?)?;
+                (z >= self.z_threshold).then(|| DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "High".to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: bssid,
+                    details: format!(
+                        "Signal jumped to {:.0} dBm from a usual {:.0} ± {:.1} dBm (z = {:.1}), the access point may be spoofed from close by",
+                        reading.dbm, reading.mean, reading.std_dev, z
+                    ),
+                    timestamp: scan.at,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SignalSpike;
+    use crate::detectors::tests::{net_dbm, noise, run_scans};
+    use crate::detectors::Detector;
+    use crate::structures::WifiNetwork;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const AP: &str = "00:11:22:33:44:01";
+
+    fn noisy_scans(rng: &mut StdRng, count: usize, dbm: i32) -> Vec<Vec<WifiNetwork>> {
+        (0..count)
+            .map(|_| vec![net_dbm("Office", AP, dbm + noise(rng, 4))])
+            .collect()
+    }
+
+    #[test]
+    fn test_jump_closer_is_reported() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut scans = noisy_scans(&mut rng, 40, -75);
+        scans.push(vec![net_dbm("Office", AP, -40)]);
+
+        let threats = run_scans(SignalSpike::default(), &scans, &[], &[]);
+
+        assert!(threats[..40].iter().all(Vec::is_empty), "{:?}", threats);
+        assert_eq!(threats[40].len(), 1);
+        assert!(
+            threats[40][0]
+                .details
+                .starts_with("Signal jumped to -40 dBm from a usual -75"),
+            "{}",
+            threats[40][0].details
+        );
+    }
+
+    #[test]
+    fn test_drops_and_gradual_changes_are_not_jumps() {
+        let mut rng = StdRng::seed_from_u64(9);
+        let mut scans = noisy_scans(&mut rng, 20, -75);
+        // Walking towards the AP: 1 dB a scan
+        scans.extend((0..30).map(|i| vec![net_dbm("Office", AP, -75 + i)]));
+        scans.push(vec![net_dbm("Office", AP, -90)]);
+
+        let threats = run_scans(SignalSpike::default(), &scans, &[], &[]);
+        assert!(threats.iter().all(Vec::is_empty), "{:?}", threats);
+    }
+
+    #[test]
+    fn test_respects_warm_up() {
+        let scans = vec![
+            vec![net_dbm("Office", AP, -75)],
+            vec![net_dbm("Office", AP, -75)],
+            vec![net_dbm("Office", AP, -40)],
+        ];
+        assert!(run_scans(SignalSpike::default(), &scans, &[], &[])[2].is_empty());
+
+        let mut eager = SignalSpike::default();
+        eager
+            .configure(&serde_json::json!({ "warm_up": 2 }))
+            .unwrap();
+        assert_eq!(run_scans(eager, &scans, &[], &[])[2].len(), 1);
+        assert!(SignalSpike::default()
+            .configure(&serde_json::json!({ "warm_up": 0 }))
+            .is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Networks missing from the BSSID whitelist, for users who keep one.
+
+use super::{no_settings_schema, parse_config, Detector, NoSettings, Scan};
+use crate::structures::{DetectedThreat, MonitoringState};
+
+pub struct UnauthorizedClient;
+
+impl Detector for UnauthorizedClient {
+    fn id(&self) -> &'static str {
+        "unauthorized_client"
+    }
+
+    fn description(&self) -> &'static str {
+        "A network that is not on the BSSID whitelist"
+    }
+
+    fn config_schema(&self) -> serde_json::Value {
+        no_settings_schema()
+    }
+
+    fn configure(&mut self, config: &serde_json::Value) -> Result<(), String> {
+        parse_config::<NoSettings>(self.id(), config).map(|_| ())
+    }
+
+    fn observe(&self, scan: &Scan, _state: &mut MonitoringState) -> Vec<DetectedThreat> {
+        // Only users who keep a whitelist want every other network reported
+        if scan.whitelist.is_empty() {
+            return Vec::new();
+        }
+
+        scan.networks
+            .iter()
+            .filter(|network| !scan.whitelist.contains(&network.bssid.to_lowercase()))
+            .map(|network| {
+                let bssid = network.bssid.to_lowercase();
+                DetectedThreat {
+                    threat_type: self.id().to_string(),
+                    severity: "Medium".to_string(),
+                    network_ssid: network.ssid.clone(),
+                    network_bssid: bssid.clone(),
+                    details: format!(
+                        "Unauthorized network detected: {} (BSSID: {}) not in whitelist",
+                        network.ssid, bssid
+                    ),
+                    timestamp: scan.at,
+                }
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UnauthorizedClient;
+    use crate::detectors::tests::{net, run_scans};
+
+    #[test]
+    fn test_reports_networks_outside_whitelist() {
+        let scans = vec![
+            vec![net("Home", "AA:BB:CC:00:00:01", 80)],
+            vec![
+                net("Home", "AA:BB:CC:00:00:01", 80),
+                net("Cafe", "00:11:22:33:44:55", 40),
+            ],
+        ];
+
+        let threats = run_scans(UnauthorizedClient, &scans, &["aa:bb:cc:00:00:01"], &[]);
+
+        assert!(threats[0].is_empty());
+        assert_eq!(threats[1].len(), 1);
+        assert_eq!(threats[1][0].network_bssid, "00:11:22:33:44:55");
+    }
+
+    #[test]
+    fn test_silent_without_whitelist() {
+        let threats = run_scans(
+            UnauthorizedClient,
+            &[vec![net("Cafe", "00:11:22:33:44:55", 40)]],
+            &[],
+            &[],
+        );
+        assert!(threats[0].is_empty());
+    }
+}
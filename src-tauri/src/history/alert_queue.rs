@@ -0,0 +1,172 @@
+//! Storage for the outbound alert queue (see `crate::alerts`). Alerts stay
+//! in the table until the server accepts them, so a restart or a long time
+//! offline loses nothing.
+
+use super::{db_error, HistoryStore};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, OptionalExtension};
+
+/// An alert waiting for delivery.
+#[derive(Debug, Clone, PartialEq)]
+pub struct QueuedAlert {
+    pub id: i64,
+    /// Sent with every attempt, so the server can drop repeats of an alert
+    /// it already stored when only the response got lost.
+    pub idempotency_key: String,
+    /// JSON body to post.
+    pub payload: String,
+    pub attempts: u32,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct QueueCounts {
+    /// Alerts still to be delivered.
+    pub pending: u64,
+    /// Alerts the server refused outright; kept but not retried.
+    pub rejected: u64,
+    /// When the next pending alert is due.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+}
+
+impl HistoryStore {
+    pub fn enqueue_alert(
+        &mut self,
+        idempotency_key: &str,
+        payload: &str,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "INSERT INTO alert_queue (idempotency_key, payload, queued_at, next_attempt_at)
+                 VALUES (?1, ?2, ?3, ?3)
+                 ON CONFLICT (idempotency_key) DO NOTHING",
+                params![idempotency_key, payload, at],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// The oldest pending alert due at `now`.
+    pub fn next_due_alert(&self, now: DateTime<Utc>) -> Result<Option<QueuedAlert>, String> {
+        self.conn
+            .query_row(
+                "SELECT id, idempotency_key, payload, attempts FROM alert_queue
+                 WHERE rejected = 0 AND next_attempt_at <= ?1
+                 ORDER BY next_attempt_at, id LIMIT 1",
+                [now],
+                |row| {
+                    Ok(QueuedAlert {
+                        id: row.get(0)?,
+                        idempotency_key: row.get(1)?,
+                        payload: row.get(2)?,
+                        attempts: row.get(3)?,
+                    })
+                },
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// The server has the alert; forget it.
+    pub fn remove_alert(&mut self, id: i64) -> Result<(), String> {
+        self.conn
+            .execute("DELETE FROM alert_queue WHERE id = ?1", [id])
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// Count a failed attempt and try again at `next_attempt_at`.
+    pub fn retry_alert(
+        &mut self,
+        id: i64,
+        next_attempt_at: DateTime<Utc>,
+        error: &str,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE alert_queue
+                 SET attempts = attempts + 1, next_attempt_at = ?2, last_error = ?3
+                 WHERE id = ?1",
+                params![id, next_attempt_at, error],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// The server refused the alert itself; retrying would not help.
+    pub fn reject_alert(&mut self, id: i64, error: &str) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE alert_queue
+                 SET attempts = attempts + 1, rejected = 1, last_error = ?2
+                 WHERE id = ?1",
+                params![id, error],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    pub fn alert_queue_counts(&self) -> Result<QueueCounts, String> {
+        self.conn
+            .query_row(
+                "SELECT
+                     COUNT(*) FILTER (WHERE rejected = 0),
+                     COUNT(*) FILTER (WHERE rejected = 1),
+                     MIN(next_attempt_at) FILTER (WHERE rejected = 0)
+                 FROM alert_queue",
+                [],
+                |row| {
+                    Ok(QueueCounts {
+                        pending: row.get(0)?,
+                        rejected: row.get(1)?,
+                        next_attempt_at: row.get(2)?,
+                    })
+                },
+            )
+            .map_err(db_error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::history::HistoryStore;
+    use chrono::{Duration, TimeZone, Utc};
+
+    #[test]
+    fn test_due_order_retry_and_reject() {
+        let t0 = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let mut store = HistoryStore::in_memory().unwrap();
+        store.enqueue_alert("a", "{}", t0).unwrap();
+        store.enqueue_alert("b", "{}", t0).unwrap();
+        // The same key again is the same alert
+        store.enqueue_alert("a", "{}", t0).unwrap();
+        assert_eq!(store.alert_queue_counts().unwrap().pending, 2);
+
+        let first = store.next_due_alert(t0).unwrap().unwrap();
+        assert_eq!((first.idempotency_key.as_str(), first.attempts), ("a", 0));
+        store
+            .retry_alert(first.id, t0 + Duration::seconds(30), "HTTP 503")
+            .unwrap();
+
+        let second = store.next_due_alert(t0).unwrap().unwrap();
+        assert_eq!(second.idempotency_key, "b");
+        store.reject_alert(second.id, "HTTP 400").unwrap();
+
+        assert_eq!(store.next_due_alert(t0).unwrap(), None);
+        let retried = store
+            .next_due_alert(t0 + Duration::seconds(30))
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (retried.idempotency_key.as_str(), retried.attempts),
+            ("a", 1)
+        );
+
+        let counts = store.alert_queue_counts().unwrap();
+        assert_eq!((counts.pending, counts.rejected), (1, 1));
+        assert_eq!(counts.next_attempt_at, Some(t0 + Duration::seconds(30)));
+
+        store.remove_alert(retried.id).unwrap();
+        assert_eq!(store.alert_queue_counts().unwrap().pending, 0);
+    }
+}
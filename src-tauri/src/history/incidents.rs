@@ -0,0 +1,218 @@
+//! Storage for incidents (see `crate::incidents`). A fingerprint has at most
+//! one open or acknowledged incident; once that is closed, the next
+//! occurrence starts a new one.
+
+use super::{db_error, HistoryStore};
+use crate::incidents::{Incident, IncidentState};
+use crate::structures::DetectedThreat;
+use chrono::{DateTime, Utc};
+use rusqlite::types::{FromSql, FromSqlError, FromSqlResult, ToSql, ToSqlOutput, ValueRef};
+use rusqlite::{params, OptionalExtension, Row};
+
+const INCIDENT_COLUMNS: &str = "id, fingerprint, group_id, subject, threat_type, severity,
+    network_ssid, network_bssid, details, state, first_seen, last_seen, occurrences,
+    acknowledged_at, closed_at";
+
+impl ToSql for IncidentState {
+    fn to_sql(&self) -> rusqlite::Result<ToSqlOutput<'_>> {
+        Ok(self.as_str().into())
+    }
+}
+
+impl FromSql for IncidentState {
+    fn column_result(value: ValueRef<'_>) -> FromSqlResult<Self> {
+        let state = value.as_str()?;
+        IncidentState::parse(state)
+            .ok_or_else(|| FromSqlError::Other(format!("unknown state {:?}", state).into()))
+    }
+}
+
+fn incident_from_row(row: &Row) -> rusqlite::Result<Incident> {
+    Ok(Incident {
+        id: row.get(0)?,
+        fingerprint: row.get(1)?,
+        group_id: row.get(2)?,
+        subject: row.get(3)?,
+        threat_type: row.get(4)?,
+        severity: row.get(5)?,
+        network_ssid: row.get(6)?,
+        network_bssid: row.get(7)?,
+        details: row.get(8)?,
+        state: row.get(9)?,
+        first_seen: row.get(10)?,
+        last_seen: row.get(11)?,
+        occurrences: row.get(12)?,
+        acknowledged_at: row.get(13)?,
+        closed_at: row.get(14)?,
+    })
+}
+
+impl HistoryStore {
+    pub fn incident(&self, id: i64) -> Result<Option<Incident>, String> {
+        self.conn
+            .query_row(
+                &format!("SELECT {} FROM incidents WHERE id = ?1", INCIDENT_COLUMNS),
+                [id],
+                incident_from_row,
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// The open or acknowledged incident for `fingerprint`.
+    pub fn active_incident(&self, fingerprint: &str) -> Result<Option<Incident>, String> {
+        self.conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM incidents
+                     WHERE fingerprint = ?1 AND state IN ('open', 'acknowledged')",
+                    INCIDENT_COLUMNS
+                ),
+                [fingerprint],
+                incident_from_row,
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// The group of the latest active incident about `subject`.
+    pub fn active_group(&self, subject: &str) -> Result<Option<i64>, String> {
+        self.conn
+            .query_row(
+                "SELECT group_id FROM incidents
+                 WHERE subject = ?1 AND state IN ('open', 'acknowledged')
+                 ORDER BY last_seen DESC, id DESC LIMIT 1",
+                [subject],
+                |row| row.get(0),
+            )
+            .optional()
+            .map_err(db_error)
+    }
+
+    /// Open an incident for the first occurrence of `threat`, in `group` or
+    /// in a group of its own.
+    pub fn open_incident(
+        &mut self,
+        fingerprint: &str,
+        subject: &str,
+        group: Option<i64>,
+        threat: &DetectedThreat,
+    ) -> Result<Incident, String> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO incidents
+                 (fingerprint, group_id, subject, threat_type, severity, network_ssid,
+                  network_bssid, details, state, first_seen, last_seen, occurrences)
+             VALUES (?1, COALESCE(?2, 0), ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?10, 1)",
+            params![
+                fingerprint,
+                group,
+                subject,
+                threat.threat_type,
+                threat.severity,
+                threat.network_ssid,
+                threat.network_bssid,
+                threat.details,
+                IncidentState::Open,
+                threat.timestamp,
+            ],
+        )
+        .map_err(db_error)?;
+        let id = tx.last_insert_rowid();
+        if group.is_none() {
+            tx.execute("UPDATE incidents SET group_id = id WHERE id = ?1", [id])
+                .map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        self.incident(id)?
+            .ok_or_else(|| format!("Incident {} vanished after insert", id))
+    }
+
+    /// Count another occurrence of incident `id`, raising its severity to
+    /// the threat's when `escalate` is set.
+    pub fn repeat_incident(
+        &mut self,
+        id: i64,
+        threat: &DetectedThreat,
+        escalate: bool,
+    ) -> Result<Incident, String> {
+        self.conn
+            .execute(
+                "UPDATE incidents
+                 SET occurrences = occurrences + 1,
+                     last_seen = MAX(last_seen, ?2),
+                     details = ?3,
+                     severity = CASE WHEN ?4 THEN ?5 ELSE severity END
+                 WHERE id = ?1",
+                params![
+                    id,
+                    threat.timestamp,
+                    threat.details,
+                    escalate,
+                    threat.severity
+                ],
+            )
+            .map_err(db_error)?;
+        self.incident(id)?
+            .ok_or_else(|| format!("Incident {} not found", id))
+    }
+
+    /// Expire the active incidents last seen before `cutoff`; returns how
+    /// many there were.
+    pub fn expire_incidents(
+        &mut self,
+        cutoff: DateTime<Utc>,
+        now: DateTime<Utc>,
+    ) -> Result<usize, String> {
+        self.conn
+            .execute(
+                "UPDATE incidents SET state = ?1, closed_at = ?2
+                 WHERE state IN ('open', 'acknowledged') AND last_seen < ?3",
+                params![IncidentState::Expired, now, cutoff],
+            )
+            .map_err(db_error)
+    }
+
+    pub fn acknowledge_incident(&mut self, id: i64, at: DateTime<Utc>) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE incidents SET state = ?2, acknowledged_at = ?3 WHERE id = ?1",
+                params![id, IncidentState::Acknowledged, at],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// Close incident `id` as resolved or expired.
+    pub fn close_incident(
+        &mut self,
+        id: i64,
+        state: IncidentState,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        self.conn
+            .execute(
+                "UPDATE incidents SET state = ?2, closed_at = ?3 WHERE id = ?1",
+                params![id, state, at],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    /// The `limit` most recently seen incidents, newest first; closed ones
+    /// only with `include_closed`.
+    pub fn incidents(&self, include_closed: bool, limit: usize) -> Result<Vec<Incident>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(&format!(
+                "SELECT {} FROM incidents
+                 WHERE ?1 OR state IN ('open', 'acknowledged')
+                 ORDER BY last_seen DESC, id DESC LIMIT ?2",
+                INCIDENT_COLUMNS
+            ))
+            .map_err(db_error)?;
+        stmt.query_map(params![include_closed, limit as i64], incident_from_row)
+            .and_then(Iterator::collect)
+            .map_err(db_error)
+    }
+}
@@ -0,0 +1,189 @@
+//! Schema of the history database. Each entry upgrades the schema by one
+//! version, tracked in SQLite's `user_version`; never edit one that has
+//! shipped, add a new one instead.
+
+use rusqlite::Connection;
+
+const MIGRATIONS: &[&str] = &[
+    // 1: scans, access points, signal samples, threats, connection attempts
+    "
+    CREATE TABLE scans (
+        id INTEGER PRIMARY KEY,
+        scanned_at TEXT NOT NULL,
+        source TEXT NOT NULL,
+        network_count INTEGER NOT NULL
+    );
+    CREATE INDEX scans_scanned_at ON scans (scanned_at);
+
+    CREATE TABLE bssids (
+        bssid TEXT PRIMARY KEY,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL,
+        times_seen INTEGER NOT NULL,
+        last_ssid TEXT NOT NULL,
+        vendor TEXT
+    );
+    CREATE INDEX bssids_last_seen ON bssids (last_seen);
+
+    CREATE TABLE bssid_ssids (
+        bssid TEXT NOT NULL REFERENCES bssids (bssid),
+        ssid TEXT NOT NULL,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL,
+        PRIMARY KEY (bssid, ssid)
+    );
+
+    CREATE TABLE signal_samples (
+        scan_id INTEGER NOT NULL REFERENCES scans (id),
+        bssid TEXT NOT NULL REFERENCES bssids (bssid),
+        ssid TEXT NOT NULL,
+        percent INTEGER,
+        dbm INTEGER,
+        channel INTEGER,
+        risk TEXT NOT NULL,
+        PRIMARY KEY (scan_id, bssid)
+    );
+    CREATE INDEX signal_samples_bssid ON signal_samples (bssid, scan_id);
+
+    CREATE TABLE threats (
+        id INTEGER PRIMARY KEY,
+        detected_at TEXT NOT NULL,
+        threat_type TEXT NOT NULL,
+        severity TEXT NOT NULL,
+        network_ssid TEXT NOT NULL,
+        network_bssid TEXT NOT NULL,
+        details TEXT NOT NULL
+    );
+    CREATE INDEX threats_detected_at ON threats (detected_at);
+
+    CREATE TABLE connection_attempts (
+        id INTEGER PRIMARY KEY,
+        attempted_at TEXT NOT NULL,
+        ssid TEXT NOT NULL,
+        succeeded INTEGER NOT NULL,
+        message TEXT NOT NULL
+    );
+    ",
+    // 2: threat alerts waiting to be delivered to the server
+    "
+    CREATE TABLE alert_queue (
+        id INTEGER PRIMARY KEY,
+        idempotency_key TEXT NOT NULL UNIQUE,
+        payload TEXT NOT NULL,
+        queued_at TEXT NOT NULL,
+        attempts INTEGER NOT NULL DEFAULT 0,
+        next_attempt_at TEXT NOT NULL,
+        last_error TEXT,
+        rejected INTEGER NOT NULL DEFAULT 0
+    );
+    CREATE INDEX alert_queue_due ON alert_queue (rejected, next_attempt_at);
+    ",
+    // 3: incidents, one per run of the same threat
+    "
+    CREATE TABLE incidents (
+        id INTEGER PRIMARY KEY,
+        fingerprint TEXT NOT NULL,
+        group_id INTEGER NOT NULL,
+        subject TEXT NOT NULL,
+        threat_type TEXT NOT NULL,
+        severity TEXT NOT NULL,
+        network_ssid TEXT NOT NULL,
+        network_bssid TEXT NOT NULL,
+        details TEXT NOT NULL,
+        state TEXT NOT NULL,
+        first_seen TEXT NOT NULL,
+        last_seen TEXT NOT NULL,
+        occurrences INTEGER NOT NULL,
+        acknowledged_at TEXT,
+        closed_at TEXT
+    );
+    CREATE UNIQUE INDEX incidents_active_fingerprint ON incidents (fingerprint)
+        WHERE state IN ('open', 'acknowledged');
+    CREATE INDEX incidents_subject ON incidents (subject, state);
+    CREATE INDEX incidents_last_seen ON incidents (last_seen);
+    ",
+];
+
+/// Schema version a fully migrated database has.
+pub const LATEST_VERSION: usize = MIGRATIONS.len();
+
+/// Bring the schema up to date, one transaction per version.
+pub fn migrate(conn: &mut Connection) -> Result<(), String> {
+    let version: usize = conn
+        .pragma_query_value(None, "user_version", |row| row.get(0))
+        .map_err(|e| format!("Failed to read history schema version: {}", e))?;
+    if version > LATEST_VERSION {
+        return Err(format!(
+            "History database has schema version {}, newer than the {} this version of the app knows",
+            version, LATEST_VERSION
+        ));
+    }
+
+    for (index, sql) in MIGRATIONS.iter().enumerate().skip(version) {
+        let target = index + 1;
+        let tx = conn
+            .transaction()
+            .map_err(|e| format!("Failed to start history migration: {}", e))?;
+        tx.execute_batch(sql)
+            .and_then(|_| tx.pragma_update(None, "user_version", target))
+            .and_then(|_| tx.commit())
+            .map_err(|e| format!("Failed to migrate history to version {}: {}", target, e))?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{migrate, LATEST_VERSION, MIGRATIONS};
+    use rusqlite::Connection;
+
+    fn version(conn: &Connection) -> usize {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_migrates_once() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn), LATEST_VERSION);
+
+        // Running again on an up-to-date database is a no-op
+        migrate(&mut conn).unwrap();
+        assert_eq!(version(&conn), LATEST_VERSION);
+    }
+
+    #[test]
+    fn test_upgrade_keeps_data() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(MIGRATIONS[0]).unwrap();
+        conn.pragma_update(None, "user_version", 1).unwrap();
+        conn.execute(
+            "INSERT INTO scans (scanned_at, source, network_count) VALUES ('2026-03-02', 'manual', 0)",
+            [],
+        )
+        .unwrap();
+
+        migrate(&mut conn).unwrap();
+
+        assert_eq!(version(&conn), LATEST_VERSION);
+        let scans: i64 = conn
+            .query_row("SELECT COUNT(*) FROM scans", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(scans, 1);
+        let queued: i64 = conn
+            .query_row("SELECT COUNT(*) FROM alert_queue", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(queued, 0);
+    }
+
+    #[test]
+    fn test_refuses_newer_schema() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.pragma_update(None, "user_version", LATEST_VERSION + 1)
+            .unwrap();
+        let err = migrate(&mut conn).unwrap_err();
+        let newer = format!("History database has schema version {}", LATEST_VERSION + 1);
+        assert!(err.starts_with(&newer), "{}", err);
+    }
+}
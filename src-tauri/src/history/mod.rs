@@ -0,0 +1,595 @@
+//! Local history of scans, access points, signal samples, threats and
+//! connection attempts, kept in SQLite in the app data dir so it survives a
+//! restart.
+//!
+//! Every scan is recorded, whether the user ran it or the monitor did;
+//! `bssid_history` then answers when an access point was first and last seen
+//! and in how many scans.
+//!
+//! Scans, their signal samples, threats and connection attempts older than
+//! the retention period are pruned; the per-access-point summaries in
+//! `bssids` are kept.
+
+mod alert_queue;
+mod incidents;
+mod migrations;
+
+pub use alert_queue::QueuedAlert;
+
+use crate::structures::{DetectedThreat, Signal, WifiNetwork};
+use chrono::{DateTime, Utc};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use std::collections::{BTreeMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+const HISTORY_FILE: &str = "history.sqlite3";
+
+/// Days of scans and threats kept when no retention is configured.
+pub const DEFAULT_RETENTION_DAYS: u64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScanSource {
+    /// Run from the scanner page.
+    Manual,
+    Monitor,
+}
+
+impl ScanSource {
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::Manual => "manual",
+            Self::Monitor => "monitor",
+        }
+    }
+}
+
+/// What the history knows about one access point.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct BssidHistory {
+    pub bssid: String,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    /// Scans it showed up in.
+    pub times_seen: u64,
+    /// Scans taken since it was first seen, the first one included, as far
+    /// as they have not been pruned.
+    pub scans_since_first_seen: u64,
+    pub last_ssid: String,
+    /// Every SSID it has advertised, oldest first.
+    pub ssids: Vec<String>,
+    pub vendor: Option<String>,
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ConnectionAttempt {
+    pub attempted_at: DateTime<Utc>,
+    pub ssid: String,
+    pub succeeded: bool,
+    /// The backend's answer or error.
+    pub message: String,
+}
+
+/// When a scan was taken and what it found.
+pub type RecordedScan = (DateTime<Utc>, Vec<WifiNetwork>);
+
+pub struct HistoryStore {
+    conn: Connection,
+}
+
+pub type SharedHistory = Arc<Mutex<HistoryStore>>;
+
+fn db_error(e: rusqlite::Error) -> String {
+    format!("History database error: {}", e)
+}
+
+impl HistoryStore {
+    /// Open the database in `dir`, creating and migrating it as needed.
+    pub fn open(dir: &Path) -> Result<Self, String> {
+        std::fs::create_dir_all(dir)
+            .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+        let path = dir.join(HISTORY_FILE);
+        let conn = Connection::open(&path)
+            .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+        Self::with_connection(conn)
+    }
+
+    /// A history that is gone when the app exits.
+    pub fn in_memory() -> Result<Self, String> {
+        Self::with_connection(Connection::open_in_memory().map_err(db_error)?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self, String> {
+        conn.pragma_update(None, "foreign_keys", true)
+            .map_err(db_error)?;
+        migrations::migrate(&mut conn)?;
+        Ok(Self { conn })
+    }
+
+    /// Record one scan and every access point in it; returns the scan's id.
+    pub fn record_scan(
+        &mut self,
+        networks: &[WifiNetwork],
+        source: ScanSource,
+        at: DateTime<Utc>,
+    ) -> Result<i64, String> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        tx.execute(
+            "INSERT INTO scans (scanned_at, source, network_count) VALUES (?1, ?2, ?3)",
+            params![at, source.as_str(), networks.len() as i64],
+        )
+        .map_err(db_error)?;
+        let scan_id = tx.last_insert_rowid();
+
+        {
+            let mut upsert_bssid = tx
+                .prepare(
+                    "INSERT INTO bssids (bssid, first_seen, last_seen, times_seen, last_ssid, vendor)
+                     VALUES (?1, ?2, ?2, 1, ?3, ?4)
+                     ON CONFLICT (bssid) DO UPDATE SET
+                         last_seen = excluded.last_seen,
+                         times_seen = times_seen + 1,
+                         last_ssid = excluded.last_ssid,
+                         vendor = COALESCE(excluded.vendor, vendor)",
+                )
+                .map_err(db_error)?;
+            let mut insert_sample = tx
+                .prepare(
+                    "INSERT INTO signal_samples (scan_id, bssid, ssid, percent, dbm, channel, risk)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                )
+                .map_err(db_error)?;
+            let mut upsert_ssid = tx
+                .prepare(
+                    "INSERT INTO bssid_ssids (bssid, ssid, first_seen, last_seen)
+                     VALUES (?1, ?2, ?3, ?3)
+                     ON CONFLICT (bssid, ssid) DO UPDATE SET last_seen = excluded.last_seen",
+                )
+                .map_err(db_error)?;
+
+            // A BSSID listed twice in one scan is one sighting, under every
+            // SSID it was listed with
+            let mut recorded = HashSet::new();
+            for net in networks {
+                let bssid = net.bssid.trim().to_lowercase();
+                if bssid.is_empty() {
+                    continue;
+                }
+                if recorded.insert(bssid.clone()) {
+                    upsert_bssid
+                        .execute(params![bssid, at, net.ssid, net.vendor])
+                        .map_err(db_error)?;
+                    insert_sample
+                        .execute(params![
+                            scan_id,
+                            bssid,
+                            net.ssid,
+                            net.signal.map(|s| s.percent),
+                            net.signal.map(|s| s.dbm),
+                            net.channel,
+                            net.risk,
+                        ])
+                        .map_err(db_error)?;
+                }
+                upsert_ssid
+                    .execute(params![bssid, net.ssid, at])
+                    .map_err(db_error)?;
+            }
+        }
+
+        tx.commit().map_err(db_error)?;
+        Ok(scan_id)
+    }
+
+    pub fn record_threats(&mut self, threats: &[DetectedThreat]) -> Result<(), String> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        {
+            let mut insert = tx
+                .prepare(
+                    "INSERT INTO threats
+                         (detected_at, threat_type, severity, network_ssid, network_bssid, details)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                )
+                .map_err(db_error)?;
+            for threat in threats {
+                insert
+                    .execute(params![
+                        threat.timestamp,
+                        threat.threat_type,
+                        threat.severity,
+                        threat.network_ssid,
+                        threat.network_bssid,
+                        threat.details,
+                    ])
+                    .map_err(db_error)?;
+            }
+        }
+        tx.commit().map_err(db_error)
+    }
+
+    pub fn record_connection_attempt(
+        &mut self,
+        ssid: &str,
+        outcome: &Result<String, String>,
+        at: DateTime<Utc>,
+    ) -> Result<(), String> {
+        let (succeeded, message) = match outcome {
+            Ok(message) => (true, message),
+            Err(message) => (false, message),
+        };
+        self.conn
+            .execute(
+                "INSERT INTO connection_attempts (attempted_at, ssid, succeeded, message)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![at, ssid, succeeded, message],
+            )
+            .map(|_| ())
+            .map_err(db_error)
+    }
+
+    pub fn bssid_history(&self, bssid: &str) -> Result<Option<BssidHistory>, String> {
+        let row = self
+            .conn
+            .query_row(
+                "SELECT bssid, first_seen, last_seen, times_seen, last_ssid, vendor
+                 FROM bssids WHERE bssid = ?1",
+                [bssid.trim().to_lowercase()],
+                bssid_row,
+            )
+            .optional()
+            .map_err(db_error)?;
+        row.map(|history| self.complete(history)).transpose()
+    }
+
+    /// The `limit` access points seen most recently.
+    pub fn bssid_histories(&self, limit: usize) -> Result<Vec<BssidHistory>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT bssid, first_seen, last_seen, times_seen, last_ssid, vendor
+                 FROM bssids ORDER BY last_seen DESC, bssid LIMIT ?1",
+            )
+            .map_err(db_error)?;
+        let rows = stmt
+            .query_map([limit as i64], bssid_row)
+            .and_then(Iterator::collect::<Result<Vec<_>, _>>)
+            .map_err(db_error)?;
+        rows.into_iter().map(|h| self.complete(h)).collect()
+    }
+
+    /// Fill in what `bssid_row` leaves out.
+    fn complete(&self, mut history: BssidHistory) -> Result<BssidHistory, String> {
+        let mut stmt = self
+            .conn
+            .prepare_cached(
+                "SELECT ssid FROM bssid_ssids WHERE bssid = ?1 ORDER BY first_seen, ssid",
+            )
+            .map_err(db_error)?;
+        history.ssids = stmt
+            .query_map([&history.bssid], |row| row.get(0))
+            .and_then(Iterator::collect::<Result<Vec<String>, _>>)
+            .map_err(db_error)?;
+        history.scans_since_first_seen = self
+            .conn
+            .query_row(
+                "SELECT COUNT(*) FROM scans WHERE scanned_at >= ?1",
+                [history.first_seen],
+                |row| row.get(0),
+            )
+            .map_err(db_error)?;
+        Ok(history)
+    }
+
+    /// The `limit` most recent threats, newest first.
+    pub fn threats(&self, limit: usize) -> Result<Vec<DetectedThreat>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT threat_type, severity, network_ssid, network_bssid, details, detected_at
+                 FROM threats ORDER BY detected_at DESC, id DESC LIMIT ?1",
+            )
+            .map_err(db_error)?;
+        stmt.query_map([limit as i64], |row| {
+            Ok(DetectedThreat {
+                threat_type: row.get(0)?,
+                severity: row.get(1)?,
+                network_ssid: row.get(2)?,
+                network_bssid: row.get(3)?,
+                details: row.get(4)?,
+                timestamp: row.get(5)?,
+            })
+        })
+        .and_then(Iterator::collect)
+        .map_err(db_error)
+    }
+
+    /// The `limit` most recent connection attempts, newest first.
+    pub fn connection_attempts(&self, limit: usize) -> Result<Vec<ConnectionAttempt>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT attempted_at, ssid, succeeded, message
+                 FROM connection_attempts ORDER BY attempted_at DESC, id DESC LIMIT ?1",
+            )
+            .map_err(db_error)?;
+        stmt.query_map([limit as i64], |row| {
+            Ok(ConnectionAttempt {
+                attempted_at: row.get(0)?,
+                ssid: row.get(1)?,
+                succeeded: row.get(2)?,
+                message: row.get(3)?,
+            })
+        })
+        .and_then(Iterator::collect)
+        .map_err(db_error)
+    }
+
+    /// Delete scans, signal samples, threats and connection attempts from
+    /// before `before`; returns how many rows went.
+    pub fn prune(&mut self, before: DateTime<Utc>) -> Result<usize, String> {
+        let tx = self.conn.transaction().map_err(db_error)?;
+        let mut deleted = tx
+            .execute(
+                "DELETE FROM signal_samples
+                 WHERE scan_id IN (SELECT id FROM scans WHERE scanned_at < ?1)",
+                [before],
+            )
+            .map_err(db_error)?;
+        for statement in [
+            "DELETE FROM scans WHERE scanned_at < ?1",
+            "DELETE FROM threats WHERE detected_at < ?1",
+            "DELETE FROM connection_attempts WHERE attempted_at < ?1",
+        ] {
+            deleted += tx.execute(statement, [before]).map_err(db_error)?;
+        }
+        tx.commit().map_err(db_error)?;
+        Ok(deleted)
+    }
+
+    /// Scans taken after `since`, oldest first, with the SSID, BSSID and
+    /// signal of every access point in them.
+    pub fn scans_since(&self, since: DateTime<Utc>) -> Result<Vec<RecordedScan>, String> {
+        let mut stmt = self
+            .conn
+            .prepare(
+                "SELECT scans.id, scans.scanned_at, s.bssid, s.ssid, s.percent, s.dbm, s.channel
+                 FROM signal_samples s JOIN scans ON scans.id = s.scan_id
+                 WHERE scans.scanned_at > ?1
+                 ORDER BY scans.scanned_at, scans.id",
+            )
+            .map_err(db_error)?;
+        let mut rows = stmt.query([since]).map_err(db_error)?;
+
+        let mut scans: BTreeMap<(DateTime<Utc>, i64), Vec<WifiNetwork>> = BTreeMap::new();
+        while let Some(row) = rows.next().map_err(db_error)? {
+            let at: DateTime<Utc> = row.get(1).map_err(db_error)?;
+            let percent: Option<u8> = row.get(4).map_err(db_error)?;
+            let dbm: Option<i32> = row.get(5).map_err(db_error)?;
+            let signal = match (percent, dbm) {
+                (Some(percent), Some(dbm)) => Some(Signal::new(percent, dbm)),
+                (Some(percent), None) => Some(Signal::from_percent(percent)),
+                (None, Some(dbm)) => Some(Signal::from_dbm(dbm)),
+                (None, None) => None,
+            };
+            let network = WifiNetwork {
+                bssid: row.get(2).map_err(db_error)?,
+                ssid: row.get(3).map_err(db_error)?,
+                signal,
+                channel: row.get(6).map_err(db_error)?,
+                ..Default::default()
+            };
+            let id: i64 = row.get(0).map_err(db_error)?;
+            scans.entry((at, id)).or_default().push(network);
+        }
+        Ok(scans
+            .into_iter()
+            .map(|((at, _), networks)| (at, networks))
+            .collect())
+    }
+}
+
+/// A `bssids` row; `ssids` and `scans_since_first_seen` are left empty.
+fn bssid_row(row: &Row) -> rusqlite::Result<BssidHistory> {
+    Ok(BssidHistory {
+        bssid: row.get(0)?,
+        first_seen: row.get(1)?,
+        last_seen: row.get(2)?,
+        times_seen: row.get(3)?,
+        scans_since_first_seen: 0,
+        last_ssid: row.get(4)?,
+        ssids: Vec::new(),
+        vendor: row.get(5)?,
+    })
+}
+
+/// Open the history in `dir`. If that fails, history is kept in memory for
+/// this session only, so scanning still works.
+pub fn load_history(dir: &Path) -> SharedHistory {
+    let store = HistoryStore::open(dir).unwrap_or_else(|e| {
+        eprintln!("{}; keeping history in memory until restart", e);
+        HistoryStore::in_memory().expect("in-memory SQLite database")
+    });
+    Arc::new(Mutex::new(store))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{HistoryStore, ScanSource};
+    use crate::structures::{DetectedThreat, Signal, WifiNetwork};
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn net(ssid: &str, bssid: &str, percent: u8) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            signal: Some(Signal::from_percent(percent)),
+            channel: Some(6),
+            ..Default::default()
+        }
+    }
+
+    fn t(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    #[test]
+    fn test_first_and_last_seen() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        store
+            .record_scan(
+                &[net("Home", "AA:BB:CC:00:00:01", 80)],
+                ScanSource::Manual,
+                t(0),
+            )
+            .unwrap();
+        store
+            .record_scan(
+                &[
+                    net("Home", "aa:bb:cc:00:00:01", 75),
+                    net("Cafe", "00:11:22:33:44:55", 40),
+                ],
+                ScanSource::Monitor,
+                t(1),
+            )
+            .unwrap();
+        store.record_scan(&[], ScanSource::Monitor, t(2)).unwrap();
+        // Listed under two names in one scan
+        store
+            .record_scan(
+                &[
+                    net("Home", "aa:bb:cc:00:00:01", 70),
+                    net("Free WiFi", "aa:bb:cc:00:00:01", 70),
+                ],
+                ScanSource::Monitor,
+                t(3),
+            )
+            .unwrap();
+
+        let home = store.bssid_history("AA:BB:CC:00:00:01").unwrap().unwrap();
+        assert_eq!(home.first_seen, t(0));
+        assert_eq!(home.last_seen, t(3));
+        assert_eq!(home.times_seen, 3);
+        assert_eq!(home.scans_since_first_seen, 4);
+        assert_eq!(home.ssids, ["Home", "Free WiFi"]);
+
+        let cafe = store.bssid_history("00:11:22:33:44:55").unwrap().unwrap();
+        assert_eq!((cafe.first_seen, cafe.last_seen), (t(1), t(1)));
+        assert_eq!(cafe.times_seen, 1);
+        assert_eq!(cafe.scans_since_first_seen, 3);
+
+        assert_eq!(store.bssid_history("66:77:88:99:aa:bb").unwrap(), None);
+        let recent: Vec<String> = store
+            .bssid_histories(10)
+            .unwrap()
+            .into_iter()
+            .map(|h| h.bssid)
+            .collect();
+        assert_eq!(recent, ["aa:bb:cc:00:00:01", "00:11:22:33:44:55"]);
+    }
+
+    #[test]
+    fn test_threats_and_connection_attempts() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let threat = |minutes: i64, threat_type: &str| DetectedThreat {
+            threat_type: threat_type.to_string(),
+            severity: "High".to_string(),
+            network_ssid: "Office".to_string(),
+            network_bssid: "02:00:00:00:00:01".to_string(),
+            details: String::new(),
+            timestamp: t(minutes),
+        };
+        store
+            .record_threats(&[threat(0, "evil_twin"), threat(5, "rf_jamming")])
+            .unwrap();
+        store
+            .record_connection_attempt("Office", &Err("Wrong password".to_string()), t(1))
+            .unwrap();
+        store
+            .record_connection_attempt("Office", &Ok("Connected".to_string()), t(2))
+            .unwrap();
+
+        let threats = store.threats(1).unwrap();
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].threat_type, "rf_jamming");
+        assert_eq!(threats[0].timestamp, t(5));
+
+        let attempts = store.connection_attempts(10).unwrap();
+        assert_eq!(attempts.len(), 2);
+        assert!(attempts[0].succeeded);
+        assert_eq!(attempts[1].message, "Wrong password");
+    }
+
+    #[test]
+    fn test_survives_restart() {
+        let dir = std::env::temp_dir().join(format!("wids-history-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        let mut store = HistoryStore::open(&dir).unwrap();
+        for minute in 0..3 {
+            store
+                .record_scan(
+                    &[net("Home", "aa:bb:cc:00:00:01", 80 - minute as u8)],
+                    ScanSource::Monitor,
+                    t(minute),
+                )
+                .unwrap();
+        }
+        drop(store);
+
+        let store = HistoryStore::open(&dir).unwrap();
+        let home = store.bssid_history("aa:bb:cc:00:00:01").unwrap().unwrap();
+        assert_eq!(home.times_seen, 3);
+
+        let scans = store.scans_since(t(0)).unwrap();
+        assert_eq!(scans.len(), 2);
+        assert_eq!(scans[0].0, t(1));
+        assert_eq!(scans[1].1[0].signal.unwrap().percent, 78);
+        assert_eq!(scans[1].1[0].channel, Some(6));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_prune_keeps_recent_history() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        for minute in 0..4 {
+            store
+                .record_scan(
+                    &[net("Home", "aa:bb:cc:00:00:01", 80)],
+                    ScanSource::Monitor,
+                    t(minute),
+                )
+                .unwrap();
+        }
+        let threat = |minutes: i64| DetectedThreat {
+            threat_type: "evil_twin".to_string(),
+            severity: "High".to_string(),
+            network_ssid: "Home".to_string(),
+            network_bssid: "aa:bb:cc:00:00:01".to_string(),
+            details: String::new(),
+            timestamp: t(minutes),
+        };
+        store.record_threats(&[threat(0), threat(3)]).unwrap();
+        store
+            .record_connection_attempt("Home", &Ok("Connected".to_string()), t(1))
+            .unwrap();
+
+        // Scans 0 and 1 with their samples, one threat, one attempt
+        assert_eq!(store.prune(t(2)).unwrap(), 6);
+        assert_eq!(store.prune(t(2)).unwrap(), 0);
+
+        let scans = store.scans_since(t(-1)).unwrap();
+        assert_eq!(scans.iter().map(|s| s.0).collect::<Vec<_>>(), [t(2), t(3)]);
+        let threats = store.threats(10).unwrap();
+        assert_eq!(threats.len(), 1);
+        assert_eq!(threats[0].timestamp, t(3));
+        assert!(store.connection_attempts(10).unwrap().is_empty());
+
+        // The access point's summary outlives its pruned samples
+        let home = store.bssid_history("aa:bb:cc:00:00:01").unwrap().unwrap();
+        assert_eq!(home.first_seen, t(0));
+        assert_eq!(home.times_seen, 4);
+        assert_eq!(home.scans_since_first_seen, 2);
+    }
+}
@@ -0,0 +1,536 @@
+//! Decoding of 802.11 information elements from beacon and probe-response
+//! bodies.
+//!
+//! Elements come straight off the air, so nothing here trusts a length
+//! field: a truncated element ends the walk, and a malformed body leaves
+//! its field unset instead of failing the rest.
+
+mod rsn;
+
+pub use rsn::RsnInfo;
+
+const ID_SSID: u8 = 0;
+const ID_DS_PARAMETER_SET: u8 = 3;
+const ID_COUNTRY: u8 = 7;
+const ID_BSS_LOAD: u8 = 11;
+const ID_HT_CAPABILITIES: u8 = 45;
+const ID_RSN: u8 = 48;
+const ID_MOBILITY_DOMAIN: u8 = 54;
+const ID_HT_OPERATION: u8 = 61;
+const ID_RM_ENABLED_CAPABILITIES: u8 = 70;
+const ID_EXTENDED_CAPABILITIES: u8 = 127;
+const ID_VHT_CAPABILITIES: u8 = 191;
+const ID_VHT_OPERATION: u8 = 192;
+const ID_VENDOR_SPECIFIC: u8 = 221;
+const ID_EXTENSION: u8 = 255;
+
+const EXT_HE_CAPABILITIES: u8 = 35;
+const EXT_HE_OPERATION: u8 = 36;
+const EXT_EHT_OPERATION: u8 = 106;
+const EXT_EHT_CAPABILITIES: u8 = 108;
+
+const MICROSOFT_OUI: [u8; 3] = [0x00, 0x50, 0xf2];
+const VENDOR_TYPE_WPA: u8 = 1;
+const VENDOR_TYPE_WPS: u8 = 4;
+
+/// One element as found in the blob.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Element<'a> {
+    pub id: u8,
+    /// Element ID Extension, for elements with ID 255.
+    pub ext_id: Option<u8>,
+    pub data: &'a [u8],
+}
+
+/// Iterator over the elements of a blob; stops at the first element whose
+/// length runs past the end.
+pub struct Elements<'a> {
+    rest: &'a [u8],
+    truncated: bool,
+}
+
+impl<'a> Elements<'a> {
+    pub fn new(blob: &'a [u8]) -> Self {
+        Self {
+            rest: blob,
+            truncated: false,
+        }
+    }
+
+    pub fn truncated(&self) -> bool {
+        self.truncated
+    }
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = Element<'a>;
+
+    fn next(&mut self) -> Option<Element<'a>> {
+        if self.rest.is_empty() {
+            return None;
+        }
+        if self.rest.len() < 2 || self.rest.len() < 2 + self.rest[1] as usize {
+            self.truncated = true;
+            self.rest = &[];
+            return None;
+        }
+
+        let id = self.rest[0];
+        let body = &self.rest[2..2 + self.rest[1] as usize];
+        self.rest = &self.rest[2 + body.len()..];
+
+        Some(match (id, body.split_first()) {
+            (ID_EXTENSION, Some((ext_id, data))) => Element {
+                id,
+                ext_id: Some(*ext_id),
+                data,
+            },
+            _ => Element {
+                id,
+                ext_id: None,
+                data: body,
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct HtOperation {
+    pub primary_channel: u8,
+    /// 0 = none, 1 = above, 3 = below.
+    pub secondary_channel_offset: u8,
+    pub any_channel_width: bool,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct VhtOperation {
+    /// 0 = 20/40 MHz, 1 = 80/160/80+80 MHz, 2 = 160 MHz, 3 = 80+80 MHz.
+    pub channel_width: u8,
+    pub center_segment0: u8,
+    pub center_segment1: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Country {
+    pub code: String,
+    /// Third octet: ' ' any, 'O' outdoor, 'I' indoor, 'X' non-country.
+    pub environment: u8,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct BssLoad {
+    pub station_count: u16,
+    /// Share of time the medium was busy, out of 255.
+    pub channel_utilization: u8,
+    pub available_admission_capacity: u16,
+}
+
+/// 802.11r Mobility Domain.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MobilityDomain {
+    pub mdid: u16,
+    pub ft_over_ds: bool,
+    pub resource_request: bool,
+}
+
+/// 802.11k RM Enabled Capabilities, the bits the scanner cares about.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RmCapabilities {
+    pub link_measurement: bool,
+    pub neighbor_report: bool,
+    pub beacon_passive: bool,
+    pub beacon_active: bool,
+    pub beacon_table: bool,
+}
+
+/// Extended Capabilities, with the 802.11v bits broken out.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtendedCapabilities {
+    pub proxy_arp: bool,
+    pub wnm_sleep_mode: bool,
+    pub bss_transition: bool,
+    pub multiple_bssid: bool,
+    pub interworking: bool,
+    pub raw: Vec<u8>,
+}
+
+impl ExtendedCapabilities {
+    fn parse(data: &[u8]) -> Self {
+        let bit = |n: usize| {
+            data.get(n / 8)
+                .map(|b| b & (1 << (n % 8)) != 0)
+                .unwrap_or(false)
+        };
+        Self {
+            proxy_arp: bit(12),
+            wnm_sleep_mode: bit(17),
+            bss_transition: bit(19),
+            multiple_bssid: bit(22),
+            interworking: bit(31),
+            raw: data.to_vec(),
+        }
+    }
+}
+
+/// Everything decoded from one IE blob.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InformationElements {
+    pub ssid: Option<Vec<u8>>,
+    pub ds_channel: Option<u8>,
+    pub country: Option<Country>,
+    pub bss_load: Option<BssLoad>,
+    pub rsn: Option<RsnInfo>,
+    pub wpa: Option<RsnInfo>,
+    pub wps: bool,
+    pub ht_capabilities: bool,
+    pub ht_operation: Option<HtOperation>,
+    pub vht_capabilities: bool,
+    pub vht_operation: Option<VhtOperation>,
+    pub he_capabilities: bool,
+    pub he_operation: bool,
+    pub eht_capabilities: bool,
+    pub eht_operation: bool,
+    pub mobility_domain: Option<MobilityDomain>,
+    pub rm_capabilities: Option<RmCapabilities>,
+    pub extended_capabilities: Option<ExtendedCapabilities>,
+    /// OUIs of all vendor-specific elements, in order of appearance.
+    pub vendor_ouis: Vec<[u8; 3]>,
+    /// The blob ended inside an element.
+    pub truncated: bool,
+}
+
+impl InformationElements {
+    /// Operating width in MHz, from VHT operation if present, else HT.
+    pub fn channel_width(&self) -> Option<u8> {
+        if let Some(vht) = &self.vht_operation {
+            match vht.channel_width {
+                1 if vht.center_segment1 != 0 => return Some(160),
+                1 => return Some(80),
+                2 | 3 => return Some(160),
+                _ => {}
+            }
+        }
+
+        self.ht_operation.as_ref().map(|ht| {
+            if ht.any_channel_width && ht.secondary_channel_offset != 0 {
+                40
+            } else {
+                20
+            }
+        })
+    }
+
+    /// Primary channel, from DS parameter set or HT operation.
+    pub fn channel(&self) -> Option<u8> {
+        self.ds_channel
+            .or_else(|| self.ht_operation.as_ref().map(|ht| ht.primary_channel))
+    }
+}
+
+pub fn parse(blob: &[u8]) -> InformationElements {
+    let mut ies = InformationElements::default();
+    let mut elements = Elements::new(blob);
+
+    for element in elements.by_ref() {
+        let data = element.data;
+        match (element.id, element.ext_id) {
+            (ID_SSID, _) if data.len() <= 32 => ies.ssid = Some(data.to_vec()),
+            (ID_DS_PARAMETER_SET, _) => ies.ds_channel = data.first().copied(),
+            (ID_COUNTRY, _) if data.len() >= 3 => {
+                ies.country = Some(Country {
+                    code: String::from_utf8_lossy(&data[..2]).to_string(),
+                    environment: data[2],
+                })
+            }
+            (ID_BSS_LOAD, _) => {
+                let mut r = Reader::new(data);
+                if let (Some(stations), Some(utilization), Some(capacity)) =
+                    (r.u16(), r.u8(), r.u16())
+                {
+                    ies.bss_load = Some(BssLoad {
+                        station_count: stations,
+                        channel_utilization: utilization,
+                        available_admission_capacity: capacity,
+                    });
+                }
+            }
+            (ID_HT_CAPABILITIES, _) => ies.ht_capabilities = true,
+            (ID_RSN, _) => ies.rsn = rsn::parse_rsn(data),
+            (ID_MOBILITY_DOMAIN, _) if data.len() >= 3 => {
+                ies.mobility_domain = Some(MobilityDomain {
+                    mdid: u16::from_le_bytes([data[0], data[1]]),
+                    ft_over_ds: data[2] & 0x01 != 0,
+                    resource_request: data[2] & 0x02 != 0,
+                })
+            }
+            (ID_HT_OPERATION, _) if data.len() >= 2 => {
+                ies.ht_operation = Some(HtOperation {
+                    primary_channel: data[0],
+                    secondary_channel_offset: data[1] & 0x03,
+                    any_channel_width: data[1] & 0x04 != 0,
+                })
+            }
+            (ID_RM_ENABLED_CAPABILITIES, _) if !data.is_empty() => {
+                ies.rm_capabilities = Some(RmCapabilities {
+                    link_measurement: data[0] & 0x01 != 0,
+                    neighbor_report: data[0] & 0x02 != 0,
+                    beacon_passive: data[0] & 0x10 != 0,
+                    beacon_active: data[0] & 0x20 != 0,
+                    beacon_table: data[0] & 0x40 != 0,
+                })
+            }
+            (ID_EXTENDED_CAPABILITIES, _) => {
+                ies.extended_capabilities = Some(ExtendedCapabilities::parse(data))
+            }
+            (ID_VHT_CAPABILITIES, _) => ies.vht_capabilities = true,
+            (ID_VHT_OPERATION, _) if data.len() >= 3 => {
+                ies.vht_operation = Some(VhtOperation {
+                    channel_width: data[0],
+                    center_segment0: data[1],
+                    center_segment1: data[2],
+                })
+            }
+            (ID_VENDOR_SPECIFIC, _) if data.len() >= 3 => {
+                let oui = [data[0], data[1], data[2]];
+                ies.vendor_ouis.push(oui);
+
+                if oui == MICROSOFT_OUI {
+                    match data.get(3) {
+                        Some(&VENDOR_TYPE_WPA) => ies.wpa = rsn::parse_rsn(&data[4..]),
+                        Some(&VENDOR_TYPE_WPS) => ies.wps = true,
+                        _ => {}
+                    }
+                }
+            }
+            (ID_EXTENSION, Some(EXT_HE_CAPABILITIES)) => ies.he_capabilities = true,
+            (ID_EXTENSION, Some(EXT_HE_OPERATION)) => ies.he_operation = true,
+            (ID_EXTENSION, Some(EXT_EHT_CAPABILITIES)) => ies.eht_capabilities = true,
+            (ID_EXTENSION, Some(EXT_EHT_OPERATION)) => ies.eht_operation = true,
+            _ => {}
+        }
+    }
+
+    ies.truncated = elements.truncated();
+    ies
+}
+
+/// Bounds-checked little-endian cursor.
+struct Reader<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn take(&mut self, n: usize) -> Option<&'a [u8]> {
+        if self.data.len() < n {
+            return None;
+        }
+        let (head, tail) = self.data.split_at(n);
+        self.data = tail;
+        Some(head)
+    }
+
+    fn skip(&mut self, n: usize) -> Option<()> {
+        self.take(n).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::rsn::{AkmSuite, CipherSuite};
+    use super::*;
+
+    /// Beacon body of a WPA2/WPA3 transition AP on channel 36 with 80 MHz VHT.
+    fn sample() -> Vec<u8> {
+        let mut blob = vec![];
+        // SSID
+        blob.extend([0, 7]);
+        blob.extend(b"HomeNet");
+        // DS parameter set
+        blob.extend([3, 1, 36]);
+        // Country DE, any environment
+        blob.extend([7, 6, b'D', b'E', b' ', 36, 4, 23]);
+        // BSS load: 3 stations, 22/255 busy, 31250 capacity
+        blob.extend([11, 5, 3, 0, 22, 0x12, 0x7a]);
+        // HT capabilities (contents ignored)
+        blob.extend([45, 2, 0xef, 0x01]);
+        // RSN: CCMP group, CCMP pairwise, PSK + SAE, MFP capable
+        blob.extend([
+            48, 24, 1, 0, 0x00, 0x0f, 0xac, 4, 1, 0, 0x00, 0x0f, 0xac, 4, 2, 0,
+        ]);
+        blob.extend([0x00, 0x0f, 0xac, 2, 0x00, 0x0f, 0xac, 8, 0x80, 0]);
+        // Mobility domain 0x1234, FT over DS
+        blob.extend([54, 3, 0x34, 0x12, 0x01]);
+        // HT operation: primary 36, secondary above, any width
+        blob.extend([61, 3, 36, 0x05, 0]);
+        // RM enabled: link measurement, neighbor report, beacon passive/active
+        blob.extend([70, 5, 0x33, 0, 0, 0, 0]);
+        // Extended capabilities with BSS transition (bit 19)
+        blob.extend([127, 3, 0, 0, 0x08]);
+        // VHT capabilities and operation: 80 MHz centred on 42
+        blob.extend([191, 2, 0, 0]);
+        blob.extend([192, 5, 1, 42, 0, 0, 0]);
+        // HE capabilities (extension 35)
+        blob.extend([255, 2, 35, 0]);
+        // WPS vendor element
+        blob.extend([221, 5, 0x00, 0x50, 0xf2, 4, 0x10]);
+        // Some other vendor
+        blob.extend([221, 4, 0x00, 0x10, 0x18, 2]);
+        blob
+    }
+
+    #[test]
+    fn test_parses_sample_beacon() {
+        let ies = parse(&sample());
+
+        assert_eq!(ies.ssid.as_deref(), Some(&b"HomeNet"[..]));
+        assert_eq!(ies.channel(), Some(36));
+        assert_eq!(ies.country.as_ref().unwrap().code, "DE");
+        assert_eq!(ies.bss_load.as_ref().unwrap().station_count, 3);
+        assert_eq!(
+            ies.bss_load.as_ref().unwrap().available_admission_capacity,
+            31250
+        );
+        assert!(ies.ht_capabilities && ies.vht_capabilities && ies.he_capabilities);
+        assert_eq!(ies.channel_width(), Some(80));
+        assert_eq!(ies.mobility_domain.as_ref().unwrap().mdid, 0x1234);
+        assert!(ies.mobility_domain.as_ref().unwrap().ft_over_ds);
+        assert!(ies.rm_capabilities.as_ref().unwrap().neighbor_report);
+        assert!(!ies.rm_capabilities.as_ref().unwrap().beacon_table);
+        assert!(ies.extended_capabilities.as_ref().unwrap().bss_transition);
+        assert!(ies.wps);
+        assert_eq!(
+            ies.vendor_ouis,
+            vec![[0x00, 0x50, 0xf2], [0x00, 0x10, 0x18]]
+        );
+        assert!(!ies.truncated);
+    }
+
+    #[test]
+    fn test_rsn_suites_and_pmf() {
+        let rsn = parse(&sample()).rsn.unwrap();
+
+        assert_eq!(rsn.version, 1);
+        assert_eq!(rsn.group_cipher, Some(CipherSuite::Ccmp128));
+        assert_eq!(rsn.pairwise_ciphers, vec![CipherSuite::Ccmp128]);
+        assert_eq!(rsn.akm_suites, vec![AkmSuite::Psk, AkmSuite::Sae]);
+        assert!(rsn.pmf_capable());
+        assert!(!rsn.pmf_required());
+        assert!(!rsn.truncated);
+    }
+
+    #[test]
+    fn test_wpa_vendor_element() {
+        let blob = [
+            221, 22, 0x00, 0x50, 0xf2, 1, 1, 0, 0x00, 0x50, 0xf2, 2, 1, 0, 0x00, 0x50, 0xf2, 2, 1,
+            0, 0x00, 0x50, 0xf2, 2,
+        ];
+        let wpa = parse(&blob).wpa.unwrap();
+
+        assert_eq!(wpa.group_cipher, Some(CipherSuite::Tkip));
+        assert_eq!(wpa.pairwise_ciphers, vec![CipherSuite::Tkip]);
+        assert_eq!(wpa.akm_suites, vec![AkmSuite::Psk]);
+        assert_eq!(wpa.akm_suites[0].name(), "PSK");
+    }
+
+    #[test]
+    fn test_rsn_stops_at_short_suite_list() {
+        // Claims two pairwise suites but carries one.
+        let blob = [48, 12, 1, 0, 0x00, 0x0f, 0xac, 4, 2, 0, 0x00, 0x0f, 0xac, 4];
+        let rsn = parse(&blob).rsn.unwrap();
+
+        assert_eq!(rsn.pairwise_ciphers, vec![CipherSuite::Ccmp128]);
+        assert!(rsn.akm_suites.is_empty());
+        assert!(rsn.truncated);
+    }
+
+    #[test]
+    fn test_truncated_element_ends_walk() {
+        let mut blob = sample();
+        blob.extend([48, 40, 1, 0]);
+        let ies = parse(&blob);
+
+        assert!(ies.truncated);
+        // Everything before the cut is kept.
+        assert!(ies.wps);
+    }
+
+    #[test]
+    fn test_every_truncation_of_sample() {
+        let blob = sample();
+        for len in 0..=blob.len() {
+            let ies = parse(&blob[..len]);
+            if let Some(rsn) = &ies.rsn {
+                assert!(rsn.pairwise_ciphers.len() <= 1);
+            }
+        }
+    }
+
+    #[test]
+    fn test_every_single_byte_corruption() {
+        let blob = sample();
+        for i in 0..blob.len() {
+            for value in [0x00, 0x01, 0x7f, 0x80, 0xfe, 0xff] {
+                let mut corrupted = blob.clone();
+                corrupted[i] = value;
+                parse(&corrupted);
+            }
+        }
+    }
+
+    #[test]
+    fn test_random_blobs_do_not_panic() {
+        // xorshift keeps the corpus reproducible without a dependency.
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..5000 {
+            let len = (next() % 300) as usize;
+            let mut blob: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+            // Bias towards interesting element IDs so bodies get decoded.
+            if let Some(first) = blob.first_mut() {
+                *first = [0, 3, 7, 11, 48, 54, 61, 70, 127, 192, 221, 255][*first as usize % 12];
+            }
+            let ies = parse(&blob);
+            let _ = ies.channel_width();
+            let _ = ies.channel();
+        }
+    }
+
+    #[test]
+    fn test_malformed_bodies_are_ignored() {
+        let blob = [
+            0, 40, // SSID longer than 32 bytes is rejected
+            b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x',
+            b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x',
+            b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', b'x', 7, 1,
+            b'D', // short country
+            61, 1, 36, // short HT operation
+            48, 1, 1, // RSN without a full version
+            255, 0, // extension without an ID
+        ];
+        let ies = parse(&blob);
+
+        assert_eq!(ies.ssid, None);
+        assert_eq!(ies.country, None);
+        assert_eq!(ies.ht_operation, None);
+        assert_eq!(ies.rsn, None);
+        assert!(!ies.truncated);
+    }
+}
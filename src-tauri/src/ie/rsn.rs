@@ -0,0 +1,252 @@
+use super::Reader;
+
+/// Cipher suite selector from an RSN or WPA element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CipherSuite {
+    UseGroup,
+    Wep40,
+    Tkip,
+    Ccmp128,
+    Wep104,
+    BipCmac128,
+    GroupNotAllowed,
+    Gcmp128,
+    Gcmp256,
+    Ccmp256,
+    BipGmac128,
+    BipGmac256,
+    BipCmac256,
+    Other([u8; 4]),
+}
+
+impl CipherSuite {
+    fn from_selector(oui: [u8; 3], suite_type: u8) -> Self {
+        if oui != IEEE_OUI && oui != MICROSOFT_OUI {
+            return Self::Other([oui[0], oui[1], oui[2], suite_type]);
+        }
+        match suite_type {
+            0 => Self::UseGroup,
+            1 => Self::Wep40,
+            2 => Self::Tkip,
+            4 => Self::Ccmp128,
+            5 => Self::Wep104,
+            6 => Self::BipCmac128,
+            7 => Self::GroupNotAllowed,
+            8 => Self::Gcmp128,
+            9 => Self::Gcmp256,
+            10 => Self::Ccmp256,
+            11 => Self::BipGmac128,
+            12 => Self::BipGmac256,
+            13 => Self::BipCmac256,
+            _ => Self::Other([oui[0], oui[1], oui[2], suite_type]),
+        }
+    }
+
+    /// Name as printed by `iw`, so IE-derived and iw-derived suites compare equal.
+    pub fn name(&self) -> String {
+        match self {
+            Self::UseGroup => "Use group cipher suite".to_string(),
+            Self::Wep40 => "WEP-40".to_string(),
+            Self::Tkip => "TKIP".to_string(),
+            Self::Ccmp128 => "CCMP".to_string(),
+            Self::Wep104 => "WEP-104".to_string(),
+            Self::BipCmac128 => "AES-128-CMAC".to_string(),
+            Self::GroupNotAllowed => "NO-GROUP".to_string(),
+            Self::Gcmp128 => "GCMP-128".to_string(),
+            Self::Gcmp256 => "GCMP-256".to_string(),
+            Self::Ccmp256 => "CCMP-256".to_string(),
+            Self::BipGmac128 => "BIP-GMAC-128".to_string(),
+            Self::BipGmac256 => "BIP-GMAC-256".to_string(),
+            Self::BipCmac256 => "BIP-CMAC-256".to_string(),
+            Self::Other(s) => format!("{:02x}-{:02x}-{:02x}:{}", s[0], s[1], s[2], s[3]),
+        }
+    }
+}
+
+/// Authentication and key management suite selector.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AkmSuite {
+    Ieee8021x,
+    Psk,
+    FtIeee8021x,
+    FtPsk,
+    Ieee8021xSha256,
+    PskSha256,
+    Tdls,
+    Sae,
+    FtSae,
+    ApPeerKey,
+    SuiteB,
+    SuiteB192,
+    FtIeee8021xSha384,
+    FilsSha256,
+    FilsSha384,
+    FtFilsSha256,
+    FtFilsSha384,
+    Owe,
+    FtPskSha384,
+    PskSha384,
+    SaeExtKey,
+    FtSaeExtKey,
+    Other([u8; 4]),
+}
+
+impl AkmSuite {
+    fn from_selector(oui: [u8; 3], suite_type: u8) -> Self {
+        if oui != IEEE_OUI && oui != MICROSOFT_OUI {
+            return Self::Other([oui[0], oui[1], oui[2], suite_type]);
+        }
+        match suite_type {
+            1 => Self::Ieee8021x,
+            2 => Self::Psk,
+            3 => Self::FtIeee8021x,
+            4 => Self::FtPsk,
+            5 => Self::Ieee8021xSha256,
+            6 => Self::PskSha256,
+            7 => Self::Tdls,
+            8 => Self::Sae,
+            9 => Self::FtSae,
+            10 => Self::ApPeerKey,
+            11 => Self::SuiteB,
+            12 => Self::SuiteB192,
+            13 => Self::FtIeee8021xSha384,
+            14 => Self::FilsSha256,
+            15 => Self::FilsSha384,
+            16 => Self::FtFilsSha256,
+            17 => Self::FtFilsSha384,
+            18 => Self::Owe,
+            19 => Self::FtPskSha384,
+            20 => Self::PskSha384,
+            24 => Self::SaeExtKey,
+            25 => Self::FtSaeExtKey,
+            _ => Self::Other([oui[0], oui[1], oui[2], suite_type]),
+        }
+    }
+
+    /// Name as printed by `iw`.
+    pub fn name(&self) -> String {
+        match self {
+            Self::Ieee8021x => "IEEE 802.1X".to_string(),
+            Self::Psk => "PSK".to_string(),
+            Self::FtIeee8021x => "FT/IEEE 802.1X".to_string(),
+            Self::FtPsk => "FT/PSK".to_string(),
+            Self::Ieee8021xSha256 => "IEEE 802.1X/SHA-256".to_string(),
+            Self::PskSha256 => "PSK/SHA-256".to_string(),
+            Self::Tdls => "TDLS/TPK".to_string(),
+            Self::Sae => "SAE".to_string(),
+            Self::FtSae => "FT/SAE".to_string(),
+            Self::ApPeerKey => "AP/PEER".to_string(),
+            Self::SuiteB => "IEEE 802.1X/SUITE-B".to_string(),
+            Self::SuiteB192 => "IEEE 802.1X/SUITE-B-192".to_string(),
+            Self::FtIeee8021xSha384 => "FT/IEEE 802.1X/SHA-384".to_string(),
+            Self::FilsSha256 => "FILS/SHA-256".to_string(),
+            Self::FilsSha384 => "FILS/SHA-384".to_string(),
+            Self::FtFilsSha256 => "FT/FILS/SHA-256".to_string(),
+            Self::FtFilsSha384 => "FT/FILS/SHA-384".to_string(),
+            Self::Owe => "OWE".to_string(),
+            Self::FtPskSha384 => "FT/PSK/SHA-384".to_string(),
+            Self::PskSha384 => "PSK/SHA-384".to_string(),
+            Self::SaeExtKey => "SAE-EXT-KEY".to_string(),
+            Self::FtSaeExtKey => "FT/SAE-EXT-KEY".to_string(),
+            Self::Other(s) => format!("{:02x}-{:02x}-{:02x}:{}", s[0], s[1], s[2], s[3]),
+        }
+    }
+}
+
+const IEEE_OUI: [u8; 3] = [0x00, 0x0f, 0xac];
+const MICROSOFT_OUI: [u8; 3] = [0x00, 0x50, 0xf2];
+
+/// Contents of an RSN element or of the legacy WPA vendor element.
+///
+/// Every field after the version is optional on the air; a field missing
+/// from a short element is left empty rather than failing the whole element.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct RsnInfo {
+    pub version: u16,
+    pub group_cipher: Option<CipherSuite>,
+    pub pairwise_ciphers: Vec<CipherSuite>,
+    pub akm_suites: Vec<AkmSuite>,
+    pub capabilities: Option<u16>,
+    pub group_management_cipher: Option<CipherSuite>,
+    /// A suite count ran past the end of the element.
+    pub truncated: bool,
+}
+
+impl RsnInfo {
+    pub fn pmf_required(&self) -> bool {
+        self.capabilities.map(|c| c & 0x0040 != 0).unwrap_or(false)
+    }
+
+    pub fn pmf_capable(&self) -> bool {
+        self.capabilities.map(|c| c & 0x0080 != 0).unwrap_or(false)
+    }
+}
+
+/// Parse the body of an RSN element, or of a WPA element after its
+/// OUI and type bytes; both share the layout.
+pub fn parse_rsn(data: &[u8]) -> Option<RsnInfo> {
+    let mut reader = Reader::new(data);
+    let mut info = RsnInfo {
+        version: reader.u16()?,
+        ..Default::default()
+    };
+
+    let Some(group) = read_selector(&mut reader) else {
+        return Some(info);
+    };
+    info.group_cipher = Some(CipherSuite::from_selector(group.0, group.1));
+
+    match read_suites(&mut reader, CipherSuite::from_selector) {
+        Some((suites, complete)) => {
+            info.pairwise_ciphers = suites;
+            if !complete {
+                info.truncated = true;
+                return Some(info);
+            }
+        }
+        None => return Some(info),
+    }
+
+    match read_suites(&mut reader, AkmSuite::from_selector) {
+        Some((suites, complete)) => {
+            info.akm_suites = suites;
+            if !complete {
+                info.truncated = true;
+                return Some(info);
+            }
+        }
+        None => return Some(info),
+    }
+
+    info.capabilities = reader.u16();
+
+    // PMKID list, then the group management cipher.
+    if let Some(pmkid_count) = reader.u16() {
+        if reader.skip(pmkid_count as usize * 16).is_none() {
+            info.truncated = true;
+            return Some(info);
+        }
+        info.group_management_cipher =
+            read_selector(&mut reader).map(|(oui, t)| CipherSuite::from_selector(oui, t));
+    }
+
+    Some(info)
+}
+
+fn read_selector(reader: &mut Reader) -> Option<([u8; 3], u8)> {
+    let bytes = reader.take(4)?;
+    Some(([bytes[0], bytes[1], bytes[2]], bytes[3]))
+}
+
+/// Read a counted suite list. The flag is false when the list was cut short.
+fn read_suites<T>(reader: &mut Reader, convert: fn([u8; 3], u8) -> T) -> Option<(Vec<T>, bool)> {
+    let count = reader.u16()? as usize;
+    let mut suites = Vec::new();
+    for _ in 0..count {
+        match read_selector(reader) {
+            Some((oui, suite_type)) => suites.push(convert(oui, suite_type)),
+            None => return Some((suites, false)),
+        }
+    }
+    Some((suites, true))
+}
@@ -0,0 +1,388 @@
+//! Incidents: the monitor reports the same threat on every scan for as long
+//! as a rogue AP stays up, so threats are folded into incidents keyed by a
+//! fingerprint of type, BSSID and SSID.
+//!
+//! An incident is open until the user acknowledges or resolves it, or until
+//! its threat has not been seen for `expire_after` and it expires. Only the
+//! first occurrence and escalations are worth telling anyone about; repeats
+//! just bump `occurrences` and `last_seen`.
+//!
+//! Incidents about the same network (the SSID, or the BSSID when the SSID is
+//! hidden) share a group while they are active, so an evil twin and a deauth
+//! burst on the same SSID show up together.
+
+use crate::history::HistoryStore;
+use crate::structures::DetectedThreat;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+/// How long an incident stays active without its threat being seen.
+pub const DEFAULT_EXPIRY_MINUTES: u64 = 30;
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum IncidentState {
+    Open,
+    Acknowledged,
+    Resolved,
+    /// Closed because the threat was not seen for a while.
+    Expired,
+}
+
+impl IncidentState {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Acknowledged => "acknowledged",
+            Self::Resolved => "resolved",
+            Self::Expired => "expired",
+        }
+    }
+
+    pub fn parse(state: &str) -> Option<Self> {
+        [
+            Self::Open,
+            Self::Acknowledged,
+            Self::Resolved,
+            Self::Expired,
+        ]
+        .into_iter()
+        .find(|s| s.as_str() == state)
+    }
+
+    pub fn is_active(self) -> bool {
+        matches!(self, Self::Open | Self::Acknowledged)
+    }
+}
+
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct Incident {
+    pub id: i64,
+    pub fingerprint: String,
+    /// Id of the first incident in its group.
+    pub group_id: i64,
+    /// The network it is about; see `subject`.
+    pub subject: String,
+    pub threat_type: String,
+    /// The highest severity seen so far.
+    pub severity: String,
+    pub network_ssid: String,
+    pub network_bssid: String,
+    /// Details of the latest occurrence.
+    pub details: String,
+    pub state: IncidentState,
+    pub first_seen: DateTime<Utc>,
+    pub last_seen: DateTime<Utc>,
+    pub occurrences: u64,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+    /// When it was resolved or expired.
+    pub closed_at: Option<DateTime<Utc>>,
+}
+
+/// Related incidents, most recently seen first.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct IncidentGroup {
+    pub id: i64,
+    pub subject: String,
+    /// The highest severity among its incidents.
+    pub severity: String,
+    pub last_seen: DateTime<Utc>,
+    pub incidents: Vec<Incident>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Opened,
+    /// Seen again at a higher severity.
+    Escalated,
+    Repeated,
+}
+
+impl Change {
+    /// Whether the threat behind it should be reported again.
+    pub fn is_news(self) -> bool {
+        self != Self::Repeated
+    }
+}
+
+pub fn fingerprint(threat: &DetectedThreat) -> String {
+    format!(
+        "{}|{}|{}",
+        threat.threat_type,
+        threat.network_bssid.to_lowercase(),
+        threat.network_ssid
+    )
+}
+
+/// The network a threat is about, for grouping. Threats spanning many
+/// networks group by type.
+pub fn subject(threat: &DetectedThreat) -> String {
+    let named = |s: &str| !s.is_empty() && s != "Multiple" && s != "Hidden";
+    if named(&threat.network_ssid) {
+        threat.network_ssid.clone()
+    } else if named(&threat.network_bssid) {
+        threat.network_bssid.to_lowercase()
+    } else {
+        threat.threat_type.clone()
+    }
+}
+
+fn severity_rank(severity: &str) -> u8 {
+    match severity {
+        "Critical" => 4,
+        "High" => 3,
+        "Medium" => 2,
+        "Low" => 1,
+        _ => 0,
+    }
+}
+
+/// Fold `threats` into incidents, first expiring those not seen since
+/// `now - expire_after`. Returns each threat's incident and what changed.
+pub fn record(
+    history: &mut HistoryStore,
+    threats: &[DetectedThreat],
+    now: DateTime<Utc>,
+    expire_after: Duration,
+) -> Result<Vec<(Incident, Change)>, String> {
+    history.expire_incidents(now - expire_after, now)?;
+
+    let mut changes = Vec::with_capacity(threats.len());
+    for threat in threats {
+        let fingerprint = fingerprint(threat);
+        let change = match history.active_incident(&fingerprint)? {
+            Some(incident) => {
+                let escalate = severity_rank(&threat.severity) > severity_rank(&incident.severity);
+                let incident = history.repeat_incident(incident.id, threat, escalate)?;
+                let change = if escalate {
+                    Change::Escalated
+                } else {
+                    Change::Repeated
+                };
+                (incident, change)
+            }
+            None => {
+                let subject = subject(threat);
+                let group = history.active_group(&subject)?;
+                let incident = history.open_incident(&fingerprint, &subject, group, threat)?;
+                (incident, Change::Opened)
+            }
+        };
+        changes.push(change);
+    }
+    Ok(changes)
+}
+
+fn active_incident(history: &HistoryStore, id: i64) -> Result<Incident, String> {
+    let incident = history
+        .incident(id)?
+        .ok_or_else(|| format!("Incident {} not found", id))?;
+    if !incident.state.is_active() {
+        return Err(format!(
+            "Incident {} is already {}",
+            id,
+            incident.state.as_str()
+        ));
+    }
+    Ok(incident)
+}
+
+/// Mark an open incident as seen by the user. It stays active, so repeats
+/// still count towards it without being reported again.
+pub fn acknowledge(
+    history: &mut HistoryStore,
+    id: i64,
+    at: DateTime<Utc>,
+) -> Result<Incident, String> {
+    let incident = active_incident(history, id)?;
+    if incident.state == IncidentState::Open {
+        history.acknowledge_incident(id, at)?;
+    }
+    history
+        .incident(id)?
+        .ok_or_else(|| format!("Incident {} not found", id))
+}
+
+/// Close an active incident; if the threat comes back it opens a new one.
+pub fn resolve(history: &mut HistoryStore, id: i64, at: DateTime<Utc>) -> Result<Incident, String> {
+    active_incident(history, id)?;
+    history.close_incident(id, IncidentState::Resolved, at)?;
+    history
+        .incident(id)?
+        .ok_or_else(|| format!("Incident {} not found", id))
+}
+
+/// Group `incidents`, keeping their order, each group placed where its
+/// first incident was.
+pub fn group(incidents: Vec<Incident>) -> Vec<IncidentGroup> {
+    let mut groups: Vec<IncidentGroup> = Vec::new();
+    for incident in incidents {
+        match groups.iter_mut().find(|g| g.id == incident.group_id) {
+            Some(group) => {
+                if severity_rank(&incident.severity) > severity_rank(&group.severity) {
+                    group.severity = incident.severity.clone();
+                }
+                group.last_seen = group.last_seen.max(incident.last_seen);
+                group.incidents.push(incident);
+            }
+            None => groups.push(IncidentGroup {
+                id: incident.group_id,
+                subject: incident.subject.clone(),
+                severity: incident.severity.clone(),
+                last_seen: incident.last_seen,
+                incidents: vec![incident],
+            }),
+        }
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{acknowledge, group, record, resolve, Change, IncidentState};
+    use crate::history::HistoryStore;
+    use crate::structures::DetectedThreat;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn threat(
+        threat_type: &str,
+        severity: &str,
+        ssid: &str,
+        bssid: &str,
+        at: DateTime<Utc>,
+    ) -> DetectedThreat {
+        DetectedThreat {
+            threat_type: threat_type.to_string(),
+            severity: severity.to_string(),
+            network_ssid: ssid.to_string(),
+            network_bssid: bssid.to_string(),
+            details: format!("seen at {}", at.format("%H:%M")),
+            timestamp: at,
+        }
+    }
+
+    fn t0() -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap()
+    }
+
+    fn changes(
+        store: &mut HistoryStore,
+        threats: &[DetectedThreat],
+        now: DateTime<Utc>,
+    ) -> Vec<Change> {
+        record(store, threats, now, Duration::minutes(30))
+            .unwrap()
+            .into_iter()
+            .map(|(_, change)| change)
+            .collect()
+    }
+
+    #[test]
+    fn test_repeats_fold_into_one_incident() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let mut seen = Vec::new();
+        for minute in 0..3 {
+            let at = t0() + Duration::minutes(minute);
+            // The BSSID's case does not make it a different AP
+            let bssid = if minute == 1 {
+                "AA:BB:CC:00:00:01"
+            } else {
+                "aa:bb:cc:00:00:01"
+            };
+            let twin = threat("evil_twin", "High", "Office", bssid, at);
+            seen.extend(changes(&mut store, &[twin], at));
+        }
+        let critical = threat(
+            "evil_twin",
+            "Critical",
+            "Office",
+            "aa:bb:cc:00:00:01",
+            t0() + Duration::minutes(3),
+        );
+        seen.extend(changes(
+            &mut store,
+            &[critical],
+            t0() + Duration::minutes(3),
+        ));
+
+        use Change::*;
+        assert_eq!(seen, [Opened, Repeated, Repeated, Escalated]);
+        let incidents = store.incidents(true, 10).unwrap();
+        assert_eq!(incidents.len(), 1);
+        let incident = &incidents[0];
+        assert_eq!(incident.occurrences, 4);
+        assert_eq!(incident.severity, "Critical");
+        assert_eq!(incident.first_seen, t0());
+        assert_eq!(incident.last_seen, t0() + Duration::minutes(3));
+        assert_eq!(incident.details, "seen at 12:03");
+        assert_eq!(incident.state, IncidentState::Open);
+    }
+
+    #[test]
+    fn test_lifecycle() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let at = |minutes| t0() + Duration::minutes(minutes);
+        let twin = |minutes| threat("evil_twin", "High", "Office", "aa:bb", at(minutes));
+
+        let (opened, _) =
+            record(&mut store, &[twin(0)], at(0), Duration::minutes(30)).unwrap()[0].clone();
+        let acked = acknowledge(&mut store, opened.id, at(1)).unwrap();
+        assert_eq!(acked.state, IncidentState::Acknowledged);
+        assert_eq!(acked.acknowledged_at, Some(at(1)));
+
+        // Still the same incident, and not news
+        assert_eq!(changes(&mut store, &[twin(2)], at(2)), [Change::Repeated]);
+        let resolved = resolve(&mut store, opened.id, at(3)).unwrap();
+        assert_eq!(resolved.state, IncidentState::Resolved);
+        assert_eq!(resolved.closed_at, Some(at(3)));
+        assert_eq!(
+            acknowledge(&mut store, opened.id, at(4)).unwrap_err(),
+            format!("Incident {} is already resolved", opened.id)
+        );
+
+        // Back after being resolved: a new incident
+        assert_eq!(changes(&mut store, &[twin(5)], at(5)), [Change::Opened]);
+
+        // Quiet for longer than the expiry, then back again
+        assert_eq!(changes(&mut store, &[], at(40)), []);
+        let incidents = store.incidents(true, 10).unwrap();
+        assert_eq!(incidents[0].state, IncidentState::Expired);
+        assert_eq!(incidents[0].closed_at, Some(at(40)));
+        assert_eq!(changes(&mut store, &[twin(41)], at(41)), [Change::Opened]);
+
+        assert_eq!(store.incidents(true, 10).unwrap().len(), 3);
+        assert_eq!(store.incidents(false, 10).unwrap().len(), 1);
+        assert_eq!(
+            resolve(&mut store, 99, at(42)).unwrap_err(),
+            "Incident 99 not found"
+        );
+    }
+
+    #[test]
+    fn test_groups_related_incidents() {
+        let mut store = HistoryStore::in_memory().unwrap();
+        let threats = [
+            // Clients kicked off the real AP so they roam to its twin
+            threat("evil_twin", "Critical", "Office", "02:00:00:00:00:01", t0()),
+            threat("deauth_attack", "High", "Office", "aa:bb:cc:00:00:02", t0()),
+            threat("evil_twin", "High", "Guest", "aa:bb:cc:00:00:03", t0()),
+            threat("flood_attack", "Medium", "Multiple", "Multiple", t0()),
+        ];
+        let recorded = record(&mut store, &threats, t0(), Duration::minutes(30)).unwrap();
+        let groups = group(recorded.into_iter().map(|(incident, _)| incident).collect());
+
+        let summary: Vec<(&str, &str, usize)> = groups
+            .iter()
+            .map(|g| (g.subject.as_str(), g.severity.as_str(), g.incidents.len()))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                ("Office", "Critical", 2),
+                ("Guest", "High", 1),
+                ("flood_attack", "Medium", 1)
+            ]
+        );
+        assert_eq!(groups[0].id, groups[0].incidents[0].id);
+    }
+}
@@ -0,0 +1,82 @@
+//! Link events from the platform's WLAN service: associations, disconnects
+//! and refused connections, with the reason the access point gave.
+//!
+//! On Windows they come from WLAN API notifications (`wlan_notification`);
+//! on Linux from wpa_supplicant, which NetworkManager drives as well: its
+//! D-Bus interface (`wpa_supplicant_dbus`), or its log in the journal when
+//! it is not on the bus (`wpa_supplicant`). The monitor collects them
+//! between scans for the `deauth_attack` detector.
+
+#[cfg(windows)]
+mod win32_notify;
+// Only the Win32 notification callback reads these buffers, but the
+// conversion is compiled and tested everywhere.
+#[cfg_attr(not(windows), allow(dead_code))]
+mod wlan_notification;
+#[cfg_attr(not(target_os = "linux"), allow(dead_code))]
+mod wpa_supplicant;
+#[cfg(target_os = "linux")]
+mod wpa_supplicant_dbus;
+
+use crate::structures::LinkEvent;
+use std::sync::Arc;
+use tokio::sync::mpsc::UnboundedSender;
+
+pub trait LinkEventSource: Send + Sync {
+    fn name(&self) -> &'static str;
+
+    /// Start sending events to `events`; they stop when the returned
+    /// subscription is dropped.
+    fn subscribe(&self, events: UnboundedSender<LinkEvent>) -> Result<Subscription, String>;
+}
+
+pub type SharedLinkEventSource = Arc<dyn LinkEventSource>;
+
+/// Keeps a source's events coming while it is alive.
+pub struct Subscription {
+    _guard: Box<dyn Send>,
+}
+
+impl Subscription {
+    pub fn new(guard: impl Send + 'static) -> Self {
+        Self {
+            _guard: Box::new(guard),
+        }
+    }
+}
+
+/// The platform's source, if it has one.
+#[cfg(windows)]
+pub fn default_source() -> Option<SharedLinkEventSource> {
+    Some(Arc::new(win32_notify::WlanNotifications))
+}
+
+#[cfg(target_os = "linux")]
+pub fn default_source() -> Option<SharedLinkEventSource> {
+    Some(Arc::new(wpa_supplicant_dbus::WpaSupplicant))
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn default_source() -> Option<SharedLinkEventSource> {
+    None
+}
+
+/// Meaning of an 802.11 reason code, for the ones worth naming.
+pub fn reason_text(code: u16) -> Option<&'static str> {
+    Some(match code {
+        1 => "Unspecified reason",
+        2 => "Previous authentication no longer valid",
+        3 => "Station is leaving",
+        4 => "Disassociated due to inactivity",
+        5 => "Access point cannot handle all associated stations",
+        6 => "Class 2 frame received from nonauthenticated station",
+        7 => "Class 3 frame received from nonassociated station",
+        8 => "Disassociated because station is leaving",
+        9 => "Station requesting association is not authenticated",
+        14 => "Message integrity code (MIC) failure",
+        15 => "4-way handshake timeout",
+        16 => "Group key handshake timeout",
+        23 => "IEEE 802.1X authentication failed",
+        _ => return None,
+    })
+}
@@ -0,0 +1,106 @@
+use super::wlan_notification::link_event_from_notification;
+use super::{LinkEventSource, Subscription};
+use crate::structures::LinkEvent;
+use chrono::Utc;
+use std::ffi::c_void;
+use std::ptr::{null, null_mut};
+use tokio::sync::mpsc::UnboundedSender;
+use windows::{Win32::Foundation::*, Win32::NetworkManagement::WiFi::*};
+
+/// ACM and MSM notifications: connection attempts, associations and
+/// disconnects on every interface.
+pub struct WlanNotifications;
+
+/// An open WLAN handle with the callback registered; `events` is what the
+/// callback's context points to.
+struct Registration {
+    client_handle: HANDLE,
+    events: *mut UnboundedSender<LinkEvent>,
+}
+
+// The handle and the sender may be used and freed from any thread.
+unsafe impl Send for Registration {}
+
+impl Drop for Registration {
+    fn drop(&mut self) {
+        unsafe {
+            WlanRegisterNotification(
+                self.client_handle,
+                WLAN_NOTIFICATION_SOURCE_NONE,
+                true,
+                None,
+                None,
+                Some(null()),
+                None,
+            );
+            // Waits for a callback in progress, so `events` is free after it
+            WlanCloseHandle(self.client_handle, Some(null()));
+            drop(Box::from_raw(self.events));
+        }
+    }
+}
+
+unsafe extern "system" fn on_notification(data: *mut L2_NOTIFICATION_DATA, context: *mut c_void) {
+    if data.is_null() || context.is_null() {
+        return;
+    }
+
+    let data = &*data;
+    let bytes: &[u8] = if data.pData.is_null() || data.dwDataSize == 0 {
+        &[]
+    } else {
+        std::slice::from_raw_parts(data.pData as *const u8, data.dwDataSize as usize)
+    };
+    if let Some(event) = link_event_from_notification(
+        data.NotificationSource,
+        data.NotificationCode,
+        bytes,
+        Utc::now(),
+    ) {
+        let events = &*(context as *const UnboundedSender<LinkEvent>);
+        let _ = events.send(event);
+    }
+}
+
+impl LinkEventSource for WlanNotifications {
+    fn name(&self) -> &'static str {
+        "wlan-notifications"
+    }
+
+    fn subscribe(&self, events: UnboundedSender<LinkEvent>) -> Result<Subscription, String> {
+        unsafe {
+            let mut client_handle = HANDLE(null_mut());
+            let mut negotiated_version: u32 = 0;
+
+            let result =
+                WlanOpenHandle(2, Some(null()), &mut negotiated_version, &mut client_handle);
+            if result != ERROR_SUCCESS.0 {
+                return Err(format!("Failed to open WLAN handle: error {}", result));
+            }
+
+            let events = Box::into_raw(Box::new(events));
+            let result = WlanRegisterNotification(
+                client_handle,
+                WLAN_NOTIFICATION_SOURCE_ACM | WLAN_NOTIFICATION_SOURCE_MSM,
+                true,
+                Some(on_notification),
+                Some(events as *const c_void),
+                Some(null()),
+                None,
+            );
+            if result != ERROR_SUCCESS.0 {
+                WlanCloseHandle(client_handle, Some(null()));
+                drop(Box::from_raw(events));
+                return Err(format!(
+                    "Failed to register for WLAN notifications: error {}",
+                    result
+                ));
+            }
+
+            Ok(Subscription::new(Registration {
+                client_handle,
+                events,
+            }))
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Conversion of WLAN API notifications into `LinkEvent`s.
+//!
+//! The notification data is read by offset rather than through the
+//! `windows` crate types so the conversion builds and is tested on every
+//! platform.
+
+use crate::structures::{LinkEvent, LinkEventKind};
+use chrono::{DateTime, Utc};
+
+// WLAN_NOTIFICATION_SOURCE_*.
+const SOURCE_ACM: u32 = 0x08;
+const SOURCE_MSM: u32 = 0x10;
+
+// WLAN_NOTIFICATION_ACM codes.
+const ACM_CONNECTION_ATTEMPT_FAIL: u32 = 11;
+
+// WLAN_NOTIFICATION_MSM codes.
+const MSM_ASSOCIATING: u32 = 1;
+const MSM_CONNECTED: u32 = 4;
+const MSM_DISCONNECTED: u32 = 10;
+
+// Offsets inside WLAN_CONNECTION_NOTIFICATION_DATA (ACM), after the
+// connection mode and the 256-character profile name.
+const CONNECTION_SSID_LEN: usize = 516;
+const CONNECTION_SSID: usize = 520;
+const CONNECTION_REASON: usize = 560;
+
+// Offsets inside WLAN_MSM_NOTIFICATION_DATA.
+const MSM_SSID_LEN: usize = 516;
+const MSM_SSID: usize = 520;
+const MSM_BSSID: usize = 556;
+const MSM_REASON: usize = 576;
+
+const DOT11_SSID_MAX: usize = 32;
+
+/// WLAN_REASON_CODE_SUCCESS: the WLAN service did what it was asked to,
+/// e.g. disconnect for the user.
+const REASON_SUCCESS: u32 = 0;
+
+fn read_u32(buffer: &[u8], offset: usize) -> Option<u32> {
+    let bytes = buffer.get(offset..offset + 4)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn read_ssid(data: &[u8], len_offset: usize, ssid_offset: usize) -> Option<String> {
+    let len = read_u32(data, len_offset)? as usize;
+    if len > DOT11_SSID_MAX {
+        return None;
+    }
+    let ssid = data.get(ssid_offset..ssid_offset + len)?;
+    Some(String::from_utf8_lossy(ssid).to_string())
+}
+
+/// The event an ACM or MSM notification stands for, if it is one. `data` is
+/// the notification's `pData`, `dwDataSize` bytes long.
+pub fn link_event_from_notification(
+    source: u32,
+    code: u32,
+    data: &[u8],
+    at: DateTime<Utc>,
+) -> Option<LinkEvent> {
+    match (source, code) {
+        (SOURCE_MSM, MSM_ASSOCIATING | MSM_CONNECTED | MSM_DISCONNECTED) => {
+            let bssid = data
+                .get(MSM_BSSID..MSM_BSSID + 6)?
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<Vec<_>>()
+                .join(":");
+            let reason = read_u32(data, MSM_REASON)?;
+            let kind = match code {
+                MSM_ASSOCIATING => LinkEventKind::Associating,
+                MSM_CONNECTED => LinkEventKind::Connected,
+                _ => LinkEventKind::Disconnected,
+            };
+            let disconnected = kind == LinkEventKind::Disconnected;
+            Some(LinkEvent {
+                at,
+                kind,
+                bssid: Some(bssid),
+                ssid: read_ssid(data, MSM_SSID_LEN, MSM_SSID),
+                code: None,
+                reason: (disconnected && reason != REASON_SUCCESS)
+                    .then(|| format!("WLAN reason code 0x{:x}", reason)),
+                locally_generated: disconnected && reason == REASON_SUCCESS,
+            })
+        }
+        (SOURCE_ACM, ACM_CONNECTION_ATTEMPT_FAIL) => {
+            let reason = read_u32(data, CONNECTION_REASON)?;
+            Some(LinkEvent {
+                at,
+                kind: LinkEventKind::ConnectionFailed,
+                // Only the MSM notifications name the access point
+                bssid: None,
+                ssid: read_ssid(data, CONNECTION_SSID_LEN, CONNECTION_SSID),
+                code: None,
+                reason: Some(format!("WLAN reason code 0x{:x}", reason)),
+                locally_generated: false,
+            })
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+
+    fn put_u32(buf: &mut [u8], offset: usize, value: u32) {
+        buf[offset..offset + 4].copy_from_slice(&value.to_le_bytes());
+    }
+
+    fn msm_data(ssid: &str, bssid: [u8; 6], reason: u32) -> Vec<u8> {
+        let mut buf = vec![0u8; 580];
+        put_u32(&mut buf, MSM_SSID_LEN, ssid.len() as u32);
+        buf[MSM_SSID..MSM_SSID + ssid.len()].copy_from_slice(ssid.as_bytes());
+        buf[MSM_BSSID..MSM_BSSID + 6].copy_from_slice(&bssid);
+        put_u32(&mut buf, MSM_REASON, reason);
+        buf
+    }
+
+    #[test]
+    fn test_converts_notifications() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let ap = [0xaa, 0xbb, 0xcc, 0x00, 0x00, 0x01];
+
+        let associating = link_event_from_notification(
+            SOURCE_MSM,
+            MSM_ASSOCIATING,
+            &msm_data("Office", ap, 0),
+            at,
+        )
+        .unwrap();
+        assert_eq!(associating.kind, LinkEventKind::Associating);
+        assert_eq!(associating.bssid.as_deref(), Some("aa:bb:cc:00:00:01"));
+        assert_eq!(associating.ssid.as_deref(), Some("Office"));
+
+        let kicked = link_event_from_notification(
+            SOURCE_MSM,
+            MSM_DISCONNECTED,
+            &msm_data("Office", ap, 0x38001),
+            at,
+        )
+        .unwrap();
+        assert_eq!(kicked.kind, LinkEventKind::Disconnected);
+        assert!(!kicked.locally_generated);
+        assert_eq!(kicked.reason.as_deref(), Some("WLAN reason code 0x38001"));
+
+        let asked = link_event_from_notification(
+            SOURCE_MSM,
+            MSM_DISCONNECTED,
+            &msm_data("Office", ap, 0),
+            at,
+        )
+        .unwrap();
+        assert!(asked.locally_generated);
+
+        let mut failed = vec![0u8; 568];
+        put_u32(&mut failed, CONNECTION_SSID_LEN, 6);
+        failed[CONNECTION_SSID..CONNECTION_SSID + 6].copy_from_slice(b"Office");
+        put_u32(&mut failed, CONNECTION_REASON, 0x28002);
+        let failed =
+            link_event_from_notification(SOURCE_ACM, ACM_CONNECTION_ATTEMPT_FAIL, &failed, at)
+                .unwrap();
+        assert_eq!(failed.kind, LinkEventKind::ConnectionFailed);
+        assert_eq!(
+            (failed.bssid, failed.ssid.as_deref()),
+            (None, Some("Office"))
+        );
+
+        // Truncated data and other notifications are skipped
+        assert!(
+            link_event_from_notification(SOURCE_MSM, MSM_DISCONNECTED, &[0; 100], at).is_none()
+        );
+        assert!(link_event_from_notification(SOURCE_ACM, 7, &[], at).is_none());
+    }
+}
@@ -0,0 +1,338 @@
+//! wpa_supplicant link events, from the interface properties it changes on
+//! D-Bus (`wpa_supplicant_dbus`) or, where that is not available, from the
+//! control events in its log in the journal. NetworkManager connects through
+//! wpa_supplicant, so this covers it too.
+
+use super::{reason_text, LinkEventSource, Subscription};
+use crate::structures::{LinkEvent, LinkEventKind};
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use std::io::{BufRead, BufReader};
+use std::process::{Child, Command, Stdio};
+use std::sync::OnceLock;
+use tokio::sync::mpsc::UnboundedSender;
+
+pub struct WpaSupplicantLog;
+
+/// Kills `journalctl` when the subscription is dropped, which ends the
+/// reader thread at end of output.
+struct Follow(Child);
+
+impl Drop for Follow {
+    fn drop(&mut self) {
+        let _ = self.0.kill();
+        let _ = self.0.wait();
+    }
+}
+
+impl LinkEventSource for WpaSupplicantLog {
+    fn name(&self) -> &'static str {
+        "wpa_supplicant"
+    }
+
+    fn subscribe(&self, events: UnboundedSender<LinkEvent>) -> Result<Subscription, String> {
+        check_journal_access()?;
+        let mut child = Command::new("journalctl")
+            .args([
+                "--follow",
+                "--lines=0",
+                "--output=cat",
+                "--unit=wpa_supplicant",
+            ])
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| format!("Failed to follow the wpa_supplicant log: {}", e))?;
+        let stdout = child
+            .stdout
+            .take()
+            .ok_or("Failed to read the wpa_supplicant log")?;
+
+        std::thread::spawn(move || {
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if let Some(event) = parse_wpa_event(&line, Utc::now()) {
+                    if events.send(event).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
+        Ok(Subscription::new(Follow(child)))
+    }
+}
+
+/// `journalctl --follow` keeps running when it may not read the system
+/// journal, it only warns on stderr, so ask once without following first.
+fn check_journal_access() -> Result<(), String> {
+    let output = Command::new("journalctl")
+        .args(["--system", "--lines=0"])
+        .output()
+        .map_err(|e| format!("Failed to read the system journal: {}", e))?;
+    let warning = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || !warning.trim().is_empty() {
+        return Err(format!(
+            "Cannot read wpa_supplicant's log in the system journal: {}",
+            warning.trim()
+        ));
+    }
+    Ok(())
+}
+
+fn trying_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(
+            r"Trying to (?:associate|authenticate) with ([0-9A-Fa-f:]{17}) \(SSID='(.*)' freq=",
+        )
+        .unwrap()
+    })
+}
+
+/// The `key=value` argument of a control event line.
+fn argument<'a>(line: &'a str, key: &str) -> Option<&'a str> {
+    line.split_whitespace()
+        .find_map(|word| word.strip_prefix(key)?.strip_prefix('='))
+}
+
+fn is_bssid(word: &str) -> bool {
+    word.len() == 17
+        && word
+            .split(':')
+            .all(|b| b.len() == 2 && u8::from_str_radix(b, 16).is_ok())
+}
+
+/// Turn one log line into a link event, if it is one.
+///
+/// ```text
+/// wlp2s0: Trying to associate with aa:bb:cc:dd:ee:ff (SSID='Office' freq=2437 MHz)
+/// wlp2s0: CTRL-EVENT-CONNECTED - Connection to aa:bb:cc:dd:ee:ff completed [id=0 id_str=]
+/// wlp2s0: CTRL-EVENT-DISCONNECTED bssid=aa:bb:cc:dd:ee:ff reason=7
+/// wlp2s0: CTRL-EVENT-ASSOC-REJECT bssid=aa:bb:cc:dd:ee:ff status_code=17
+/// wlp2s0: CTRL-EVENT-AUTH-REJECT aa:bb:cc:dd:ee:ff auth_type=0 status_code=1
+/// ```
+pub fn parse_wpa_event(line: &str, at: DateTime<Utc>) -> Option<LinkEvent> {
+    let event = |kind, bssid: Option<&str>| LinkEvent {
+        at,
+        kind,
+        bssid: bssid.map(str::to_lowercase),
+        ssid: None,
+        code: None,
+        reason: None,
+        locally_generated: false,
+    };
+
+    if let Some(captures) = trying_pattern().captures(line) {
+        return Some(LinkEvent {
+            ssid: Some(captures[2].to_string()),
+            ..event(LinkEventKind::Associating, Some(&captures[1]))
+        });
+    }
+
+    let (_, control) = line.split_once("CTRL-EVENT-")?;
+    let (name, _) = control.split_once(' ').unwrap_or((control, ""));
+    let first_bssid = || control.split_whitespace().find(|w| is_bssid(w));
+    match name {
+        "CONNECTED" => Some(event(LinkEventKind::Connected, first_bssid())),
+        "DISCONNECTED" => {
+            let code = argument(control, "reason").and_then(|r| r.parse().ok());
+            Some(LinkEvent {
+                code,
+                reason: code.and_then(reason_text).map(str::to_string),
+                locally_generated: argument(control, "locally_generated") == Some("1"),
+                ..event(LinkEventKind::Disconnected, argument(control, "bssid"))
+            })
+        }
+        "ASSOC-REJECT" | "AUTH-REJECT" => {
+            let bssid = argument(control, "bssid").or_else(first_bssid);
+            Some(LinkEvent {
+                code: argument(control, "status_code").and_then(|s| s.parse().ok()),
+                ..event(LinkEventKind::ConnectionFailed, bssid)
+            })
+        }
+        _ => None,
+    }
+}
+
+/// The properties of a wpa_supplicant interface one `PropertiesChanged`
+/// signal carried, as far as link events are concerned.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct InterfaceChange {
+    pub state: Option<String>,
+    /// 802.11 reason code of the last disconnect, negative when the station
+    /// disconnected itself.
+    pub disconnect_reason: Option<i32>,
+    /// 802.11 status code of the last refused association.
+    pub assoc_status: Option<i32>,
+    /// 802.11 status code of the last refused authentication.
+    pub auth_status: Option<i32>,
+}
+
+/// The access point an interface was last associated with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CurrentBss {
+    /// Lowercase.
+    pub bssid: String,
+    pub ssid: String,
+}
+
+/// Turn an interface's changed properties into link events. `bss` is the
+/// access point it is associating with, or was on until the change.
+pub fn interface_events(
+    change: &InterfaceChange,
+    bss: Option<&CurrentBss>,
+    at: DateTime<Utc>,
+) -> Vec<LinkEvent> {
+    let event = |kind| LinkEvent {
+        at,
+        kind,
+        bssid: bss.map(|b| b.bssid.clone()),
+        ssid: bss.map(|b| b.ssid.clone()),
+        code: None,
+        reason: None,
+        locally_generated: false,
+    };
+    let mut events = Vec::new();
+
+    if let Some(reason) = change.disconnect_reason.filter(|&r| r != 0) {
+        let code = u16::try_from(reason.unsigned_abs()).ok();
+        events.push(LinkEvent {
+            code,
+            reason: code.and_then(reason_text).map(str::to_string),
+            locally_generated: reason < 0,
+            ..event(LinkEventKind::Disconnected)
+        });
+    }
+    for status in [change.assoc_status, change.auth_status] {
+        if let Some(status) = status.filter(|&s| s > 0) {
+            events.push(LinkEvent {
+                code: u16::try_from(status).ok(),
+                ..event(LinkEventKind::ConnectionFailed)
+            });
+        }
+    }
+    match change.state.as_deref() {
+        Some("associating") => events.push(event(LinkEventKind::Associating)),
+        Some("completed") => events.push(event(LinkEventKind::Connected)),
+        _ => {}
+    }
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{interface_events, parse_wpa_event, CurrentBss, InterfaceChange};
+    use crate::structures::LinkEventKind;
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn test_parses_control_events() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let parse = |line| parse_wpa_event(line, at).unwrap();
+
+        let trying = parse(
+            "wlp2s0: Trying to associate with AA:BB:CC:DD:EE:FF (SSID='Cafe Wi-Fi' freq=2437 MHz)",
+        );
+        assert_eq!(trying.kind, LinkEventKind::Associating);
+        assert_eq!(trying.bssid.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+        assert_eq!(trying.ssid.as_deref(), Some("Cafe Wi-Fi"));
+
+        let connected = parse(
+            "wlp2s0: CTRL-EVENT-CONNECTED - Connection to aa:bb:cc:dd:ee:ff completed [id=0 id_str=]",
+        );
+        assert_eq!(connected.kind, LinkEventKind::Connected);
+        assert_eq!(connected.bssid.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+
+        let kicked = parse("wlp2s0: CTRL-EVENT-DISCONNECTED bssid=aa:bb:cc:dd:ee:ff reason=7");
+        assert_eq!(kicked.kind, LinkEventKind::Disconnected);
+        assert_eq!(kicked.code, Some(7));
+        assert_eq!(
+            kicked.reason.as_deref(),
+            Some("Class 3 frame received from nonassociated station")
+        );
+        assert!(!kicked.locally_generated);
+
+        let left = parse(
+            "wlp2s0: CTRL-EVENT-DISCONNECTED bssid=aa:bb:cc:dd:ee:ff reason=3 locally_generated=1",
+        );
+        assert!(left.locally_generated);
+
+        let rejected =
+            parse("wlp2s0: CTRL-EVENT-ASSOC-REJECT bssid=aa:bb:cc:dd:ee:ff status_code=17");
+        assert_eq!(rejected.kind, LinkEventKind::ConnectionFailed);
+        assert_eq!(rejected.code, Some(17));
+        let auth =
+            parse("wlp2s0: CTRL-EVENT-AUTH-REJECT aa:bb:cc:dd:ee:ff auth_type=0 status_code=1");
+        assert_eq!(auth.bssid.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+
+        assert!(parse_wpa_event("wlp2s0: CTRL-EVENT-SCAN-RESULTS", at).is_none());
+        assert!(parse_wpa_event("Successfully initialized wpa_supplicant", at).is_none());
+    }
+
+    #[test]
+    fn test_interface_property_changes() {
+        let at = Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap();
+        let office = CurrentBss {
+            bssid: "aa:bb:cc:dd:ee:ff".to_string(),
+            ssid: "Office".to_string(),
+        };
+        let events = |change: InterfaceChange| interface_events(&change, Some(&office), at);
+
+        let associating = events(InterfaceChange {
+            state: Some("associating".to_string()),
+            ..Default::default()
+        });
+        assert_eq!(associating.len(), 1);
+        assert_eq!(associating[0].kind, LinkEventKind::Associating);
+        assert_eq!(associating[0].bssid.as_deref(), Some("aa:bb:cc:dd:ee:ff"));
+        assert_eq!(associating[0].ssid.as_deref(), Some("Office"));
+
+        let kicked = events(InterfaceChange {
+            state: Some("disconnected".to_string()),
+            disconnect_reason: Some(7),
+            ..Default::default()
+        });
+        assert_eq!(kicked.len(), 1);
+        assert_eq!(kicked[0].kind, LinkEventKind::Disconnected);
+        assert_eq!(kicked[0].code, Some(7));
+        assert_eq!(
+            kicked[0].reason.as_deref(),
+            Some("Class 3 frame received from nonassociated station")
+        );
+        assert!(!kicked[0].locally_generated);
+
+        let left = events(InterfaceChange {
+            disconnect_reason: Some(-3),
+            ..Default::default()
+        });
+        assert_eq!(left[0].code, Some(3));
+        assert!(left[0].locally_generated);
+
+        let rejected = events(InterfaceChange {
+            assoc_status: Some(17),
+            ..Default::default()
+        });
+        assert_eq!(rejected.len(), 1);
+        assert_eq!(rejected[0].kind, LinkEventKind::ConnectionFailed);
+        assert_eq!(rejected[0].code, Some(17));
+
+        // Cleared codes and states in between are not events
+        assert!(events(InterfaceChange {
+            state: Some("scanning".to_string()),
+            disconnect_reason: Some(0),
+            assoc_status: Some(0),
+            auth_status: Some(-1),
+        })
+        .is_empty());
+        let connected = interface_events(
+            &InterfaceChange {
+                state: Some("completed".to_string()),
+                ..Default::default()
+            },
+            None,
+            at,
+        );
+        assert_eq!(connected[0].kind, LinkEventKind::Connected);
+        assert_eq!(connected[0].bssid, None);
+    }
+}
@@ -0,0 +1,138 @@
+//! wpa_supplicant's D-Bus interface: disconnect reasons and refused
+//! associations arrive as `PropertiesChanged` signals on each interface,
+//! which any user may receive. Its log in the journal is only read when
+//! wpa_supplicant is not on the system bus.
+
+use super::wpa_supplicant::{interface_events, CurrentBss, InterfaceChange, WpaSupplicantLog};
+use super::{LinkEventSource, Subscription};
+use crate::structures::LinkEvent;
+use chrono::Utc;
+use std::collections::HashMap;
+use tokio::sync::mpsc::UnboundedSender;
+use zbus::blocking::fdo::{DBusProxy, PropertiesProxy};
+use zbus::blocking::{Connection, MessageIterator};
+use zbus::message::Type;
+use zbus::names::InterfaceName;
+use zbus::zvariant::{ObjectPath, OwnedValue};
+use zbus::MatchRule;
+
+const SERVICE: &str = "fi.w1.wpa_supplicant1";
+const INTERFACE: &str = "fi.w1.wpa_supplicant1.Interface";
+const BSS: &str = "fi.w1.wpa_supplicant1.BSS";
+
+/// Interface, changed properties and invalidated ones.
+type PropertiesChanged = (String, HashMap<String, OwnedValue>, Vec<String>);
+
+pub struct WpaSupplicant;
+
+/// Closes the bus connection when the subscription is dropped, which ends
+/// the reader thread.
+struct Watch(Connection);
+
+impl Drop for Watch {
+    fn drop(&mut self) {
+        let _ = self.0.clone().close();
+    }
+}
+
+impl LinkEventSource for WpaSupplicant {
+    fn name(&self) -> &'static str {
+        "wpa_supplicant"
+    }
+
+    fn subscribe(&self, events: UnboundedSender<LinkEvent>) -> Result<Subscription, String> {
+        watch_bus(events.clone()).or_else(|e| {
+            eprintln!("{}; reading its log instead", e);
+            WpaSupplicantLog.subscribe(events)
+        })
+    }
+}
+
+fn bus_error(e: impl std::fmt::Display) -> String {
+    format!("Failed to watch wpa_supplicant on D-Bus: {}", e)
+}
+
+fn watch_bus(events: UnboundedSender<LinkEvent>) -> Result<Subscription, String> {
+    let connection = Connection::system().map_err(bus_error)?;
+    let running = DBusProxy::new(&connection)
+        .and_then(|bus| Ok(bus.name_has_owner(SERVICE.try_into()?)?))
+        .map_err(bus_error)?;
+    if !running {
+        return Err(bus_error("wpa_supplicant is not on the system bus"));
+    }
+    let rule = MatchRule::builder()
+        .msg_type(Type::Signal)
+        .sender(SERVICE)
+        .and_then(|rule| rule.interface("org.freedesktop.DBus.Properties"))
+        .and_then(|rule| rule.member("PropertiesChanged"))
+        .and_then(|rule| rule.arg(0, INTERFACE))
+        .map_err(bus_error)?
+        .build();
+    let messages = MessageIterator::for_match_rule(rule, &connection, None).map_err(bus_error)?;
+
+    let reader = connection.clone();
+    std::thread::spawn(move || {
+        // By interface object path; kept after a disconnect clears it, so
+        // the disconnect and later refusals still name the access point
+        let mut current: HashMap<String, CurrentBss> = HashMap::new();
+        for message in messages {
+            let Ok(message) = message else { break };
+            let Some(path) = message.header().path().map(|p| p.to_string()) else {
+                continue;
+            };
+            let Ok((_, changed, _)) = message.body().deserialize::<PropertiesChanged>() else {
+                continue;
+            };
+            let bss_path = changed
+                .get("CurrentBSS")
+                .and_then(|v| <&ObjectPath>::try_from(v).ok())
+                .filter(|p| p.as_str() != "/");
+            if let Some(bss) = bss_path.and_then(|p| current_bss(&reader, p)) {
+                current.insert(path.clone(), bss);
+            }
+
+            let int = |name: &str| changed.get(name).and_then(|v| i32::try_from(v).ok());
+            let change = InterfaceChange {
+                state: changed
+                    .get("State")
+                    .and_then(|v| <&str>::try_from(v).ok())
+                    .map(str::to_string),
+                disconnect_reason: int("DisconnectReason"),
+                assoc_status: int("AssocStatusCode"),
+                auth_status: int("AuthStatusCode"),
+            };
+            for event in interface_events(&change, current.get(&path), Utc::now()) {
+                if events.send(event).is_err() {
+                    return;
+                }
+            }
+        }
+    });
+    Ok(Subscription::new(Watch(connection)))
+}
+
+/// BSSID and SSID of the BSS object at `path`.
+fn current_bss(connection: &Connection, path: &ObjectPath) -> Option<CurrentBss> {
+    let properties = PropertiesProxy::builder(connection)
+        .destination(SERVICE)
+        .and_then(|proxy| proxy.path(path))
+        .and_then(|proxy| proxy.build())
+        .ok()?
+        .get_all(InterfaceName::from_static_str_unchecked(BSS))
+        .ok()?;
+    let bytes = |name: &str| -> Option<Vec<u8>> {
+        Vec::try_from(properties.get(name)?.try_clone().ok()?).ok()
+    };
+    let bssid = bytes("BSSID")?;
+    if bssid.len() != 6 {
+        return None;
+    }
+    Some(CurrentBss {
+        bssid: bssid
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(":"),
+        ssid: String::from_utf8_lossy(&bytes("SSID").unwrap_or_default()).into_owned(),
+    })
+}
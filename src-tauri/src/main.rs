@@ -1,8 +1,25 @@
+mod alerts;
+mod backends;
+mod baseline;
+mod detectors;
+mod history;
+mod ie;
+mod incidents;
+mod link_events;
+mod rules;
 mod structures;
 mod wifi_functions;
 
+use alerts::{AlertQueue, RetryPolicy};
+use std::sync::Arc;
+use tauri::Manager;
 use wifi_functions::{
-    connect_wifi, disconnect_wifi, get_active_network, scan_wifi,
+    acknowledge_incident, connect_wifi, disconnect_wifi, get_active_network,
+    get_alert_queue_status, get_baseline, get_bssid_history, get_connection_history,
+    get_monitoring_status, get_threat_history, learn_baseline, list_bssid_history, list_detectors,
+    list_incidents, load_oui_database, load_risk_policy, refresh_oui_database, reset_baseline,
+    resolve_incident, scan_wifi, set_alert_credentials, start_monitoring, stop_monitoring,
+    MonitorHandle, SharedKarmaDetector,
 };
 
 fn main() {
@@ -10,13 +27,47 @@ fn main() {
         .plugin(tauri_plugin_store::Builder::default().build())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
+        .manage(backends::select_backend())
+        .manage(SharedKarmaDetector::default())
+        .manage(MonitorHandle::default())
+        .setup(|app| {
+            let data_dir = app.path().app_data_dir()?;
+            app.manage(load_risk_policy(&data_dir));
+            app.manage(rules::load_rules(&data_dir));
+            load_oui_database(&data_dir);
+            app.manage(baseline::load_baseline(&data_dir));
+            let history = history::load_history(&data_dir);
+            // Alerts left from an earlier session go out once credentials are set
+            app.manage(Arc::new(AlertQueue::new(
+                Arc::clone(&history),
+                RetryPolicy::default(),
+            )));
+            app.manage(history);
+            Ok(())
+        })
         .invoke_handler(tauri::generate_handler![
             scan_wifi,
             connect_wifi,
             get_active_network,
             disconnect_wifi,
+            refresh_oui_database,
+            learn_baseline,
+            get_baseline,
+            reset_baseline,
+            start_monitoring,
+            stop_monitoring,
+            get_monitoring_status,
+            list_detectors,
+            get_bssid_history,
+            list_bssid_history,
+            get_threat_history,
+            get_connection_history,
+            get_alert_queue_status,
+            set_alert_credentials,
+            list_incidents,
+            acknowledge_incident,
+            resolve_incident,
         ])
         .run(tauri::generate_context!())
         .expect("error while running Tauri application");
 }
-
@@ -0,0 +1,834 @@
+//! Condition language of detection rules.
+//!
+//! ```text
+//! expr       := or
+//! or         := and (("||" | "or") and)*
+//! and        := unary (("&&" | "and") unary)*
+//! unary      := ("!" | "not") unary | "(" expr ")" | comparison
+//! comparison := field [op literal]        a bare field must be boolean
+//! op         := == != < <= > >= ~ !~ matches in
+//! literal    := "string" | number | true | false | /regex/flags | [literal, ...]
+//! ```
+//!
+//! Conditions are type-checked when the rule is loaded, so a typo in a field
+//! name or a regex compared against a number fails the load instead of
+//! silently never matching.
+
+use crate::structures::{AuthSuite, Cipher, RiskGrade, WifiNetwork};
+use crate::wifi_functions::oui_database::parse_mac;
+use regex::Regex;
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Field {
+    Ssid,
+    Bssid,
+    Vendor,
+    Auth,
+    Cipher,
+    Signal,
+    Dbm,
+    Channel,
+    Band,
+    RadioType,
+    Score,
+    Grade,
+    IsEvilTwin,
+    Hidden,
+    LocallyAdministered,
+    Multicast,
+    Wps,
+    PmfRequired,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FieldType {
+    Text,
+    Number,
+    Bool,
+    Auth,
+    Cipher,
+    Grade,
+}
+
+const FIELDS: [(&str, Field); 18] = [
+    ("ssid", Field::Ssid),
+    ("bssid", Field::Bssid),
+    ("vendor", Field::Vendor),
+    ("auth", Field::Auth),
+    ("cipher", Field::Cipher),
+    ("signal", Field::Signal),
+    ("dbm", Field::Dbm),
+    ("channel", Field::Channel),
+    ("band", Field::Band),
+    ("radio_type", Field::RadioType),
+    ("score", Field::Score),
+    ("grade", Field::Grade),
+    ("is_evil_twin", Field::IsEvilTwin),
+    ("hidden", Field::Hidden),
+    ("locally_administered", Field::LocallyAdministered),
+    ("multicast", Field::Multicast),
+    ("wps", Field::Wps),
+    ("pmf_required", Field::PmfRequired),
+];
+
+impl Field {
+    fn parse(name: &str) -> Option<Self> {
+        FIELDS.iter().find(|(n, _)| *n == name).map(|(_, f)| *f)
+    }
+
+    fn name(&self) -> &'static str {
+        FIELDS
+            .iter()
+            .find(|(_, f)| f == self)
+            .map(|(n, _)| *n)
+            .unwrap_or("?")
+    }
+
+    fn field_type(&self) -> FieldType {
+        match self {
+            Self::Ssid | Self::Bssid | Self::Vendor | Self::Band | Self::RadioType => FieldType::Text,
+            Self::Signal | Self::Dbm | Self::Channel | Self::Score => FieldType::Number,
+            Self::IsEvilTwin
+            | Self::Hidden
+            | Self::LocallyAdministered
+            | Self::Multicast
+            | Self::Wps
+            | Self::PmfRequired => FieldType::Bool,
+            Self::Auth => FieldType::Auth,
+            Self::Cipher => FieldType::Cipher,
+            Self::Grade => FieldType::Grade,
+        }
+    }
+
+    /// The field's value for `net`; `None` when the backend did not report it.
+    fn value(&self, net: &WifiNetwork) -> Option<Value> {
+        Some(match self {
+            Self::Ssid => Value::Text(net.ssid.clone()),
+            Self::Bssid => Value::Text(net.bssid.to_lowercase()),
+            Self::Vendor => Value::Text(net.vendor.clone()?),
+            Self::Auth => Value::Auth(net.authentication.clone()),
+            Self::Cipher => Value::Cipher(net.encryption.clone()),
+            Self::Signal => Value::Number(f64::from(net.signal?.percent)),
+            Self::Dbm => Value::Number(f64::from(net.signal?.dbm)),
+            Self::Channel => Value::Number(f64::from(net.channel?)),
+            Self::Band => Value::Text(net.band?.as_str().to_string()),
+            Self::RadioType => Value::Text(net.radio_type?.as_str().to_string()),
+            Self::Score => Value::Number(f64::from(net.risk_assessment.as_ref()?.score)),
+            Self::Grade => Value::Grade(net.risk_assessment.as_ref()?.grade),
+            Self::IsEvilTwin => Value::Bool(net.is_evil_twin),
+            Self::Hidden => Value::Bool(net.ssid.trim().is_empty()),
+            Self::LocallyAdministered => Value::Bool(parse_mac(&net.bssid)?[0] & 0x02 != 0),
+            Self::Multicast => Value::Bool(parse_mac(&net.bssid)?[0] & 0x01 != 0),
+            Self::Wps => Value::Bool(net.wps?),
+            Self::PmfRequired => Value::Bool(net.pmf_required?),
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Auth(AuthSuite),
+    Cipher(Cipher),
+    Grade(RiskGrade),
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Text(s) => write!(f, "{:?}", s),
+            Self::Number(n) => write!(f, "{}", n),
+            Self::Bool(b) => write!(f, "{}", b),
+            Self::Auth(a) => write!(f, "{:?}", a.as_str()),
+            Self::Cipher(c) => write!(f, "{:?}", c.as_str()),
+            Self::Grade(g) => write!(f, "{:?}", g.as_str()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CmpOp {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Matches,
+    NotMatches,
+    In,
+}
+
+impl CmpOp {
+    fn symbol(&self) -> &'static str {
+        match self {
+            Self::Eq => "==",
+            Self::Ne => "!=",
+            Self::Lt => "<",
+            Self::Le => "<=",
+            Self::Gt => ">",
+            Self::Ge => ">=",
+            Self::Matches => "~",
+            Self::NotMatches => "!~",
+            Self::In => "in",
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    Value(Value),
+    Regex(Regex),
+    List(Vec<Value>),
+}
+
+impl fmt::Display for Operand {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Value(v) => write!(f, "{}", v),
+            Self::Regex(r) => write!(f, "/{}/", r.as_str()),
+            Self::List(values) => {
+                let items: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+                write!(f, "[{}]", items.join(", "))
+            }
+        }
+    }
+}
+
+/// A parsed, type-checked condition.
+#[derive(Debug, Clone)]
+pub struct Expr(Node);
+
+#[derive(Debug, Clone)]
+enum Node {
+    And(Box<Node>, Box<Node>),
+    Or(Box<Node>, Box<Node>),
+    Not(Box<Node>),
+    Flag(Field),
+    Compare(Field, CmpOp, Operand),
+}
+
+impl Expr {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        let mut parser = Parser { tokens, pos: 0 };
+        let node = parser.or()?;
+        match parser.peek() {
+            None => Ok(Self(node)),
+            Some((token, at)) => Err(format!("unexpected {} at column {}", token, at + 1)),
+        }
+    }
+
+    /// Evaluate against `net`, appending one line per comparison made to
+    /// `trace`. `&&` and `||` short-circuit, so skipped comparisons are not
+    /// traced.
+    ///
+    /// A comparison on a value the network does not report is unknown, and
+    /// stays unknown under `!`, so a condition that depends on it does not
+    /// match.
+    pub fn eval(&self, net: &WifiNetwork, trace: &mut Vec<String>) -> bool {
+        self.0.eval(net, trace).unwrap_or(false)
+    }
+}
+
+impl Node {
+    /// Three-valued: `None` when the outcome depends on a missing value.
+    fn eval(&self, net: &WifiNetwork, trace: &mut Vec<String>) -> Option<bool> {
+        match self {
+            Self::And(a, b) => match a.eval(net, trace) {
+                Some(false) => Some(false),
+                left => match (left, b.eval(net, trace)) {
+                    (_, Some(false)) => Some(false),
+                    (Some(true), right) => right,
+                    _ => None,
+                },
+            },
+            Self::Or(a, b) => match a.eval(net, trace) {
+                Some(true) => Some(true),
+                left => match (left, b.eval(net, trace)) {
+                    (_, Some(true)) => Some(true),
+                    (Some(false), right) => right,
+                    _ => None,
+                },
+            },
+            Self::Not(e) => e.eval(net, trace).map(|result| !result),
+            Self::Flag(field) => {
+                let value = field.value(net);
+                let result = value.as_ref().map(|v| *v == Value::Bool(true));
+                trace.push(format!(
+                    "{} ({}) => {}",
+                    field.name(),
+                    describe(&value),
+                    outcome(result)
+                ));
+                result
+            }
+            Self::Compare(field, op, operand) => {
+                let value = field.value(net);
+                let result = value.as_ref().map(|v| compare(v, *op, operand));
+                trace.push(format!(
+                    "{} ({}) {} {} => {}",
+                    field.name(),
+                    describe(&value),
+                    op.symbol(),
+                    operand,
+                    outcome(result)
+                ));
+                result
+            }
+        }
+    }
+}
+
+fn describe(value: &Option<Value>) -> String {
+    value
+        .as_ref()
+        .map(|v| v.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn outcome(result: Option<bool>) -> String {
+    result
+        .map(|r| r.to_string())
+        .unwrap_or_else(|| "unknown".to_string())
+}
+
+fn compare(value: &Value, op: CmpOp, operand: &Operand) -> bool {
+    match (op, operand) {
+        (CmpOp::Matches, Operand::Regex(re)) => matches!(value, Value::Text(s) if re.is_match(s)),
+        (CmpOp::NotMatches, Operand::Regex(re)) => {
+            matches!(value, Value::Text(s) if !re.is_match(s))
+        }
+        (CmpOp::In, Operand::List(items)) => items.iter().any(|item| equal(value, item)),
+        (_, Operand::Value(literal)) => match op {
+            CmpOp::Eq => equal(value, literal),
+            CmpOp::Ne => !equal(value, literal),
+            _ => match order(value, literal) {
+                Some(ordering) => match op {
+                    CmpOp::Lt => ordering.is_lt(),
+                    CmpOp::Le => ordering.is_le(),
+                    CmpOp::Gt => ordering.is_gt(),
+                    CmpOp::Ge => ordering.is_ge(),
+                    _ => false,
+                },
+                None => false,
+            },
+        },
+        _ => false,
+    }
+}
+
+/// Text compares exactly: SSIDs are case-sensitive, and "corpnet" is not
+/// the trusted "CorpNet". BSSIDs, which are not, are lowercased on both sides
+/// beforehand.
+fn equal(a: &Value, b: &Value) -> bool {
+    a == b
+}
+
+fn order(a: &Value, b: &Value) -> Option<std::cmp::Ordering> {
+    match (a, b) {
+        (Value::Number(a), Value::Number(b)) => a.partial_cmp(b),
+        (Value::Auth(a), Value::Auth(b)) => Some(a.cmp(b)),
+        (Value::Cipher(a), Value::Cipher(b)) => Some(a.cmp(b)),
+        (Value::Grade(a), Value::Grade(b)) => Some(a.cmp(b)),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Str(String),
+    Number(f64),
+    Regex(String, String),
+    Op(CmpOp),
+    And,
+    Or,
+    Not,
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(name) => write!(f, "'{}'", name),
+            Self::Str(s) => write!(f, "string {:?}", s),
+            Self::Number(n) => write!(f, "number {}", n),
+            Self::Regex(r, _) => write!(f, "regex /{}/", r),
+            Self::Op(op) => write!(f, "'{}'", op.symbol()),
+            Self::And => write!(f, "'&&'"),
+            Self::Or => write!(f, "'||'"),
+            Self::Not => write!(f, "'!'"),
+            Self::LParen => write!(f, "'('"),
+            Self::RParen => write!(f, "')'"),
+            Self::LBracket => write!(f, "'['"),
+            Self::RBracket => write!(f, "']'"),
+            Self::Comma => write!(f, "','"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<(Token, usize)>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        let start = i;
+        let next = chars.get(i + 1).copied();
+
+        let token = match c {
+            c if c.is_whitespace() => {
+                i += 1;
+                continue;
+            }
+            '(' => Token::LParen,
+            ')' => Token::RParen,
+            '[' => Token::LBracket,
+            ']' => Token::RBracket,
+            ',' => Token::Comma,
+            '~' => Token::Op(CmpOp::Matches),
+            '&' if next == Some('&') => {
+                i += 1;
+                Token::And
+            }
+            '|' if next == Some('|') => {
+                i += 1;
+                Token::Or
+            }
+            '=' if next == Some('=') => {
+                i += 1;
+                Token::Op(CmpOp::Eq)
+            }
+            '!' if next == Some('=') => {
+                i += 1;
+                Token::Op(CmpOp::Ne)
+            }
+            '!' if next == Some('~') => {
+                i += 1;
+                Token::Op(CmpOp::NotMatches)
+            }
+            '!' => Token::Not,
+            '<' | '>' => {
+                let or_equal = next == Some('=');
+                if or_equal {
+                    i += 1;
+                }
+                Token::Op(match (c, or_equal) {
+                    ('<', false) => CmpOp::Lt,
+                    ('<', true) => CmpOp::Le,
+                    ('>', false) => CmpOp::Gt,
+                    _ => CmpOp::Ge,
+                })
+            }
+            '"' => {
+                let (text, end) = read_delimited(&chars, i, '"')?;
+                i = end;
+                Token::Str(text)
+            }
+            '/' => {
+                let (pattern, end) = read_delimited(&chars, i, '/')?;
+                i = end + 1;
+                let mut flags = String::new();
+                while i < chars.len() && chars[i].is_ascii_alphabetic() {
+                    flags.push(chars[i]);
+                    i += 1;
+                }
+                tokens.push((Token::Regex(pattern, flags), start));
+                continue;
+            }
+            c if c.is_ascii_digit() || (c == '-' && next.is_some_and(|n| n.is_ascii_digit())) => {
+                let mut end = i + 1;
+                while end < chars.len() && (chars[end].is_ascii_digit() || chars[end] == '.') {
+                    end += 1;
+                }
+                let text: String = chars[i..end].iter().collect();
+                i = end;
+                let number = text
+                    .parse()
+                    .map_err(|_| format!("invalid number '{}' at column {}", text, start + 1))?;
+                tokens.push((Token::Number(number), start));
+                continue;
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut end = i + 1;
+                while end < chars.len() && (chars[end].is_ascii_alphanumeric() || chars[end] == '_')
+                {
+                    end += 1;
+                }
+                let word: String = chars[i..end].iter().collect();
+                i = end;
+                let token = match word.as_str() {
+                    "and" => Token::And,
+                    "or" => Token::Or,
+                    "not" => Token::Not,
+                    "in" => Token::Op(CmpOp::In),
+                    "matches" => Token::Op(CmpOp::Matches),
+                    _ => Token::Ident(word),
+                };
+                tokens.push((token, start));
+                continue;
+            }
+            other => return Err(format!("unexpected '{}' at column {}", other, start + 1)),
+        };
+
+        tokens.push((token, start));
+        i += 1;
+    }
+
+    Ok(tokens)
+}
+
+/// Read a `"..."` or `/.../` literal starting at `open`; a backslash escapes
+/// the delimiter. Returns the contents and the index of the closing delimiter.
+fn read_delimited(chars: &[char], open: usize, delimiter: char) -> Result<(String, usize), String> {
+    let mut text = String::new();
+    let mut i = open + 1;
+    while i < chars.len() {
+        match chars[i] {
+            '\\' if chars.get(i + 1) == Some(&delimiter) => {
+                text.push(delimiter);
+                i += 2;
+            }
+            c if c == delimiter => return Ok((text, i)),
+            c => {
+                text.push(c);
+                i += 1;
+            }
+        }
+    }
+    Err(format!("unterminated {} at column {}", delimiter, open + 1))
+}
+
+struct Parser {
+    tokens: Vec<(Token, usize)>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&(Token, usize)> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<(Token, usize), String> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| "unexpected end of condition".to_string())?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn eat(&mut self, token: &Token) -> bool {
+        if self.peek().map(|(t, _)| t) == Some(token) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn or(&mut self) -> Result<Node, String> {
+        let mut expr = self.and()?;
+        while self.eat(&Token::Or) {
+            expr = Node::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Node, String> {
+        let mut expr = self.unary()?;
+        while self.eat(&Token::And) {
+            expr = Node::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.eat(&Token::Not) {
+            return Ok(Node::Not(Box::new(self.unary()?)));
+        }
+        if self.eat(&Token::LParen) {
+            let expr = self.or()?;
+            return match self.next()? {
+                (Token::RParen, _) => Ok(expr),
+                (token, at) => Err(format!(
+                    "expected ')' but found {} at column {}",
+                    token,
+                    at + 1
+                )),
+            };
+        }
+        self.comparison()
+    }
+
+    fn comparison(&mut self) -> Result<Node, String> {
+        let (name, at) = match self.next()? {
+            (Token::Ident(name), at) => (name, at),
+            (token, at) => {
+                return Err(format!(
+                    "expected a field but found {} at column {}",
+                    token,
+                    at + 1
+                ))
+            }
+        };
+        let field = Field::parse(&name).ok_or_else(|| {
+            let known: Vec<&str> = FIELDS.iter().map(|(n, _)| *n).collect();
+            format!(
+                "unknown field '{}' at column {} (known fields: {})",
+                name,
+                at + 1,
+                known.join(", ")
+            )
+        })?;
+
+        let op = match self.peek() {
+            Some((Token::Op(op), _)) => *op,
+            _ if field.field_type() == FieldType::Bool => return Ok(Node::Flag(field)),
+            _ => return Err(format!("field '{}' needs a comparison", name)),
+        };
+        self.pos += 1;
+
+        let operand = self.operand(field, op)?;
+        Ok(Node::Compare(field, op, operand))
+    }
+
+    fn operand(&mut self, field: Field, op: CmpOp) -> Result<Operand, String> {
+        let field_type = field.field_type();
+        let (token, at) = self.next()?;
+        let mismatch = |what: &str| {
+            format!(
+                "'{}' cannot be used with {} on field '{}' at column {}",
+                op.symbol(),
+                what,
+                field.name(),
+                at + 1
+            )
+        };
+
+        match op {
+            CmpOp::Matches | CmpOp::NotMatches => {
+                if field_type != FieldType::Text {
+                    return Err(mismatch("a non-text field"));
+                }
+                match token {
+                    Token::Regex(pattern, flags) => {
+                        Ok(Operand::Regex(compile_regex(&pattern, &flags, at)?))
+                    }
+                    other => Err(mismatch(&other.to_string())),
+                }
+            }
+            CmpOp::In => {
+                if token != Token::LBracket {
+                    return Err(format!("expected '[' after 'in' at column {}", at + 1));
+                }
+                let mut items = Vec::new();
+                loop {
+                    if self.eat(&Token::RBracket) {
+                        break;
+                    }
+                    let (item, item_at) = self.next()?;
+                    items.push(literal(field, item, item_at)?);
+                    if !self.eat(&Token::Comma)
+                        && !matches!(self.peek(), Some((Token::RBracket, _)))
+                    {
+                        return Err(format!(
+                            "expected ',' or ']' in list at column {}",
+                            item_at + 1
+                        ));
+                    }
+                }
+                Ok(Operand::List(items))
+            }
+            CmpOp::Lt | CmpOp::Le | CmpOp::Gt | CmpOp::Ge
+                if matches!(field_type, FieldType::Text | FieldType::Bool) =>
+            {
+                Err(mismatch("an ordering"))
+            }
+            _ => Ok(Operand::Value(literal(field, token, at)?)),
+        }
+    }
+}
+
+/// Convert a literal to the field's type, so `auth == "wpa2psk"` compares
+/// against the parsed suite rather than the raw text.
+fn literal(field: Field, token: Token, at: usize) -> Result<Value, String> {
+    let wrong = |token: &Token| {
+        format!(
+            "field '{}' cannot be compared with {} at column {}",
+            field.name(),
+            token,
+            at + 1
+        )
+    };
+
+    match (field.field_type(), token) {
+        (FieldType::Text, Token::Str(s)) if field == Field::Bssid => {
+            Ok(Value::Text(s.to_lowercase()))
+        }
+        (FieldType::Text, Token::Str(s)) => Ok(Value::Text(s)),
+        (FieldType::Number, Token::Number(n)) => Ok(Value::Number(n)),
+        (FieldType::Bool, Token::Ident(word)) if word == "true" || word == "false" => {
+            Ok(Value::Bool(word == "true"))
+        }
+        (FieldType::Auth, Token::Str(s)) => match AuthSuite::parse(&s) {
+            AuthSuite::Other(_) => Err(format!(
+                "unknown authentication {:?} at column {}",
+                s,
+                at + 1
+            )),
+            auth => Ok(Value::Auth(auth)),
+        },
+        (FieldType::Cipher, Token::Str(s)) => match Cipher::parse(&s) {
+            Cipher::Other(_) => Err(format!("unknown cipher {:?} at column {}", s, at + 1)),
+            cipher => Ok(Value::Cipher(cipher)),
+        },
+        (FieldType::Grade, Token::Str(s)) => match s.to_uppercase().as_str() {
+            "L" => Ok(Value::Grade(RiskGrade::Low)),
+            "M" => Ok(Value::Grade(RiskGrade::Medium)),
+            "H" => Ok(Value::Grade(RiskGrade::High)),
+            "C" => Ok(Value::Grade(RiskGrade::Critical)),
+            _ => Err(format!(
+                "grade must be one of L, M, H, C at column {}",
+                at + 1
+            )),
+        },
+        (_, token) => Err(wrong(&token)),
+    }
+}
+
+fn compile_regex(pattern: &str, flags: &str, at: usize) -> Result<Regex, String> {
+    let mut builder = regex::RegexBuilder::new(pattern);
+    for flag in flags.chars() {
+        match flag {
+            'i' => builder.case_insensitive(true),
+            'x' => builder.ignore_whitespace(true),
+            other => {
+                return Err(format!(
+                    "unknown regex flag '{}' at column {}",
+                    other,
+                    at + 1
+                ))
+            }
+        };
+    }
+    builder
+        .build()
+        .map_err(|e| format!("invalid regex at column {}: {}", at + 1, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Expr;
+    use crate::structures::{AuthSuite, Cipher, Signal, WifiNetwork};
+
+    fn network(ssid: &str, bssid: &str, auth: AuthSuite) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            authentication: auth,
+            encryption: Cipher::Ccmp,
+            signal: Some(Signal::from_percent(70)),
+            channel: Some(6),
+            ..Default::default()
+        }
+    }
+
+    fn eval(source: &str, net: &WifiNetwork) -> bool {
+        Expr::parse(source).unwrap().eval(net, &mut Vec::new())
+    }
+
+    #[test]
+    fn test_comparisons() {
+        let net = network("CorpNet", "02:11:22:33:44:55", AuthSuite::Open);
+
+        assert!(eval(r#"auth == "Open" && ssid ~ /corp/i"#, &net));
+        assert!(!eval(r#"ssid ~ /corp/"#, &net));
+        assert!(eval(r#"ssid == "CorpNet""#, &net));
+        assert!(eval(r#"bssid == "02:11:22:33:44:55""#, &net));
+        assert!(eval(r#"bssid in ["02:11:22:33:44:55"]"#, &net));
+        assert!(eval(r#"locally_administered"#, &net));
+        assert!(eval(r#"signal >= 70 and channel in [1, 6, 11]"#, &net));
+        assert!(eval(r#"not (dbm < -80) or wps"#, &net));
+        assert!(eval(r#"auth < "WPA2-Personal""#, &net));
+        assert!(eval(r#"cipher == "AES""#, &net));
+        assert!(eval(r#"ssid !~ /guest/i"#, &net));
+    }
+
+    #[test]
+    fn test_ssid_case_matters() {
+        let impostor = network("corpnet", "02:11:22:33:44:55", AuthSuite::Open);
+
+        assert!(!eval(r#"ssid == "CorpNet""#, &impostor));
+        assert!(!eval(r#"ssid in ["CorpNet"]"#, &impostor));
+        assert!(eval(r#"ssid != "CorpNet""#, &impostor));
+
+        // MACs are written either way
+        let upper = network("CorpNet", "AA:BB:CC:00:00:01", AuthSuite::Open);
+        assert!(eval(r#"bssid == "aa:bb:cc:00:00:01""#, &upper));
+        assert!(eval(r#"bssid in ["AA:BB:CC:00:00:01"]"#, &upper));
+        assert!(eval(r#"bssid == "Aa:bB:cc:00:00:01""#, &upper));
+    }
+
+    #[test]
+    fn test_missing_values_never_match() {
+        let mut net = network("Home", "00:11:22:33:44:55", AuthSuite::Wpa2Personal);
+        net.channel = None;
+
+        assert!(!eval("channel == 6", &net));
+        assert!(!eval("channel != 6", &net));
+        assert!(!eval("!(channel == 6)", &net));
+        assert!(!eval(
+            "not (channel in [1, 6, 11]) && ssid == \"Home\"",
+            &net
+        ));
+        assert!(!eval("wps", &net));
+        assert!(!eval("!wps", &net));
+        assert!(!eval("locally_administered", &net));
+
+        // Known values still decide on their own
+        assert!(eval("channel == 6 || ssid == \"Home\"", &net));
+        assert!(!eval("channel == 6 && ssid == \"Other\"", &net));
+        assert!(eval("!(channel == 6 && ssid == \"Other\")", &net));
+    }
+
+    #[test]
+    fn test_trace_records_comparisons() {
+        let net = network("CorpNet", "00:11:22:33:44:55", AuthSuite::Wpa2Personal);
+        let mut trace = Vec::new();
+
+        let matched = Expr::parse(r#"auth == "Open" && ssid ~ /corp/i"#)
+            .unwrap()
+            .eval(&net, &mut trace);
+
+        assert!(!matched);
+        // The SSID check is short-circuited away.
+        assert_eq!(trace, [r#"auth ("WPA2-Personal") == "Open" => false"#]);
+    }
+
+    #[test]
+    fn test_load_time_errors() {
+        let err = |source: &str| Expr::parse(source).unwrap_err();
+
+        assert!(err("sssid == \"x\"").contains("unknown field 'sssid'"));
+        assert!(err("signal ~ /x/").contains("non-text field"));
+        assert!(err("ssid > \"a\"").contains("ordering"));
+        assert!(err("auth == \"WPA9\"").contains("unknown authentication"));
+        assert!(err("ssid ~ /[/").contains("invalid regex"));
+        assert!(err("ssid").contains("needs a comparison"));
+        assert!(err("(wps").contains("unexpected end"));
+        assert!(err("wps wps").contains("column 5"));
+        assert!(err("ssid == \"open").contains("unterminated"));
+        assert!(err("signal == \"high\"").contains("cannot be compared"));
+    }
+}
@@ -0,0 +1,484 @@
+//! Declarative detection rules evaluated after risk scoring.
+//!
+//! Rules live in `detection_rules.toml` in the app data dir:
+//!
+//! ```toml
+//! [[rule]]
+//! name = "open-corp"
+//! priority = 100
+//! when = 'auth == "Open" && ssid ~ /corp/i'
+//! grade = "C"
+//! reason = "Open network using the corporate name {ssid}"
+//! ```
+//!
+//! Rules are tried from the highest priority down (file order breaks ties)
+//! and the first one that matches sets the network's grade, whether that
+//! raises or lowers it. The exception is a network a detector flagged as an
+//! evil twin, KARMA radio, look-alike or baseline mismatch: a rule may raise
+//! its grade but not lower it, so a verdict never contradicts the flag.
+//! `{ssid}` and `{bssid}` in a reason are filled in.
+
+mod expr;
+
+pub use expr::Expr;
+
+use crate::structures::{RiskFactorKind, RiskGrade, WifiNetwork};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::path::Path;
+use std::sync::Arc;
+
+pub const RULES_FILE: &str = "detection_rules.toml";
+
+#[derive(Debug, Clone)]
+pub struct Rule {
+    pub name: String,
+    pub priority: i32,
+    pub condition: Expr,
+    pub grade: RiskGrade,
+    pub reason: String,
+}
+
+/// Result of trying one rule against one network.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RuleTrace {
+    pub rule: String,
+    pub matched: bool,
+    /// Each comparison made, e.g. `auth ("Open") == "Open" => true`.
+    pub steps: Vec<String>,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct RuleSet {
+    rules: Vec<Rule>,
+}
+
+pub type SharedRuleSet = Arc<RuleSet>;
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleFile {
+    #[serde(default, rename = "rule")]
+    rules: Vec<RuleSpec>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RuleSpec {
+    name: String,
+    #[serde(default)]
+    priority: i32,
+    when: String,
+    grade: RiskGrade,
+    reason: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+impl RuleSet {
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let file: RuleFile =
+            toml::from_str(content).map_err(|e| format!("Invalid detection rules: {}", e))?;
+
+        let mut errors = Vec::new();
+        let mut rules = Vec::new();
+        let mut names = HashSet::new();
+        for spec in file.rules.into_iter().filter(|r| r.enabled) {
+            if !names.insert(spec.name.clone()) {
+                errors.push(format!("rule '{}' is defined twice", spec.name));
+                continue;
+            }
+            match Expr::parse(&spec.when) {
+                Ok(condition) => rules.push(Rule {
+                    name: spec.name,
+                    priority: spec.priority,
+                    condition,
+                    grade: spec.grade,
+                    reason: spec.reason,
+                }),
+                Err(e) => errors.push(format!("rule '{}': {}", spec.name, e)),
+            }
+        }
+
+        if !errors.is_empty() {
+            return Err(format!("Invalid detection rules: {}", errors.join("; ")));
+        }
+
+        // Stable, so equal priorities keep file order.
+        rules.sort_by_key(|r| std::cmp::Reverse(r.priority));
+        Ok(Self { rules })
+    }
+
+    /// Load `detection_rules.toml` from `dir`; no file means no rules.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        let path = dir.join(RULES_FILE);
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        let content = std::fs::read_to_string(&path)
+            .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+        Self::from_toml(&content).map_err(|e| format!("{}: {}", path.display(), e))
+    }
+
+    pub fn rules(&self) -> &[Rule] {
+        &self.rules
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Try rules in priority order and return the first match together with
+    /// the trace of every rule tried up to it.
+    pub fn evaluate(&self, net: &WifiNetwork) -> (Option<&Rule>, Vec<RuleTrace>) {
+        let mut traces = Vec::new();
+        for rule in &self.rules {
+            let mut steps = Vec::new();
+            let matched = rule.condition.eval(net, &mut steps);
+            traces.push(RuleTrace {
+                rule: rule.name.clone(),
+                matched,
+                steps,
+            });
+            if matched {
+                return (Some(rule), traces);
+            }
+        }
+        (None, traces)
+    }
+
+    /// Apply the first matching rule to each network. With `WIDS_RULE_TRACE`
+    /// set, every evaluation is printed.
+    pub fn apply(&self, networks: &mut [WifiNetwork]) {
+        if self.rules.is_empty() {
+            return;
+        }
+        let tracing = std::env::var_os("WIDS_RULE_TRACE").is_some();
+
+        for net in networks.iter_mut() {
+            let (matched, traces) = self.evaluate(net);
+            if tracing {
+                for trace in &traces {
+                    println!(
+                        "[rules] {} {} {}: {}",
+                        net.bssid,
+                        trace.rule,
+                        if trace.matched { "matched" } else { "no match" },
+                        trace.steps.join("; ")
+                    );
+                }
+            }
+
+            if let Some(rule) = matched {
+                if detector_grade(net).is_some_and(|grade| rule.grade < grade) {
+                    if tracing {
+                        println!(
+                            "[rules] {} {}: not lowering a detector finding",
+                            net.bssid, rule.name
+                        );
+                    }
+                    continue;
+                }
+                let reason = rule
+                    .reason
+                    .replace("{ssid}", &net.ssid)
+                    .replace("{bssid}", &net.bssid);
+                net.override_risk(
+                    rule.grade,
+                    RiskFactorKind::Rule,
+                    format!("{} (rule '{}')", reason, rule.name),
+                );
+            }
+        }
+    }
+}
+
+/// The grade of a network a detector flagged, which rules may not lower.
+fn detector_grade(net: &WifiNetwork) -> Option<RiskGrade> {
+    let assessment = net.risk_assessment.as_ref()?;
+    let flagged = net.is_evil_twin
+        || assessment.factors.iter().any(|f| {
+            matches!(
+                f.kind,
+                RiskFactorKind::EvilTwin
+                    | RiskFactorKind::Karma
+                    | RiskFactorKind::Lookalike
+                    | RiskFactorKind::Baseline
+            )
+        });
+    flagged.then_some(assessment.grade)
+}
+
+/// Load the rules for this run from `dir`; an invalid file is reported and
+/// no rules are applied.
+pub fn load_rules(dir: &Path) -> SharedRuleSet {
+    match RuleSet::load(dir) {
+        Ok(rules) => {
+            if !rules.is_empty() {
+                println!("Loaded {} detection rules", rules.rules().len());
+            }
+            Arc::new(rules)
+        }
+        Err(e) => {
+            eprintln!("{}; no detection rules applied", e);
+            Arc::new(RuleSet::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RuleSet;
+    use crate::structures::{AuthSuite, Cipher, RiskFactorKind, RiskGrade, Signal, WifiNetwork};
+    use crate::wifi_functions::RiskPolicy;
+
+    const RULES: &str = r#"
+        [[rule]]
+        name = "corp-open"
+        priority = 100
+        when = 'auth == "Open" && ssid ~ /corp/i'
+        grade = "C"
+        reason = "Open network named like ours: {ssid}"
+
+        [[rule]]
+        name = "corp-spoofed-mac"
+        priority = 50
+        when = 'locally_administered && ssid == "CorpNet"'
+        grade = "H"
+        reason = "Randomised MAC advertising the corporate SSID"
+
+        [[rule]]
+        name = "corp-known-ap"
+        priority = 200
+        when = 'bssid in ["00:11:22:33:44:55"] && auth >= "WPA2-Enterprise"'
+        grade = "L"
+        reason = "Known corporate access point"
+
+        [[rule]]
+        name = "disabled"
+        when = 'ssid ~ /.*/'
+        grade = "C"
+        reason = "never applied"
+        enabled = false
+    "#;
+
+    fn scored(ssid: &str, bssid: &str, auth: AuthSuite, encryption: Cipher) -> WifiNetwork {
+        let mut net = WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            authentication: auth,
+            encryption,
+            signal: Some(Signal::from_percent(60)),
+            ..Default::default()
+        };
+        let policy = RiskPolicy::default();
+        let assessment = crate::wifi_functions::calculate_risk(
+            &policy,
+            &net.authentication,
+            &net.encryption,
+            net.signal,
+            &net.ssid,
+        );
+        net.set_risk(assessment);
+        net
+    }
+
+    #[test]
+    fn test_priorities_and_first_match() {
+        let rules = RuleSet::from_toml(RULES).unwrap();
+        let names: Vec<&str> = rules.rules().iter().map(|r| r.name.as_str()).collect();
+        assert_eq!(names, ["corp-known-ap", "corp-open", "corp-spoofed-mac"]);
+
+        let mut networks = vec![
+            scored(
+                "CorpNet",
+                "00:11:22:33:44:55",
+                AuthSuite::Wpa2Enterprise,
+                Cipher::Ccmp,
+            ),
+            scored(
+                "CorpNet-Guest",
+                "66:77:88:99:aa:bb",
+                AuthSuite::Open,
+                Cipher::None,
+            ),
+            scored(
+                "CorpNet",
+                "02:00:00:00:00:01",
+                AuthSuite::Wpa2Personal,
+                Cipher::Ccmp,
+            ),
+            scored(
+                "HomeNet",
+                "02:00:00:00:00:02",
+                AuthSuite::Wpa2Personal,
+                Cipher::Ccmp,
+            ),
+        ];
+        rules.apply(&mut networks);
+
+        assert_eq!(networks[0].risk, "L");
+        assert_eq!(networks[1].risk, "C");
+        assert_eq!(networks[2].risk, "H");
+        assert_eq!(networks[3].risk, "L");
+
+        let factor = networks[1]
+            .risk_assessment
+            .as_ref()
+            .unwrap()
+            .factors
+            .last()
+            .unwrap()
+            .clone();
+        assert_eq!(factor.kind, RiskFactorKind::Rule);
+        assert_eq!(
+            factor.reason,
+            "Open network named like ours: CorpNet-Guest (rule 'corp-open')"
+        );
+        assert!(networks[3]
+            .risk_assessment
+            .as_ref()
+            .unwrap()
+            .factors
+            .iter()
+            .all(|f| f.kind != RiskFactorKind::Rule));
+    }
+
+    #[test]
+    fn test_rule_can_lower_grade() {
+        let rules = RuleSet::from_toml(
+            r#"
+            [[rule]]
+            name = "lab"
+            when = 'ssid == "Lab" && grade >= "M"'
+            grade = "L"
+            reason = "Lab network, open by design"
+            "#,
+        )
+        .unwrap();
+
+        let mut networks = vec![scored(
+            "Lab",
+            "00:00:00:00:00:01",
+            AuthSuite::Open,
+            Cipher::None,
+        )];
+        assert_eq!(networks[0].risk, "C");
+        rules.apply(&mut networks);
+
+        let assessment = networks[0].risk_assessment.as_ref().unwrap();
+        assert_eq!(networks[0].risk, "L");
+        assert_eq!(assessment.grade, RiskGrade::Low);
+        assert!(assessment.verdict.contains("safe"));
+    }
+
+    #[test]
+    fn test_detector_findings_are_only_raised() {
+        let rules = RuleSet::from_toml(
+            r#"
+            [[rule]]
+            name = "corp"
+            when = 'ssid == "CorpNet"'
+            grade = "M"
+            reason = "Corporate network"
+            "#,
+        )
+        .unwrap();
+        let flag = |grade| {
+            let mut net = scored(
+                "CorpNet",
+                "00:11:22:33:44:55",
+                AuthSuite::Wpa2Personal,
+                Cipher::Ccmp,
+            );
+            net.escalate_risk(grade, 0, RiskFactorKind::Karma, "KARMA".to_string());
+            net
+        };
+
+        let mut networks = vec![flag(RiskGrade::Critical), flag(RiskGrade::Low)];
+        rules.apply(&mut networks);
+
+        assert_eq!(networks[0].risk, "C");
+        assert_eq!(networks[1].risk, "M");
+    }
+
+    #[test]
+    fn test_trace_covers_rules_tried() {
+        let rules = RuleSet::from_toml(RULES).unwrap();
+        let net = scored(
+            "CorpNet",
+            "02:00:00:00:00:01",
+            AuthSuite::Wpa2Personal,
+            Cipher::Ccmp,
+        );
+
+        let (matched, traces) = rules.evaluate(&net);
+
+        assert_eq!(matched.unwrap().name, "corp-spoofed-mac");
+        assert_eq!(traces.len(), 3);
+        assert!(!traces[0].matched);
+        assert_eq!(
+            traces[0].steps,
+            [r#"bssid ("02:00:00:00:00:01") in ["00:11:22:33:44:55"] => false"#]
+        );
+        assert!(traces[2].matched);
+        assert_eq!(traces[2].steps.len(), 2);
+    }
+
+    #[test]
+    fn test_loader_errors() {
+        let err = RuleSet::from_toml(
+            r#"
+            [[rule]]
+            name = "typo"
+            when = 'sid == "x"'
+            grade = "H"
+            reason = "x"
+
+            [[rule]]
+            name = "bad-grade"
+            when = 'wps'
+            grade = "X"
+            reason = "x"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("Invalid detection rules"), "{}", err);
+
+        let err = RuleSet::from_toml(
+            r#"
+            [[rule]]
+            name = "typo"
+            when = 'sid == "x"'
+            grade = "H"
+            reason = "x"
+
+            [[rule]]
+            name = "typo"
+            when = 'wps'
+            grade = "H"
+            reason = "x"
+            "#,
+        )
+        .unwrap_err();
+        assert!(err.contains("rule 'typo': unknown field 'sid'"), "{}", err);
+        assert!(err.contains("defined twice"), "{}", err);
+    }
+
+    #[test]
+    fn test_load_from_dir() {
+        let dir = std::env::temp_dir().join(format!("wids-rules-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert!(RuleSet::load(&dir).unwrap().is_empty());
+
+        std::fs::write(dir.join(super::RULES_FILE), RULES).unwrap();
+        assert_eq!(RuleSet::load(&dir).unwrap().rules().len(), 3);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,32 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkEventKind {
+    /// The station started associating; names the SSID behind a BSSID.
+    Associating,
+    Connected,
+    Disconnected,
+    /// Association or authentication was refused or timed out.
+    ConnectionFailed,
+}
+
+/// A change in the adapter's link to its access point, as reported by the
+/// WLAN service.
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LinkEvent {
+    pub at: DateTime<Utc>,
+    pub kind: LinkEventKind,
+    /// Lowercase, when the platform reports it.
+    pub bssid: Option<String>,
+    pub ssid: Option<String>,
+    /// 802.11 reason code of a disconnect, or status code of a refused
+    /// association.
+    pub code: Option<u16>,
+    /// Readable reason, from `code` or the platform's own reason code.
+    pub reason: Option<String>,
+    /// The station itself ended the link (the user disconnected, it
+    /// roamed), so the access point had no part in it.
+    pub locally_generated: bool,
+}
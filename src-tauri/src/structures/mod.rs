@@ -1,5 +1,15 @@
+mod link_event;
+mod risk;
+mod security;
+mod signal;
+mod threat;
 mod wifi_network;
 
 
-pub use wifi_network::WifiNetwork;
+pub use link_event::{LinkEvent, LinkEventKind};
+pub use risk::{RiskAssessment, RiskFactor, RiskFactorKind, RiskGrade};
+pub use security::{AuthSuite, Cipher};
+pub use signal::Signal;
+pub use threat::{DetectedThreat, MonitoringState, NetworkHistory};
+pub use wifi_network::{Band, CipherSuites, NetworkType, RadioType, WifiNetwork};
 
@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Letter grade shown in the scanner table.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RiskGrade {
+    #[serde(rename = "L")]
+    Low,
+    #[serde(rename = "M")]
+    Medium,
+    #[serde(rename = "H")]
+    High,
+    #[serde(rename = "C")]
+    Critical,
+}
+
+impl RiskGrade {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Low => "L",
+            Self::Medium => "M",
+            Self::High => "H",
+            Self::Critical => "C",
+        }
+    }
+
+    /// Severity name used in threat reports.
+    pub fn severity(&self) -> &'static str {
+        match self {
+            Self::Low => "Low",
+            Self::Medium => "Medium",
+            Self::High => "High",
+            Self::Critical => "Critical",
+        }
+    }
+}
+
+impl fmt::Display for RiskGrade {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskFactorKind {
+    Authentication,
+    Cipher,
+    Signal,
+    SsidKeyword,
+    EvilTwin,
+    /// A detection rule from `detection_rules.toml` set the grade.
+    Rule,
+    /// The network differs from its SSID's trusted baseline.
+    Baseline,
+    /// The SSID imitates a whitelisted or trusted one.
+    Lookalike,
+    /// One radio advertises more SSIDs than a real access point would.
+    Karma,
+}
+
+/// One contribution to a risk score.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RiskFactor {
+    pub kind: RiskFactorKind,
+    pub weight: u32,
+    pub reason: String,
+}
+
+impl RiskFactor {
+    /// Evil-twin, rule, baseline, look-alike and KARMA factors explain the
+    /// grade even when they add no weight.
+    fn is_finding(&self) -> bool {
+        matches!(
+            self.kind,
+            RiskFactorKind::EvilTwin
+                | RiskFactorKind::Rule
+                | RiskFactorKind::Baseline
+                | RiskFactorKind::Lookalike
+                | RiskFactorKind::Karma
+        )
+    }
+}
+
+/// Score, grade and the factors that produced them.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct RiskAssessment {
+    pub score: u32,
+    pub grade: RiskGrade,
+    pub factors: Vec<RiskFactor>,
+    /// One-sentence recommendation for the scanner's detail view.
+    pub verdict: String,
+}
+
+impl RiskAssessment {
+    pub fn new(factors: Vec<RiskFactor>, grade_for: impl Fn(u32) -> RiskGrade) -> Self {
+        let score = factors.iter().map(|f| f.weight).sum();
+        let grade = grade_for(score);
+        let mut assessment = Self {
+            score,
+            grade,
+            factors,
+            verdict: String::new(),
+        };
+        assessment.verdict = assessment.build_verdict();
+        assessment
+    }
+
+    /// Raise the grade to at least `grade`, recording why. `floor` is the
+    /// lowest score of that grade; the factor's weight makes up the gap so
+    /// the score and grade keep agreeing.
+    pub fn escalate(&mut self, grade: RiskGrade, floor: u32, kind: RiskFactorKind, reason: String) {
+        self.factors.push(RiskFactor {
+            kind,
+            weight: floor.saturating_sub(self.score),
+            reason,
+        });
+        self.score = self.score.max(floor);
+        self.grade = self.grade.max(grade);
+        self.verdict = self.build_verdict();
+    }
+
+    /// Set the grade outright, up or down, keeping the score as computed.
+    pub fn override_grade(&mut self, grade: RiskGrade, kind: RiskFactorKind, reason: String) {
+        self.factors.push(RiskFactor {
+            kind,
+            weight: 0,
+            reason,
+        });
+        self.grade = grade;
+        self.verdict = self.build_verdict();
+    }
+
+    fn build_verdict(&self) -> String {
+        let mut reasons: Vec<&RiskFactor> = self
+            .factors
+            .iter()
+            .filter(|f| f.weight > 0 || f.is_finding())
+            .collect();
+        // Findings that set the grade lead, then the heaviest weights.
+        reasons.sort_by_key(|f| (!f.is_finding(), std::cmp::Reverse(f.weight)));
+        let reasons = reasons
+            .iter()
+            .map(|f| f.reason.to_lowercase())
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        match self.grade {
+            RiskGrade::Low => "This network is safe to connect.".to_string(),
+            RiskGrade::Medium => format!(
+                "This network is moderately safe ({}), but be careful what you send over it.",
+                reasons
+            ),
+            RiskGrade::High | RiskGrade::Critical => format!(
+                "This network is harmful or dangerous ({}). Better avoid it or add it to the blacklist.",
+                reasons
+            ),
+        }
+    }
+}
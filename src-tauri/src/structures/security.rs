@@ -0,0 +1,242 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Authentication and key management a network advertises, ordered from
+/// weakest to strongest.
+///
+/// Serialized with the names netsh prints ("WPA2-Personal", "Open", ...), which
+/// is what the frontend displays and sends back to `connect_wifi`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum AuthSuite {
+    /// A value none of the backends' vocabularies covers, kept verbatim.
+    Other(String),
+    Open,
+    /// Legacy WEP shared-key authentication.
+    Shared,
+    /// Opportunistic Wireless Encryption: encrypted, but unauthenticated.
+    Owe,
+    WpaPersonal,
+    WpaEnterprise,
+    Wpa2Personal,
+    Wpa2Enterprise,
+    /// SAE and PSK offered side by side; a client can still be downgraded to PSK.
+    Wpa3Transition,
+    Wpa3Personal,
+    Wpa3Enterprise,
+}
+
+impl AuthSuite {
+    /// Parse the authentication names used by netsh (`show networks` and
+    /// profile XML), NetworkManager key-mgmt values and iw AKM names.
+    pub fn parse(value: &str) -> Self {
+        let normalized = value.trim().to_lowercase().replace(['_', ' '], "-");
+        match normalized.as_str() {
+            "" | "unknown" => Self::Other(String::new()),
+            "open" | "none" | "open-system" => Self::Open,
+            "shared" | "shared-key" | "wep" => Self::Shared,
+            "owe" | "owe-tm" | "enhanced-open" => Self::Owe,
+            "wpa-personal" | "wpapsk" | "wpa-psk-tkip" => Self::WpaPersonal,
+            "wpa-enterprise" | "wpa" | "wpa-eap-tkip" => Self::WpaEnterprise,
+            "wpa2-personal" | "wpa2psk" | "wpa-psk" | "psk" => Self::Wpa2Personal,
+            "wpa2-enterprise" | "wpa2" | "wpa-eap" | "ieee-802.1x" | "802.1x" => {
+                Self::Wpa2Enterprise
+            }
+            "wpa2/wpa3-personal" | "wpa3-transition" | "sae-psk" | "psk-sae" => {
+                Self::Wpa3Transition
+            }
+            "wpa3-personal" | "wpa3sae" | "wpa3-sae" | "sae" => Self::Wpa3Personal,
+            "wpa3-enterprise"
+            | "wpa3ent"
+            | "wpa3ent192"
+            | "wpa3-enterprise-192-bit"
+            | "wpa-eap-suite-b-192" => Self::Wpa3Enterprise,
+            _ => Self::Other(value.trim().to_string()),
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        matches!(self, Self::Open | Self::Owe)
+    }
+
+    pub fn is_enterprise(&self) -> bool {
+        matches!(
+            self,
+            Self::WpaEnterprise | Self::Wpa2Enterprise | Self::Wpa3Enterprise
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(value) => value,
+            Self::Open => "Open",
+            Self::Shared => "Shared",
+            Self::Owe => "OWE",
+            Self::WpaPersonal => "WPA-Personal",
+            Self::WpaEnterprise => "WPA-Enterprise",
+            Self::Wpa2Personal => "WPA2-Personal",
+            Self::Wpa2Enterprise => "WPA2-Enterprise",
+            Self::Wpa3Transition => "WPA2/WPA3-Personal",
+            Self::Wpa3Personal => "WPA3-Personal",
+            Self::Wpa3Enterprise => "WPA3-Enterprise",
+        }
+    }
+}
+
+impl Default for AuthSuite {
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+
+/// Pairwise cipher, ordered from weakest to strongest.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(from = "String", into = "String")]
+pub enum Cipher {
+    Other(String),
+    None,
+    Wep,
+    Tkip,
+    Ccmp,
+    Gcmp,
+}
+
+impl Cipher {
+    /// Parse cipher names from netsh ("CCMP", "AES", profile "none"),
+    /// NetworkManager pairwise values and iw suite names.
+    pub fn parse(value: &str) -> Self {
+        let normalized = value.trim().to_lowercase();
+        match normalized.as_str() {
+            "" | "unknown" => Self::Other(String::new()),
+            "none" | "open" => Self::None,
+            "wep" | "wep-40" | "wep-104" | "wep40" | "wep104" => Self::Wep,
+            "tkip" => Self::Tkip,
+            "ccmp" | "aes" | "ccmp-128" | "ccmp-256" => Self::Ccmp,
+            "gcmp" | "gcmp-128" | "gcmp-256" => Self::Gcmp,
+            _ => Self::Other(value.trim().to_string()),
+        }
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Other(value) => value,
+            Self::None => "None",
+            Self::Wep => "WEP",
+            Self::Tkip => "TKIP",
+            Self::Ccmp => "CCMP",
+            Self::Gcmp => "GCMP",
+        }
+    }
+}
+
+impl Default for Cipher {
+    fn default() -> Self {
+        Self::Other(String::new())
+    }
+}
+
+impl fmt::Display for AuthSuite {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl fmt::Display for Cipher {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl From<String> for AuthSuite {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<AuthSuite> for String {
+    fn from(value: AuthSuite) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+impl From<String> for Cipher {
+    fn from(value: String) -> Self {
+        Self::parse(&value)
+    }
+}
+
+impl From<Cipher> for String {
+    fn from(value: Cipher) -> Self {
+        value.as_str().to_string()
+    }
+}
+
+/// Lets tests and call sites compare against the netsh names directly.
+impl PartialEq<&str> for AuthSuite {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl PartialEq<&str> for Cipher {
+    fn eq(&self, other: &&str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AuthSuite, Cipher};
+
+    #[test]
+    fn test_every_backend_vocabulary() {
+        // netsh scan and profile XML
+        assert_eq!(AuthSuite::parse("WPA2-Personal"), AuthSuite::Wpa2Personal);
+        assert_eq!(AuthSuite::parse("WPA3SAE"), AuthSuite::Wpa3Personal);
+        assert_eq!(AuthSuite::parse("open"), AuthSuite::Open);
+        // NetworkManager key-mgmt
+        assert_eq!(AuthSuite::parse("wpa-psk"), AuthSuite::Wpa2Personal);
+        assert_eq!(
+            AuthSuite::parse("wpa-eap-suite-b-192"),
+            AuthSuite::Wpa3Enterprise
+        );
+        assert_eq!(AuthSuite::parse("owe"), AuthSuite::Owe);
+        // iw AKM names
+        assert_eq!(AuthSuite::parse("IEEE 802.1X"), AuthSuite::Wpa2Enterprise);
+        assert_eq!(AuthSuite::parse("SAE"), AuthSuite::Wpa3Personal);
+
+        assert_eq!(Cipher::parse("AES"), Cipher::Ccmp);
+        assert_eq!(Cipher::parse("GCMP-256"), Cipher::Gcmp);
+        assert_eq!(Cipher::parse("WEP-104"), Cipher::Wep);
+        assert_eq!(Cipher::parse("none"), Cipher::None);
+    }
+
+    #[test]
+    fn test_ordering_by_strength() {
+        assert!(AuthSuite::Open < AuthSuite::Owe);
+        assert!(AuthSuite::WpaEnterprise < AuthSuite::Wpa2Personal);
+        assert!(AuthSuite::Wpa2Personal < AuthSuite::Wpa2Enterprise);
+        assert!(AuthSuite::Wpa2Enterprise < AuthSuite::Wpa3Transition);
+        assert!(AuthSuite::Wpa3Transition < AuthSuite::Wpa3Personal);
+        assert!(AuthSuite::Other("Mystery".into()) < AuthSuite::Open);
+
+        assert!(Cipher::None < Cipher::Wep);
+        assert!(Cipher::Tkip < Cipher::Ccmp);
+        assert!(Cipher::Ccmp < Cipher::Gcmp);
+    }
+
+    #[test]
+    fn test_serde_keeps_frontend_names() {
+        let json = serde_json::to_string(&(AuthSuite::Wpa3Transition, Cipher::Ccmp)).unwrap();
+        assert_eq!(json, r#"["WPA2/WPA3-Personal","CCMP"]"#);
+
+        let (auth, cipher): (AuthSuite, Cipher) =
+            serde_json::from_str(r#"["WPA2-Enterprise","AES"]"#).unwrap();
+        assert_eq!(auth, AuthSuite::Wpa2Enterprise);
+        assert_eq!(cipher, Cipher::Ccmp);
+
+        // Unknown names survive a round trip.
+        let other: AuthSuite = serde_json::from_str(r#""Vendor-X""#).unwrap();
+        assert_eq!(serde_json::to_string(&other).unwrap(), r#""Vendor-X""#);
+    }
+}
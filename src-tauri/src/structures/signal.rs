@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Received signal strength as both link quality and RSSI.
+///
+/// Backends that measure only one of the two derive the other with the
+/// linear mapping Windows uses for link quality: -100 dBm is 0% and
+/// -50 dBm is 100%, so one percentage point is half a dB.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(try_from = "SignalRepr")]
+pub struct Signal {
+    pub percent: u8,
+    pub dbm: i32,
+}
+
+impl Signal {
+    /// Both values as measured, e.g. WLAN link quality and RSSI from the same BSS entry.
+    pub fn new(percent: u8, dbm: i32) -> Self {
+        Self {
+            percent: percent.min(100),
+            dbm,
+        }
+    }
+
+    pub fn from_percent(percent: u8) -> Self {
+        let percent = percent.min(100);
+        Self {
+            percent,
+            dbm: dbm_from_percent(percent),
+        }
+    }
+
+    pub fn from_dbm(dbm: i32) -> Self {
+        Self {
+            percent: percent_from_dbm(dbm),
+            dbm,
+        }
+    }
+
+    /// Parse "78%", "78 %" or "-61 dBm".
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        if let Some(percent) = value.strip_suffix('%') {
+            return percent.trim().parse().ok().map(Self::from_percent);
+        }
+        if let Some(dbm) = value.strip_suffix("dBm") {
+            return dbm
+                .trim()
+                .parse::<f32>()
+                .ok()
+                .map(|v| Self::from_dbm(v.round() as i32));
+        }
+        None
+    }
+}
+
+pub fn percent_from_dbm(dbm: i32) -> u8 {
+    (2 * (dbm + 100)).clamp(0, 100) as u8
+}
+
+pub fn dbm_from_percent(percent: u8) -> i32 {
+    i32::from(percent.min(100)) / 2 - 100
+}
+
+impl fmt::Display for Signal {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}% ({} dBm)", self.percent, self.dbm)
+    }
+}
+
+/// Accepts the `{ percent, dbm }` form as well as the "78%" strings older
+/// replay recordings contain.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SignalRepr {
+    Text(String),
+    Fields {
+        percent: Option<u8>,
+        dbm: Option<i32>,
+    },
+}
+
+impl TryFrom<SignalRepr> for Signal {
+    type Error = String;
+
+    fn try_from(repr: SignalRepr) -> Result<Self, Self::Error> {
+        match repr {
+            SignalRepr::Text(text) => {
+                Signal::parse(&text).ok_or_else(|| format!("Invalid signal strength: {}", text))
+            }
+            SignalRepr::Fields {
+                percent: Some(percent),
+                dbm: Some(dbm),
+            } => Ok(Signal::new(percent, dbm)),
+            SignalRepr::Fields {
+                percent: Some(percent),
+                dbm: None,
+            } => Ok(Signal::from_percent(percent)),
+            SignalRepr::Fields {
+                percent: None,
+                dbm: Some(dbm),
+            } => Ok(Signal::from_dbm(dbm)),
+            SignalRepr::Fields { .. } => Err("Signal needs percent or dbm".to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{dbm_from_percent, percent_from_dbm, Signal};
+
+    #[test]
+    fn test_linear_mapping() {
+        assert_eq!(percent_from_dbm(-100), 0);
+        assert_eq!(percent_from_dbm(-75), 50);
+        assert_eq!(percent_from_dbm(-50), 100);
+        assert_eq!(percent_from_dbm(-30), 100);
+        assert_eq!(percent_from_dbm(-120), 0);
+
+        assert_eq!(dbm_from_percent(0), -100);
+        assert_eq!(dbm_from_percent(84), -58);
+        assert_eq!(dbm_from_percent(100), -50);
+
+        for percent in (0..=100).step_by(2) {
+            assert_eq!(percent_from_dbm(dbm_from_percent(percent)), percent);
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(Signal::parse("78%"), Some(Signal::new(78, -61)));
+        assert_eq!(Signal::parse(" 90 %"), Some(Signal::new(90, -55)));
+        assert_eq!(Signal::parse("-55.00 dBm"), Some(Signal::new(90, -55)));
+        assert_eq!(Signal::parse("strong"), None);
+        assert_eq!(Signal::parse(""), None);
+    }
+
+    #[test]
+    fn test_serde_accepts_legacy_strings() {
+        let json = serde_json::to_string(&Signal::new(84, -58)).unwrap();
+        assert_eq!(json, r#"{"percent":84,"dbm":-58}"#);
+
+        let legacy: Signal = serde_json::from_str(r#""80%""#).unwrap();
+        assert_eq!(legacy, Signal::from_percent(80));
+
+        let dbm_only: Signal = serde_json::from_str(r#"{"dbm":-70}"#).unwrap();
+        assert_eq!(dbm_only.percent, 60);
+
+        assert!(serde_json::from_str::<Signal>(r#""n/a""#).is_err());
+    }
+}
@@ -1,55 +1,37 @@
-// Real-time monitoring feature - COMMENTED OUT (will be re-enabled in future)
-// use chrono::{DateTime, Utc};
-// use serde::{Deserialize, Serialize};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
-// #[derive(Serialize, Deserialize, Debug, Clone)]
-// pub struct DetectedThreat {
-//     pub threat_type: String,
-//     pub severity: String,
-//     pub network_ssid: String,
-//     pub network_bssid: String,
-//     pub details: String,
-//     pub timestamp: DateTime<Utc>,
-// }
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DetectedThreat {
+    pub threat_type: String,
+    pub severity: String,
+    pub network_ssid: String,
+    pub network_bssid: String,
+    pub details: String,
+    pub timestamp: DateTime<Utc>,
+}
 
-// #[derive(Debug, Clone)]
-// pub struct NetworkHistory {
-//     pub ssid: String,
-//     pub bssid: String,
-//     pub signal_strength: i32,
-//     pub first_seen: DateTime<Utc>,
-//     pub last_seen: DateTime<Utc>,
-//     pub appearance_count: u32,
-//     pub previous_signals: Vec<i32>,
-// }
+/// What the monitor remembers about one BSSID. Signal values are link
+/// quality in percent.
+#[derive(Debug, Clone)]
+pub struct NetworkHistory {
+    pub ssid: String,
+    pub signal_strength: i32,
+    pub last_seen: DateTime<Utc>,
+    pub appearance_count: u32,
+    pub previous_signals: Vec<i32>,
+}
 
-// #[derive(Debug, Clone)]
-// pub struct MonitoringState {
-//     pub previous_networks: Vec<crate::structures::WifiNetwork>,
-//     pub network_history: std::collections::HashMap<String, NetworkHistory>,
-//     pub connection_failures: Vec<ConnectionFailure>,
-//     pub signal_baselines: std::collections::HashMap<String, Vec<i32>>,
-//     pub scan_count: u64,
-//     pub last_scan_time: Option<DateTime<Utc>>,
-// }
-
-// #[derive(Debug, Clone)]
-// pub struct ConnectionFailure {
-//     pub network_ssid: String,
-//     pub network_bssid: String,
-//     pub timestamp: DateTime<Utc>,
-//     pub reason: String,
-// }
-
-// impl Default for MonitoringState {
-//     fn default() -> Self {
-//         Self {
-//             previous_networks: Vec::new(),
-//             network_history: std::collections::HashMap::new(),
-//             connection_failures: Vec::new(),
-//             signal_baselines: std::collections::HashMap::new(),
-//             scan_count: 0,
-//             last_scan_time: None,
-//         }
-//     }
-// }
+/// State carried by the continuous monitor from one scan to the next.
+#[derive(Debug, Clone, Default)]
+pub struct MonitoringState {
+    pub previous_networks: Vec<crate::structures::WifiNetwork>,
+    /// Keyed by lowercase BSSID.
+    pub network_history: HashMap<String, NetworkHistory>,
+    /// Keyed by lowercase BSSID.
+    pub signal_models: HashMap<String, crate::detectors::SignalModel>,
+    pub scan_count: u64,
+    pub last_scan_time: Option<DateTime<Utc>>,
+    pub deauth: crate::detectors::DeauthTracker,
+}
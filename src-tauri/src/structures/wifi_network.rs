@@ -1,12 +1,194 @@
-use serde::Serialize;
+use crate::ie::{InformationElements, RsnInfo};
+use crate::structures::{AuthSuite, Cipher, RiskAssessment, RiskFactorKind, RiskGrade, Signal};
+use crate::wifi_functions::oui_database;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[serde(default)]
 pub struct WifiNetwork {
     pub(crate) ssid: String,
-    pub(crate) authentication: String,
-    pub(crate) encryption: String,
+    pub(crate) authentication: AuthSuite,
+    pub(crate) encryption: Cipher,
     pub(crate) bssid: String,
-    pub(crate) signal: String,
+    pub(crate) signal: Option<Signal>,
     pub(crate) risk: String,
+    /// Breakdown behind `risk`, set by the scan pipeline.
+    pub(crate) risk_assessment: Option<RiskAssessment>,
     pub(crate) is_evil_twin: bool,
+    /// Whitelisted or trusted SSID this network's name imitates.
+    pub(crate) imitates: Option<String>,
+    /// First three BSSID octets as uppercase hex, e.g. `"F492BF"`.
+    pub(crate) oui: Option<String>,
+    pub(crate) is_locally_administered: bool,
+    /// Manufacturer registered for the BSSID's prefix.
+    pub(crate) vendor: Option<String>,
+    pub(crate) frequency_mhz: Option<u32>,
+    pub(crate) channel: Option<u32>,
+    pub(crate) beacon_interval: Option<u16>,
+    pub(crate) capability: Option<u16>,
+    pub(crate) rsn: Option<CipherSuites>,
+    pub(crate) wpa: Option<CipherSuites>,
+    pub(crate) pmf_capable: Option<bool>,
+    pub(crate) pmf_required: Option<bool>,
+    pub(crate) wps: Option<bool>,
+    pub(crate) ht_capabilities: Option<bool>,
+    pub(crate) vht_capabilities: Option<bool>,
+    pub(crate) he_capabilities: Option<bool>,
+    pub(crate) channel_width: Option<u8>,
+    pub(crate) country: Option<String>,
+    pub(crate) band: Option<Band>,
+    pub(crate) radio_type: Option<RadioType>,
+    pub(crate) network_type: Option<NetworkType>,
+    pub(crate) basic_rates: Vec<f32>,
+    pub(crate) other_rates: Vec<f32>,
+    /// Raw information elements from the last beacon or probe response, when
+    /// the backend can read them.
+    #[serde(skip)]
+    pub(crate) information_elements: Vec<u8>,
+}
+
+impl WifiNetwork {
+    pub fn set_risk(&mut self, assessment: RiskAssessment) {
+        self.risk = assessment.grade.to_string();
+        self.risk_assessment = Some(assessment);
+    }
+
+    pub fn override_risk(&mut self, grade: RiskGrade, kind: RiskFactorKind, reason: String) {
+        if let Some(assessment) = self.risk_assessment.as_mut() {
+            assessment.override_grade(grade, kind, reason);
+        }
+        self.risk = grade.to_string();
+    }
+
+    /// Raise the risk grade to at least `grade` for a reason found after scoring.
+    pub fn escalate_risk(&mut self, grade: RiskGrade, floor: u32, kind: RiskFactorKind, reason: String) {
+        if let Some(assessment) = self.risk_assessment.as_mut() {
+            assessment.escalate(grade, floor, kind, reason);
+            self.risk = assessment.grade.to_string();
+        } else {
+            self.risk = grade.to_string();
+        }
+    }
+
+    /// Fill the fields derived from the BSSID itself.
+    pub fn fill_from_bssid(&mut self) {
+        self.oui = oui_database::extract_oui(&self.bssid);
+        self.is_locally_administered = oui_database::is_locally_administered(&self.bssid);
+        self.vendor = oui_database::lookup_vendor(&self.bssid);
+    }
+
+    /// Fill the element-derived fields from decoded IEs, keeping whatever
+    /// the backend already reported when the blob lacks the element.
+    pub fn fill_from_ies(&mut self, ies: &InformationElements) {
+        if let Some(rsn) = &ies.rsn {
+            self.rsn = Some(CipherSuites::from(rsn));
+            self.pmf_capable = Some(rsn.pmf_capable());
+            self.pmf_required = Some(rsn.pmf_required());
+        }
+        if let Some(wpa) = &ies.wpa {
+            self.wpa = Some(CipherSuites::from(wpa));
+        }
+
+        self.wps = Some(ies.wps);
+        self.ht_capabilities = Some(ies.ht_capabilities);
+        self.vht_capabilities = Some(ies.vht_capabilities);
+        self.he_capabilities = Some(ies.he_capabilities);
+        self.channel_width = ies.channel_width().or(self.channel_width);
+        self.channel = self.channel.or(ies.channel().map(u32::from));
+        if let Some(country) = &ies.country {
+            self.country = Some(country.code.clone());
+        }
+    }
+}
+
+/// Cipher and key-management suites advertised in an RSN or WPA element.
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[serde(default)]
+pub struct CipherSuites {
+    pub(crate) group_cipher: Option<String>,
+    pub(crate) pairwise_ciphers: Vec<String>,
+    pub(crate) akm_suites: Vec<String>,
+}
+
+impl From<&RsnInfo> for CipherSuites {
+    fn from(info: &RsnInfo) -> Self {
+        Self {
+            group_cipher: info.group_cipher.map(|c| c.name()),
+            pairwise_ciphers: info.pairwise_ciphers.iter().map(|c| c.name()).collect(),
+            akm_suites: info.akm_suites.iter().map(|a| a.name()).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Band {
+    #[serde(rename = "2.4 GHz")]
+    Ghz2_4,
+    #[serde(rename = "5 GHz")]
+    Ghz5,
+    #[serde(rename = "6 GHz")]
+    Ghz6,
+}
+
+/// PHY generation, named the way netsh reports it.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum RadioType {
+    #[serde(rename = "802.11b")]
+    Dot11b,
+    #[serde(rename = "802.11a")]
+    Dot11a,
+    #[serde(rename = "802.11g")]
+    Dot11g,
+    #[serde(rename = "802.11n")]
+    Dot11n,
+    #[serde(rename = "802.11ac")]
+    Dot11ac,
+    #[serde(rename = "802.11ax")]
+    Dot11ax,
+    #[serde(rename = "802.11be")]
+    Dot11be,
+}
+
+impl Band {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Ghz2_4 => "2.4 GHz",
+            Self::Ghz5 => "5 GHz",
+            Self::Ghz6 => "6 GHz",
+        }
+    }
+}
+
+impl RadioType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Dot11b => "802.11b",
+            Self::Dot11a => "802.11a",
+            Self::Dot11g => "802.11g",
+            Self::Dot11n => "802.11n",
+            Self::Dot11ac => "802.11ac",
+            Self::Dot11ax => "802.11ax",
+            Self::Dot11be => "802.11be",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "802.11b" => Some(Self::Dot11b),
+            "802.11a" => Some(Self::Dot11a),
+            "802.11g" => Some(Self::Dot11g),
+            "802.11n" => Some(Self::Dot11n),
+            "802.11ac" => Some(Self::Dot11ac),
+            "802.11ax" => Some(Self::Dot11ax),
+            "802.11be" => Some(Self::Dot11be),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NetworkType {
+    Infrastructure,
+    AdHoc,
 }
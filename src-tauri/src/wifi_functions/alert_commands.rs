@@ -0,0 +1,24 @@
+use crate::alerts::{AlertQueueStatus, SharedAlertQueue};
+use tauri::State;
+
+#[tauri::command]
+pub fn get_alert_queue_status(
+    alerts: State<'_, SharedAlertQueue>,
+) -> Result<AlertQueueStatus, String> {
+    Ok(alerts.status())
+}
+
+/// Send queued alerts to `server_url` with `auth_token`, e.g. after logging
+/// in again when the server rejected the old token.
+#[tauri::command]
+pub async fn set_alert_credentials(
+    server_url: String,
+    auth_token: String,
+    alerts: State<'_, SharedAlertQueue>,
+) -> Result<(), String> {
+    if server_url.trim().is_empty() {
+        return Err("Server URL is required".to_string());
+    }
+    alerts.set_credentials(server_url.trim(), &auth_token);
+    Ok(())
+}
@@ -0,0 +1,46 @@
+use crate::backends::SharedBackend;
+use crate::baseline::{BaselineReview, SharedBaseline, SsidBaseline};
+use tauri::State;
+
+/// Scan and add the access points of `ssid` that are in range to its trusted
+/// baseline. Run it where the genuine APs are, once per floor or room if
+/// needed; every call adds to what was learned before.
+#[tauri::command]
+pub fn learn_baseline(
+    ssid: String,
+    backend: State<'_, SharedBackend>,
+    baseline: State<'_, SharedBaseline>,
+) -> Result<SsidBaseline, String> {
+    let mut networks = backend.scan()?;
+    for net in networks.iter_mut() {
+        net.fill_from_bssid();
+    }
+
+    let mut store = baseline.lock().unwrap_or_else(|e| e.into_inner());
+    let learned = store.learn(&ssid, &networks, chrono::Utc::now())?;
+    println!(
+        "Trusted baseline for \"{}\": {} access points",
+        ssid,
+        learned.access_points.len()
+    );
+    Ok(learned)
+}
+
+/// The trusted baselines, and why the saved ones could not be loaded if
+/// that happened.
+#[tauri::command]
+pub fn get_baseline(baseline: State<'_, SharedBaseline>) -> Result<BaselineReview, String> {
+    Ok(baseline.lock().unwrap_or_else(|e| e.into_inner()).review())
+}
+
+/// Forget the baseline of `ssid`, or all of them when no SSID is given.
+#[tauri::command]
+pub fn reset_baseline(
+    ssid: Option<String>,
+    baseline: State<'_, SharedBaseline>,
+) -> Result<usize, String> {
+    baseline
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .reset(ssid.as_deref())
+}
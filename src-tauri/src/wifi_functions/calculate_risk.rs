@@ -1,40 +1,138 @@
-    pub fn calculate_risk(authentication: &str, encryption: &str, signal: &str, ssid: &str) -> String {
-        let mut score = 0;
-
-        score += match authentication {
-            a if a.contains("WPA3") => 0,
-            a if a.contains("WPA2") => 10,
-            a if a.contains("WPA") => 20,
-            a if a.contains("Open") => 50,
-            _ => 30,
-        };
-
-        score += match encryption {
-            e if e.contains("CCMP") => 0,
-            e if e.contains("TKIP") => 20,
-            e if e.contains("WEP") || e.is_empty() || e.contains("None") => 50,
-            _ => 10,
-        };
-
-        if let Ok(signal_strength) = signal.trim_end_matches('%').parse::<i32>() {
-            score += match signal_strength {
-                s if s > 80 => 20,
-                s if s > 50 => 10,
-                _ => 0,
-            };
-        }
+use crate::structures::{AuthSuite, Cipher, RiskAssessment, RiskFactor, RiskFactorKind, Signal};
+use crate::wifi_functions::risk_policy::RiskPolicy;
 
-        let lowercase_ssid = ssid.to_lowercase();
-        if lowercase_ssid.contains("free")
-            || lowercase_ssid.contains("xfinity")
-            || lowercase_ssid.contains("wifi")
-        {
-            score += 30;
-        }
-        match score {
-            0..=39 => "L".to_string(),
-            40..=69 => "M".to_string(),
-            70..=89 => "H".to_string(),
-            _ => "C".to_string(),
+pub fn calculate_risk(
+    policy: &RiskPolicy,
+    authentication: &AuthSuite,
+    encryption: &Cipher,
+    signal: Option<Signal>,
+    ssid: &str,
+) -> RiskAssessment {
+    let mut factors = Vec::new();
+
+    let strength = match authentication {
+        AuthSuite::Wpa3Personal | AuthSuite::Wpa3Enterprise | AuthSuite::Wpa3Transition => "strong",
+        AuthSuite::Wpa2Personal | AuthSuite::Wpa2Enterprise => "moderate",
+        AuthSuite::WpaPersonal | AuthSuite::WpaEnterprise => "weak",
+        _ => "unknown",
+    };
+    factors.push(RiskFactor {
+        kind: RiskFactorKind::Authentication,
+        weight: policy.auth_weight(authentication),
+        reason: match authentication {
+            AuthSuite::Open => "No authentication: anyone can connect".to_string(),
+            AuthSuite::Other(name) if name.is_empty() => "Authentication unknown".to_string(),
+            other => format!("{} authentication ({} security)", other, strength),
+        },
+    });
+
+    let unencrypted = "No encryption: traffic is readable by anyone nearby".to_string();
+    factors.push(RiskFactor {
+        kind: RiskFactorKind::Cipher,
+        weight: policy.cipher_weight(encryption),
+        reason: match encryption {
+            Cipher::Ccmp | Cipher::Gcmp => format!("{} encryption (strong)", encryption),
+            Cipher::Tkip => "TKIP encryption (weak)".to_string(),
+            Cipher::Wep => "WEP encryption (broken)".to_string(),
+            Cipher::None => unencrypted,
+            Cipher::Other(e) if e.is_empty() => unencrypted,
+            other => format!("{} encryption (unknown strength)", other),
+        },
+    });
+
+    if let Some(signal) = signal {
+        let weight = policy.signal_weight(signal.percent);
+        if weight > 0 {
+            factors.push(RiskFactor {
+                kind: RiskFactorKind::Signal,
+                weight,
+                reason: format!(
+                    "Unusually strong signal ({}%, {} dBm)",
+                    signal.percent, signal.dbm
+                ),
+            });
         }
     }
+
+    if let Some(keyword) = policy.ssid_keyword(ssid) {
+        factors.push(RiskFactor {
+            kind: RiskFactorKind::SsidKeyword,
+            weight: policy.ssid_keywords.weight,
+            reason: format!(
+                "Network name contains \"{}\", common for public hotspots",
+                keyword
+            ),
+        });
+    }
+
+    RiskAssessment::new(factors, |score| policy.grade(score))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::calculate_risk;
+    use crate::structures::{AuthSuite, Cipher, RiskFactorKind, RiskGrade, Signal};
+    use crate::wifi_functions::risk_policy::RiskPolicy;
+
+    #[test]
+    fn test_factors_add_up_to_score() {
+        let assessment = calculate_risk(
+            &RiskPolicy::default(),
+            &AuthSuite::Open,
+            &Cipher::None,
+            Signal::parse("90%"),
+            "Free Cafe WiFi",
+        );
+
+        let kinds: Vec<_> = assessment.factors.iter().map(|f| f.kind).collect();
+        assert_eq!(
+            kinds,
+            [
+                RiskFactorKind::Authentication,
+                RiskFactorKind::Cipher,
+                RiskFactorKind::Signal,
+                RiskFactorKind::SsidKeyword,
+            ]
+        );
+        assert_eq!(assessment.score, 150);
+        assert_eq!(
+            assessment.score,
+            assessment.factors.iter().map(|f| f.weight).sum::<u32>()
+        );
+        assert_eq!(assessment.grade, RiskGrade::Critical);
+        assert!(assessment.verdict.contains("no authentication"));
+    }
+
+    #[test]
+    fn test_home_network_breakdown() {
+        let assessment = calculate_risk(
+            &RiskPolicy::default(),
+            &AuthSuite::Wpa2Personal,
+            &Cipher::Ccmp,
+            Signal::parse("60%"),
+            "HomeNet",
+        );
+
+        assert_eq!(assessment.score, 20);
+        assert_eq!(assessment.grade, RiskGrade::Low);
+        assert_eq!(assessment.factors[0].weight, 10);
+        assert!(assessment.factors[0].reason.starts_with("WPA2-Personal"));
+        assert_eq!(assessment.factors[1].weight, 0);
+        assert_eq!(assessment.factors[2].kind, RiskFactorKind::Signal);
+    }
+
+    #[test]
+    fn test_policy_changes_grade() {
+        let policy = RiskPolicy::from_toml("[auth]\nwpa2 = 45\n").unwrap();
+        let assessment = calculate_risk(
+            &policy,
+            &AuthSuite::Wpa2Personal,
+            &Cipher::Ccmp,
+            None,
+            "HomeNet",
+        );
+
+        assert_eq!(assessment.score, 45);
+        assert_eq!(assessment.grade, RiskGrade::Medium);
+    }
+}
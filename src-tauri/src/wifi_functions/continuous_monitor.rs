@@ -1,159 +1,661 @@
-// use crate::structures::{DetectedThreat, MonitoringState};
-// use crate::wifi_functions::{detect_threats, scan_wifi};
-// use std::collections::HashSet;
-// use std::sync::Arc;
-// use tokio::sync::Mutex;
-// use tokio::time::{interval, Duration};
-// use tauri::{AppHandle, Emitter};
-
-// pub struct MonitorConfig {
-//     pub server_url: String,
-//     pub auth_token: String,
-//     pub interval_seconds: u64,
-//     pub enabled_threat_types: HashSet<String>,
-//     pub app_handle: Option<AppHandle>,
-// }
-
-// pub struct MonitorHandle {
-//     pub is_running: Arc<Mutex<bool>>,
-//     pub state: Arc<Mutex<MonitoringState>>,
-// }
-
-// impl MonitorHandle {
-//     pub fn new() -> Self {
-//         Self {
-//             is_running: Arc::new(Mutex::new(false)),
-//             state: Arc::new(Mutex::new(MonitoringState::default())),
-//         }
-//     }
-
-//     pub async fn start(&self, config: MonitorConfig, whitelist: Vec<String>, blacklist: Vec<String>) {
-//         let mut running = self.is_running.lock().await;
-//         if *running {
-//             return; // Already running
-//         }
-//         *running = true;
-//         drop(running);
-
-//         let is_running = Arc::clone(&self.is_running);
-//         let state = Arc::clone(&self.state);
-//         let whitelist_set: HashSet<String> = whitelist.iter().map(|s| s.to_lowercase()).collect();
-//         let blacklist_set: HashSet<String> = blacklist.iter().map(|s| s.to_lowercase()).collect();
-//         let app_handle = config.app_handle.clone();
-
-//         tokio::spawn(async move {
-//             let mut interval = interval(Duration::from_secs(config.interval_seconds));
-//             let client = reqwest::Client::new();
-
-//             loop {
-//                 // Check if monitoring is still enabled
-//                 let running = is_running.lock().await;
-//                 if !*running {
-//                     break;
-//                 }
-//                 drop(running);
-
-//                 interval.tick().await;
-
-//                 // Perform scan and detect threats (run in blocking thread since scan_wifi is blocking)
-//                 let networks = tokio::task::spawn_blocking(|| {
-//                     scan_wifi()
-//                 }).await;
-
-//                 match networks {
-//                     Ok(networks) => {
-//                         let mut state_guard = state.lock().await;
-//                         let threats = detect_threats(
-//                             &networks,
-//                             &mut *state_guard,
-//                             &whitelist_set,
-//                             &blacklist_set,
-//                         );
-
-//                         // Filter threats by enabled types
-//                         let filtered_threats: Vec<DetectedThreat> = threats
-//                             .into_iter()
-//                             .filter(|t| {
-//                                 config.enabled_threat_types.is_empty()
-//                                     || config.enabled_threat_types.contains(&t.threat_type)
-//                             })
-//                             .collect();
-
-//                         // Send threats to server and emit Tauri events
-//                         for threat in filtered_threats {
-//                             // Send to server
-//                             send_threat_to_server(&client, &config.server_url, &config.auth_token, &threat).await;
-                            
-//                             // Emit Tauri event if app handle is available
-//                             if let Some(ref app) = app_handle {
-//                                 let _ = app.emit(
-//                                     "threat-detected",
-//                                     serde_json::json!({
-//                                         "threat_type": threat.threat_type,
-//                                         "severity": threat.severity,
-//                                         "network_ssid": threat.network_ssid,
-//                                         "network_bssid": threat.network_bssid,
-//                                         "details": threat.details,
-//                                     })
-//                                 );
-//                             }
-//                         }
-//                     }
-//                     Err(e) => {
-//                         eprintln!("Error during monitoring scan: {}", e);
-//                     }
-//                 }
-//             }
-//         });
-//     }
-
-//     pub async fn stop(&self) {
-//         let mut running = self.is_running.lock().await;
-//         *running = false;
-//     }
-
-//     pub async fn is_running(&self) -> bool {
-//         let running = self.is_running.lock().await;
-//         *running
-//     }
-// }
-
-// async fn send_threat_to_server(
-//     client: &reqwest::Client,
-//     server_url: &str,
-//     auth_token: &str,
-//     threat: &DetectedThreat,
-// ) {
-//     let url = format!("{}/threats/alert", server_url.trim_end_matches('/'));
-
-//     let payload = serde_json::json!({
-//         "threat_type": threat.threat_type,
-//         "severity": threat.severity,
-//         "network_ssid": threat.network_ssid,
-//         "network_bssid": threat.network_bssid,
-//         "details": threat.details,
-//         "timestamp": threat.timestamp.to_rfc3339(),
-//     });
-
-//     let response = client
-//         .post(&url)
-//         .header("Authorization", format!("Bearer {}", auth_token))
-//         .header("Content-Type", "application/json")
-//         .json(&payload)
-//         .send()
-//         .await;
-
-//     match response {
-//         Ok(res) => {
-//             if res.status().is_success() {
-//                 println!("Threat alert sent successfully: {}", threat.threat_type);
-//             } else {
-//                 eprintln!("Failed to send threat alert: HTTP {}", res.status());
-//             }
-//         }
-//         Err(e) => {
-//             eprintln!("Error sending threat alert to server: {}", e);
-//         }
-//     }
-// }
+use crate::alerts::SharedAlertQueue;
+use crate::backends::SharedBackend;
+use crate::baseline::SharedBaseline;
+use crate::detectors::{self, DetectorRegistry, Scan};
+use crate::history::{self, ScanSource, SharedHistory};
+use crate::incidents;
+use crate::link_events::SharedLinkEventSource;
+use crate::rules::SharedRuleSet;
+use crate::structures::{DetectedThreat, MonitoringState, WifiNetwork};
+use crate::wifi_functions::{
+    score_scan, KarmaFinding, ScoringContext, SharedKarmaDetector, SharedRiskPolicy,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
+use tokio_util::sync::CancellationToken;
 
+pub struct MonitorConfig {
+    /// Where threats are queued for the server, if one is set.
+    pub alerts: Option<SharedAlertQueue>,
+    pub interval: Duration,
+    /// The detectors `enabled_threat_types` switched on.
+    pub detectors: DetectorRegistry,
+    /// Where disconnects and refused connections come from, for the
+    /// `deauth_attack` detector.
+    pub link_events: Option<SharedLinkEventSource>,
+    /// Lowercase BSSIDs.
+    pub whitelist: HashSet<String>,
+    pub blacklist: HashSet<String>,
+    /// Names look-alikes are checked against, besides the trusted baseline.
+    pub whitelisted_ssids: Vec<String>,
+    /// How long an incident stays open without its threat being seen.
+    pub incident_expiry: Duration,
+    /// How long scans and threats are kept in the history.
+    pub history_retention: Duration,
+}
+
+/// The shared state one monitoring scan goes through, the same the
+/// `scan_wifi` command uses.
+#[derive(Clone)]
+pub struct ScanContext {
+    pub backend: SharedBackend,
+    pub policy: SharedRiskPolicy,
+    pub rules: SharedRuleSet,
+    pub baseline: SharedBaseline,
+    pub karma: SharedKarmaDetector,
+    pub history: SharedHistory,
+}
+
+impl ScanContext {
+    fn scan(
+        &self,
+        whitelisted_ssids: &[String],
+    ) -> Result<(Vec<WifiNetwork>, Vec<KarmaFinding>), String> {
+        // Scan first so `scan_wifi` and the baseline commands are not kept
+        // waiting behind the platform scan
+        let networks = self.backend.scan()?;
+        let (networks, findings) = {
+            let baseline = self.baseline.lock().unwrap_or_else(|e| e.into_inner());
+            let mut karma = self.karma.lock().unwrap_or_else(|e| e.into_inner());
+            let context = ScoringContext {
+                policy: &self.policy,
+                rules: &self.rules,
+                baseline: &baseline,
+                whitelisted: whitelisted_ssids,
+            };
+            let networks = score_scan(networks, &context, &mut karma);
+            (networks, karma.findings())
+        };
+
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = history.record_scan(&networks, ScanSource::Monitor, Utc::now()) {
+            eprintln!("Failed to record monitoring scan: {}", e);
+        }
+        Ok((networks, findings))
+    }
+
+    fn record_threats(&self, threats: &[DetectedThreat]) {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        if let Err(e) = history.record_threats(threats) {
+            eprintln!("Failed to record threats: {}", e);
+        }
+    }
+
+    /// Fold `threats` into incidents and return the ones worth reporting:
+    /// those that opened or escalated one. If the incidents cannot be
+    /// updated, everything is reported rather than nothing.
+    fn track_incidents(
+        &self,
+        threats: Vec<DetectedThreat>,
+        now: DateTime<Utc>,
+        expire_after: Duration,
+    ) -> Vec<DetectedThreat> {
+        let expire_after = chrono::Duration::from_std(expire_after).unwrap_or_else(|_| {
+            chrono::Duration::minutes(incidents::DEFAULT_EXPIRY_MINUTES as i64)
+        });
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        match incidents::record(&mut history, &threats, now, expire_after) {
+            Ok(changes) => threats
+                .into_iter()
+                .zip(changes)
+                .filter(|(_, (_, change))| change.is_news())
+                .map(|(threat, _)| threat)
+                .collect(),
+            Err(e) => {
+                eprintln!("Failed to update incidents: {}", e);
+                threats
+            }
+        }
+    }
+
+    /// Drop history older than `retention` before `now`.
+    fn prune_history(&self, now: DateTime<Utc>, retention: Duration) {
+        let retention = chrono::Duration::from_std(retention)
+            .unwrap_or_else(|_| chrono::Duration::days(history::DEFAULT_RETENTION_DAYS as i64));
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        match history.prune(now - retention) {
+            Ok(0) => {}
+            Ok(deleted) => println!("Pruned {} old history rows", deleted),
+            Err(e) => eprintln!("Failed to prune history: {}", e),
+        }
+    }
+
+    /// Monitoring state rebuilt from the scans recorded before `now`, so a
+    /// restart does not throw away the signal history detectors rely on.
+    fn restore_state(&self, now: DateTime<Utc>) -> MonitoringState {
+        let mut state = MonitoringState::default();
+        let since = now - chrono::Duration::minutes(detectors::HISTORY_MINUTES);
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        match history.scans_since(since) {
+            Ok(scans) => {
+                for (at, networks) in scans {
+                    detectors::warm_up(&mut state, &networks, at);
+                }
+            }
+            Err(e) => eprintln!("Failed to restore monitoring history: {}", e),
+        }
+        state
+    }
+}
+
+/// Called with every threat the monitor reports, i.e. each one that opened
+/// or escalated an incident.
+pub type ThreatSink = Arc<dyn Fn(&DetectedThreat) + Send + Sync>;
+
+#[derive(Serialize, Debug, Clone, Default, PartialEq)]
+pub struct MonitoringStatus {
+    pub running: bool,
+    pub interval_seconds: u64,
+    pub started_at: Option<DateTime<Utc>>,
+    pub scan_count: u64,
+    pub threats_detected: u64,
+    /// Threats that opened or escalated an incident and were sent on;
+    /// repeats of a known incident are not.
+    pub threats_reported: u64,
+    pub last_scan_time: Option<DateTime<Utc>>,
+    /// Why the last scan failed, cleared by the next successful one.
+    pub last_error: Option<String>,
+}
+
+struct MonitorRun {
+    cancel: CancellationToken,
+    task: JoinHandle<()>,
+}
+
+/// Owns the background monitoring task, if one is running.
+#[derive(Default)]
+pub struct MonitorHandle {
+    run: Mutex<Option<MonitorRun>>,
+    status: Arc<Mutex<MonitoringStatus>>,
+}
+
+impl MonitorHandle {
+    /// Spawn the monitoring loop on the current tokio runtime.
+    pub fn start(
+        &self,
+        config: MonitorConfig,
+        context: ScanContext,
+        sink: ThreatSink,
+    ) -> Result<(), String> {
+        if config.interval.is_zero() {
+            return Err("Monitoring interval must be at least 1 second".to_string());
+        }
+        let mut run = self.run.lock().unwrap_or_else(|e| e.into_inner());
+        if run.as_ref().is_some_and(|r| !r.task.is_finished()) {
+            return Err("Monitoring is already running".to_string());
+        }
+
+        *self.status.lock().unwrap_or_else(|e| e.into_inner()) = MonitoringStatus {
+            running: true,
+            interval_seconds: config.interval.as_secs(),
+            started_at: Some(Utc::now()),
+            ..Default::default()
+        };
+        let cancel = CancellationToken::new();
+        let task = tokio::spawn(monitor_loop(
+            config,
+            context,
+            sink,
+            Arc::clone(&self.status),
+            cancel.clone(),
+        ));
+        *run = Some(MonitorRun { cancel, task });
+        Ok(())
+    }
+
+    /// Cancel the loop and wait for it to finish; a scan in progress is
+    /// abandoned rather than waited for.
+    pub async fn stop(&self) -> Result<(), String> {
+        let run = self.run.lock().unwrap_or_else(|e| e.into_inner()).take();
+        let Some(run) = run.filter(|r| !r.task.is_finished()) else {
+            return Err("Monitoring is not running".to_string());
+        };
+        run.cancel.cancel();
+        run.task
+            .await
+            .map_err(|e| format!("Monitoring task failed: {}", e))
+    }
+
+    pub fn status(&self) -> MonitoringStatus {
+        self.status
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+}
+
+async fn monitor_loop(
+    config: MonitorConfig,
+    context: ScanContext,
+    sink: ThreatSink,
+    status: Arc<Mutex<MonitoringStatus>>,
+    cancel: CancellationToken,
+) {
+    let mut interval = tokio::time::interval(config.interval);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    // Pruned now and then once a day while monitoring runs
+    let mut last_pruned = Utc::now();
+    context.prune_history(last_pruned, config.history_retention);
+    let mut state = context.restore_state(last_pruned);
+    let whitelisted_ssids = Arc::new(config.whitelisted_ssids.clone());
+    let (link_sender, mut link_receiver) = tokio::sync::mpsc::unbounded_channel();
+    // Events stop when this is dropped at the end of the loop
+    let _link_subscription = match &config.link_events {
+        Some(source) => match source.subscribe(link_sender) {
+            Ok(subscription) => {
+                println!("Watching link events from {}", source.name());
+                Some(subscription)
+            }
+            Err(e) => {
+                eprintln!("Link events unavailable: {}", e);
+                None
+            }
+        },
+        None => None,
+    };
+
+    loop {
+        tokio::select! {
+            _ = cancel.cancelled() => break,
+            _ = interval.tick() => {}
+        }
+
+        // Backends shell out or block on the WLAN API, so scan off the runtime
+        let scan_context = context.clone();
+        let ssids = Arc::clone(&whitelisted_ssids);
+        let scan = tokio::select! {
+            _ = cancel.cancelled() => break,
+            scan = tokio::task::spawn_blocking(move || scan_context.scan(&ssids)) => scan,
+        };
+        let now = Utc::now();
+        if now - last_pruned >= chrono::Duration::days(1) {
+            context.prune_history(now, config.history_retention);
+            last_pruned = now;
+        }
+        let (networks, karma) = match scan.map_err(|e| e.to_string()).and_then(|r| r) {
+            Ok(scan) => scan,
+            Err(e) => {
+                eprintln!("Error during monitoring scan: {}", e);
+                let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+                status.scan_count += 1;
+                status.last_scan_time = Some(now);
+                status.last_error = Some(e);
+                continue;
+            }
+        };
+
+        // Events that came in while a scan failed wait for the next one
+        let mut link_events = Vec::new();
+        while let Ok(event) = link_receiver.try_recv() {
+            link_events.push(event);
+        }
+        let scan = Scan {
+            networks: &networks,
+            karma: &karma,
+            whitelist: &config.whitelist,
+            blacklist: &config.blacklist,
+            link_events: &link_events,
+            at: now,
+        };
+        let threats = config.detectors.observe(&scan, &mut state);
+        if !threats.is_empty() {
+            context.record_threats(&threats);
+        }
+        let detected = threats.len() as u64;
+        // Also run with no threats, so quiet incidents expire
+        let reported = context.track_incidents(threats, now, config.incident_expiry);
+
+        {
+            let mut status = status.lock().unwrap_or_else(|e| e.into_inner());
+            status.scan_count += 1;
+            status.threats_detected += detected;
+            status.threats_reported += reported.len() as u64;
+            status.last_scan_time = Some(now);
+            status.last_error = None;
+        }
+
+        for threat in reported {
+            if let Some(alerts) = &config.alerts {
+                if let Err(e) = alerts.enqueue(&threat) {
+                    eprintln!("Failed to queue threat alert: {}", e);
+                }
+            }
+            sink(&threat);
+        }
+    }
+
+    status.lock().unwrap_or_else(|e| e.into_inner()).running = false;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{MonitorConfig, MonitorHandle, ScanContext, ThreatSink};
+    use crate::backends::WlanBackend;
+    use crate::baseline::BaselineStore;
+    use crate::detectors::{DetectorRegistry, EnabledThreatTypes};
+    use crate::history::{HistoryStore, ScanSource};
+    use crate::link_events::{LinkEventSource, Subscription};
+    use crate::rules::RuleSet;
+    use crate::structures::{
+        AuthSuite, Cipher, DetectedThreat, LinkEvent, LinkEventKind, Signal, WifiNetwork,
+    };
+    use crate::wifi_functions::{KarmaDetector, RiskPolicy};
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::sync::mpsc::UnboundedSender;
+
+    struct CountingBackend {
+        scans: Arc<AtomicUsize>,
+        delay: Duration,
+    }
+
+    impl WlanBackend for CountingBackend {
+        fn name(&self) -> &'static str {
+            "counting"
+        }
+
+        fn scan(&self) -> Result<Vec<WifiNetwork>, String> {
+            std::thread::sleep(self.delay);
+            self.scans.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![WifiNetwork {
+                ssid: "Bad".to_string(),
+                bssid: "66:77:88:99:AA:BB".to_string(),
+                signal: Some(Signal::from_percent(10)),
+                ..Default::default()
+            }])
+        }
+
+        fn active_network(&self) -> Result<Vec<WifiNetwork>, String> {
+            Ok(Vec::new())
+        }
+
+        fn stored_profile(&self, _ssid: &str) -> Result<(AuthSuite, Cipher), String> {
+            Err("no profiles".to_string())
+        }
+
+        fn connect(
+            &self,
+            _ssid: &str,
+            _password: Option<&str>,
+            _authentication: Option<&str>,
+        ) -> Result<String, String> {
+            Err("not supported".to_string())
+        }
+
+        fn disconnect(&self) -> Result<String, String> {
+            Err("not supported".to_string())
+        }
+    }
+
+    /// Reports the AP kicking the station off a few times, once subscribed.
+    struct KickedOff;
+
+    impl LinkEventSource for KickedOff {
+        fn name(&self) -> &'static str {
+            "kicked-off"
+        }
+
+        fn subscribe(&self, events: UnboundedSender<LinkEvent>) -> Result<Subscription, String> {
+            for _ in 0..4 {
+                let _ = events.send(LinkEvent {
+                    at: Utc::now(),
+                    kind: LinkEventKind::Disconnected,
+                    bssid: Some("66:77:88:99:aa:bb".to_string()),
+                    ssid: Some("Bad".to_string()),
+                    code: Some(7),
+                    reason: None,
+                    locally_generated: false,
+                });
+            }
+            Ok(Subscription::new(()))
+        }
+    }
+
+    fn context(scans: &Arc<AtomicUsize>, delay: Duration) -> ScanContext {
+        ScanContext {
+            backend: Arc::new(CountingBackend {
+                scans: Arc::clone(scans),
+                delay,
+            }),
+            policy: Arc::new(RiskPolicy::default()),
+            rules: Arc::new(RuleSet::default()),
+            baseline: Arc::new(Mutex::new(BaselineStore::default())),
+            karma: Arc::new(Mutex::new(KarmaDetector::default())),
+            history: Arc::new(Mutex::new(HistoryStore::in_memory().unwrap())),
+        }
+    }
+
+    fn config(interval: Duration, enabled: &[&str]) -> MonitorConfig {
+        MonitorConfig {
+            alerts: None,
+            link_events: None,
+            interval,
+            detectors: DetectorRegistry::from_settings(&EnabledThreatTypes::Ids(
+                enabled.iter().map(|t| t.to_string()).collect(),
+            ))
+            .unwrap(),
+            whitelist: ["aa:bb:cc:00:00:01".to_string()].into(),
+            blacklist: ["66:77:88:99:aa:bb".to_string()].into(),
+            whitelisted_ssids: Vec::new(),
+            incident_expiry: Duration::from_secs(1800),
+            history_retention: Duration::from_secs(86400),
+        }
+    }
+
+    fn collecting_sink() -> (ThreatSink, Arc<Mutex<Vec<DetectedThreat>>>) {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink_seen = Arc::clone(&seen);
+        let sink: ThreatSink =
+            Arc::new(move |t: &DetectedThreat| sink_seen.lock().unwrap().push(t.clone()));
+        (sink, seen)
+    }
+
+    #[test]
+    fn test_scan_holds_no_locks_while_scanning() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let context = context(&scans, Duration::from_millis(300));
+        let scanning = {
+            let context = context.clone();
+            std::thread::spawn(move || context.scan(&[]))
+        };
+
+        std::thread::sleep(Duration::from_millis(100));
+        assert_eq!(scans.load(Ordering::SeqCst), 0);
+        assert!(context.baseline.try_lock().is_ok());
+        assert!(context.karma.try_lock().is_ok());
+        assert_eq!(scanning.join().unwrap().unwrap().0.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_reports_enabled_threats_until_stopped() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let (sink, seen) = collecting_sink();
+        let monitor = MonitorHandle::default();
+        let context = context(&scans, Duration::ZERO);
+
+        monitor
+            .start(
+                config(Duration::from_millis(20), &["blacklisted_network"]),
+                context.clone(),
+                sink,
+            )
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.status().scan_count < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        monitor.stop().await.unwrap();
+
+        let status = monitor.status();
+        assert!(!status.running);
+        {
+            // The same blacklisted AP on every scan is one incident, reported once
+            let seen = seen.lock().unwrap();
+            assert_eq!(seen.len(), 1);
+            assert_eq!(status.threats_reported, 1);
+            assert!(status.threats_detected >= 3);
+            assert_eq!(seen[0].threat_type, "blacklisted_network");
+        }
+        {
+            let history = context.history.lock().unwrap();
+            let bad = history.bssid_history("66:77:88:99:aa:bb").unwrap().unwrap();
+            // A scan abandoned by stop may still have been recorded
+            assert!(bad.times_seen >= status.scan_count);
+            assert_eq!(
+                history.threats(100).unwrap().len() as u64,
+                status.threats_detected
+            );
+            let incidents = history.incidents(false, 10).unwrap();
+            assert_eq!(incidents.len(), 1);
+            assert_eq!(incidents[0].occurrences, status.threats_detected);
+        }
+
+        // Nothing scans after stop returns.
+        let after = scans.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(scans.load(Ordering::SeqCst), after);
+    }
+
+    #[tokio::test]
+    async fn test_signal_history_survives_restart() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let (sink, seen) = collecting_sink();
+        let context = context(&scans, Duration::ZERO);
+        {
+            // A strong signal recorded by an earlier session
+            let mut history = context.history.lock().unwrap();
+            let strong = WifiNetwork {
+                ssid: "Bad".to_string(),
+                bssid: "66:77:88:99:aa:bb".to_string(),
+                signal: Some(Signal::from_percent(80)),
+                ..Default::default()
+            };
+            for seconds in [180, 150, 120, 90, 60, 30] {
+                let at = Utc::now() - chrono::Duration::seconds(seconds);
+                history
+                    .record_scan(std::slice::from_ref(&strong), ScanSource::Monitor, at)
+                    .unwrap();
+            }
+        }
+
+        let monitor = MonitorHandle::default();
+        monitor
+            .start(
+                config(Duration::from_secs(3600), &["rf_jamming"]),
+                context,
+                sink,
+            )
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.status().scan_count < 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        monitor.stop().await.unwrap();
+
+        // The first scan after the restart already has a model to drop from
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert!(
+            seen[0].details.contains("to -95 dBm"),
+            "{}",
+            seen[0].details
+        );
+    }
+
+    #[tokio::test]
+    async fn test_link_events_reach_detectors() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let (sink, seen) = collecting_sink();
+        let monitor = MonitorHandle::default();
+        let mut config = config(Duration::from_secs(3600), &["deauth_attack"]);
+        config.link_events = Some(Arc::new(KickedOff));
+
+        monitor
+            .start(config, context(&scans, Duration::ZERO), sink)
+            .unwrap();
+        tokio::time::timeout(Duration::from_secs(5), async {
+            while monitor.status().scan_count < 1 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await
+        .unwrap();
+        monitor.stop().await.unwrap();
+
+        let seen = seen.lock().unwrap();
+        assert_eq!(seen.len(), 1);
+        assert_eq!(seen[0].threat_type, "deauth_attack");
+        assert_eq!(seen[0].network_ssid, "Bad");
+    }
+
+    #[tokio::test]
+    async fn test_start_twice_and_stop_idle() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let monitor = MonitorHandle::default();
+        assert_eq!(
+            monitor.stop().await.unwrap_err(),
+            "Monitoring is not running"
+        );
+
+        let (sink, _) = collecting_sink();
+        monitor
+            .start(
+                config(Duration::from_secs(3600), &[]),
+                context(&scans, Duration::ZERO),
+                sink.clone(),
+            )
+            .unwrap();
+        let err = monitor
+            .start(
+                config(Duration::from_secs(3600), &[]),
+                context(&scans, Duration::ZERO),
+                sink.clone(),
+            )
+            .unwrap_err();
+        assert_eq!(err, "Monitoring is already running");
+        assert!(monitor.status().running);
+
+        monitor.stop().await.unwrap();
+        assert!(monitor
+            .start(
+                config(Duration::ZERO, &[]),
+                context(&scans, Duration::ZERO),
+                sink
+            )
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_stop_does_not_wait_for_a_slow_scan() {
+        let scans = Arc::new(AtomicUsize::new(0));
+        let (sink, seen) = collecting_sink();
+        let monitor = MonitorHandle::default();
+
+        monitor
+            .start(
+                config(Duration::from_secs(3600), &[]),
+                context(&scans, Duration::from_millis(600)),
+                sink,
+            )
+            .unwrap();
+        tokio::time::sleep(Duration::from_millis(50)).await;
+
+        let stopped = tokio::time::timeout(Duration::from_millis(200), monitor.stop()).await;
+        assert!(stopped.is_ok());
+        assert!(seen.lock().unwrap().is_empty());
+        assert_eq!(monitor.status().scan_count, 0);
+    }
+}
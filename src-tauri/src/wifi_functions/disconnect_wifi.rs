@@ -1,18 +1,7 @@
-use std::process::Command;
+use crate::backends::SharedBackend;
+use tauri::State;
 
 #[tauri::command]
-pub fn disconnect_wifi() -> Result<String, String> {
-    let output = Command::new("netsh")
-        .args(["wlan", "disconnect"])
-        .output()
-        .map_err(|e| format!("Failed to execute netsh disconnect: {}", e))?;
-
-    if output.status.success() {
-        Ok("Disconnected from Wi-Fi".to_string())
-    } else {
-        Err(format!(
-            "Failed to disconnect: {}",
-            String::from_utf8_lossy(&output.stderr)
-        ))
-    }
+pub fn disconnect_wifi(backend: State<'_, SharedBackend>) -> Result<String, String> {
+    backend.disconnect()
 }
@@ -1,77 +1,926 @@
-use crate::structures::WifiNetwork;
-use std::cmp::Reverse;
-use std::collections::HashMap;
-
-fn encryption_strength(auth: &str, enc: &str) -> u8 {
-    match (auth.to_lowercase().as_str(), enc.to_lowercase().as_str()) {
-        ("wpa3-personal", _) => 4,
-        ("wpa2-personal", "aes") | ("wpa2-personal", "ccmp") => 3,
-        ("wpa2-personal", "tkip") => 2,
-        ("wep", _) => 1,
-        ("open", _) => 0,
-        _ => 0,
+use crate::structures::{AuthSuite, Band, Cipher, RiskFactorKind, RiskGrade, WifiNetwork};
+use crate::wifi_functions::oui_database::{is_multicast, lookup_vendor};
+use crate::wifi_functions::RiskPolicy;
+use std::collections::{HashMap, HashSet};
+
+/// How many percentage points a BSS may be stronger than its group's average
+/// before it counts as a hard indicator; a rogue clone is usually closer.
+const SIGNAL_DELTA_PERCENT: f32 = 40.0;
+
+/// Beacon intervals further than this from the group's usual value, in TU,
+/// are unusual.
+const BEACON_INTERVAL_DELTA_TU: i32 = 50;
+
+/// Radio group key for clustering APs (SSID + band)
+#[derive(Hash, PartialEq, Eq, Clone)]
+struct RadioGroup {
+    ssid: String,
+    band: Option<Band>,
+}
+
+impl RadioGroup {
+    fn from_network(net: &WifiNetwork) -> Self {
+        RadioGroup {
+            ssid: net.ssid.clone(),
+            band: band_of(net),
+        }
     }
 }
 
-pub fn mark_evil_twins(networks: &mut Vec<WifiNetwork>) {
-    let mut ssid_map: HashMap<String, Vec<&mut WifiNetwork>> = HashMap::new();
+/// The reported band, or the one the channel number suggests.
+fn band_of(net: &WifiNetwork) -> Option<Band> {
+    net.band.or_else(|| {
+        net.channel.map(|ch| {
+            if ch <= 14 {
+                Band::Ghz2_4
+            } else if ch <= 165 {
+                Band::Ghz5
+            } else {
+                Band::Ghz6
+            }
+        })
+    })
+}
 
-    for net in networks.iter_mut() {
-        if net.ssid.trim().is_empty() {
-            continue;
-        }
+/// Rank of an authentication suite for downgrade checks; `None` when unknown.
+fn auth_tier(auth: &AuthSuite) -> Option<u8> {
+    match auth {
+        AuthSuite::Open => Some(0),
+        AuthSuite::Shared | AuthSuite::Owe => Some(1),
+        AuthSuite::WpaPersonal | AuthSuite::WpaEnterprise => Some(2),
+        AuthSuite::Wpa2Personal | AuthSuite::Wpa2Enterprise => Some(3),
+        AuthSuite::Wpa3Transition | AuthSuite::Wpa3Personal | AuthSuite::Wpa3Enterprise => Some(4),
+        AuthSuite::Other(_) => None,
+    }
+}
 
-        ssid_map.entry(net.ssid.clone()).or_default().push(net);
+/// `other`'s auth tier as `net`'s band allows it. 6 GHz admits only WPA3 and
+/// OWE, so a router that runs WPA2 or transition mode on 2.4/5 GHz still has
+/// WPA3 on 6 GHz, and an open one has OWE there; neither is a downgrade.
+fn comparable_auth_tier(other: &WifiNetwork, net: &WifiNetwork) -> Option<u8> {
+    let tier = auth_tier(&other.authentication)?;
+    if band_of(other) != Some(Band::Ghz6) || band_of(net) == Some(Band::Ghz6) {
+        return Some(tier);
     }
+    Some(match other.authentication {
+        AuthSuite::Owe => 0,
+        _ => tier.min(3),
+    })
+}
 
-    for (_ssid, group) in ssid_map.iter_mut() {
-        if group.len() < 2 {
-            continue;
+fn cipher_tier(cipher: &Cipher) -> Option<u8> {
+    match cipher {
+        Cipher::None => Some(0),
+        Cipher::Wep => Some(1),
+        Cipher::Tkip => Some(2),
+        Cipher::Ccmp | Cipher::Gcmp => Some(3),
+        Cipher::Other(_) => None,
+    }
+}
+
+fn uses_strong_cipher(net: &WifiNetwork) -> bool {
+    matches!(net.encryption, Cipher::Ccmp | Cipher::Gcmp)
+}
+
+/// Most frequent value; ties go to the value seen last.
+fn most_common<T: PartialEq + Copy>(values: &[T]) -> Option<T> {
+    values
+        .iter()
+        .max_by_key(|&&v| values.iter().filter(|&&x| x == v).count())
+        .copied()
+}
+
+/// Hard and soft findings against one BSS.
+#[derive(Default)]
+struct Indicators {
+    hard: Vec<String>,
+    soft: Vec<String>,
+    security_downgrade: bool,
+}
+
+impl Indicators {
+    /// Critical: a security downgrade or ≥ 2 hard indicators
+    /// High: 1 hard indicator + ≥ 3 soft
+    /// Medium: 1 hard indicator, or ≥ 4 soft indicators alone
+    /// Fewer soft indicators are normal variance and not flagged.
+    fn grade(&self) -> Option<RiskGrade> {
+        let hard = self.hard.len();
+        let soft = self.soft.len();
+        if self.security_downgrade || hard >= 2 {
+            Some(RiskGrade::Critical)
+        } else if hard == 1 && soft >= 3 {
+            Some(RiskGrade::High)
+        } else if hard == 1 || soft >= 4 {
+            Some(RiskGrade::Medium)
+        } else {
+            None
         }
+    }
 
-        group.sort_by_key(|n| Reverse(encryption_strength(&n.authentication, &n.encryption)));
-        let strongest_strength =
-            encryption_strength(&group[0].authentication, &group[0].encryption);
+    fn reasons(&self) -> String {
+        self.hard
+            .iter()
+            .chain(self.soft.iter())
+            .cloned()
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
 
-        let avg_signal: i32 = group
+    /// Compare `net`'s security with the strongest of `others`.
+    fn check_downgrade(&mut self, net: &WifiNetwork, others: &[&WifiNetwork]) {
+        let strongest_auth = others
             .iter()
-            .map(|n| {
-                let mut signal = 0;
-                if let Ok(signal_strength) = n.signal.trim_end_matches('%').parse::<i32>() {
-                    signal = signal_strength
-                }
-                signal
-            })
-            .sum::<i32>()
-            / group.len() as i32;
-
-        for net in group.iter_mut() {
-            let this_strength = encryption_strength(&net.authentication, &net.encryption);
-
-            let mut suspicion_score = 0;
-            if this_strength < strongest_strength {
-                suspicion_score += 2;
+            .filter_map(|o| comparable_auth_tier(o, net).map(|t| (t, &o.authentication)))
+            .max_by_key(|(t, _)| *t);
+        if let (Some(tier), Some((best, auth))) = (auth_tier(&net.authentication), strongest_auth) {
+            if tier < best {
+                self.hard.push(format!(
+                    "weaker security than another access point named \"{}\" ({} instead of {})",
+                    net.ssid, net.authentication, auth
+                ));
+                self.security_downgrade = true;
             }
+        }
 
-            if let Ok(signal_strength) = net.signal.trim_end_matches('%').parse::<i32>() {
-                if signal_strength > avg_signal + 20 {
-                    suspicion_score += 2;
-                }
+        let strongest_cipher = others
+            .iter()
+            .filter_map(|o| cipher_tier(&o.encryption).map(|t| (t, &o.encryption)))
+            .max_by_key(|(t, _)| *t);
+        if let (Some(tier), Some((best, cipher))) = (cipher_tier(&net.encryption), strongest_cipher) {
+            if tier < best {
+                self.hard.push(format!(
+                    "weaker encryption than another access point named \"{}\" ({} instead of {})",
+                    net.ssid, net.encryption, cipher
+                ));
+                self.security_downgrade = true;
             }
+        }
+    }
+}
+
+/// Vendor of `net`'s BSSID as the scan list shows it, or its OUI if unknown.
+fn vendor_name(net: &WifiNetwork) -> String {
+    lookup_vendor(&net.bssid)
+        .or_else(|| net.oui.clone())
+        .unwrap_or_default()
+}
+
+/// Findings for `net` against the other members of its radio group.
+fn analyse(net: &WifiNetwork, others: &[&WifiNetwork], group_size: usize) -> Indicators {
+    let mut found = Indicators::default();
+
+    // ===== HARD INDICATORS (Real rogue patterns only) =====
+
+    // Hard Indicator 1: Same SSID + same band but weaker security
+    found.check_downgrade(net, others);
+
+    // Hard Indicator 2: Same BSSID on another channel of the band. Duplicates
+    // on one channel were dropped already, so this is a cloned address.
+    if let Some(clone) = others.iter().find(|o| o.bssid.eq_ignore_ascii_case(&net.bssid)) {
+        found.hard.push(match clone.channel {
+            Some(ch) => format!("BSSID also seen on channel {}", ch),
+            None => "BSSID also seen on another channel".to_string(),
+        });
+    }
+
+    // Hard Indicator 3: Much stronger than the group's average signal
+    let signals: Vec<f32> = std::iter::once(net)
+        .chain(others.iter().copied())
+        .filter_map(|n| n.signal.map(|s| f32::from(s.percent)))
+        .collect();
+    if let Some(signal) = net.signal {
+        let avg = signals.iter().sum::<f32>() / signals.len() as f32;
+        let delta = f32::from(signal.percent) - avg;
+        if delta > SIGNAL_DELTA_PERCENT {
+            found.hard.push(format!(
+                "signal {:.0} points above the other access points",
+                delta
+            ));
+        }
+    }
+
+    // Hard Indicator 4: Group address as BSSID, which no real AP transmits from
+    if is_multicast(&net.bssid) {
+        found.hard.push("multicast BSSID".to_string());
+    }
+
+    // ===== SOFT INDICATORS (Normal variance) =====
 
-            if let Ok(signal_strength) = net.signal.trim_end_matches('%').parse::<i32>() {
-                if signal_strength + 20 < avg_signal {
-                    suspicion_score += 1;
-                }
+    // Soft Indicator 1: Locally administered MAC
+    if net.is_locally_administered {
+        found.soft.push("locally administered MAC address".to_string());
+    }
+
+    // Soft Indicator 2: OUI differs from a clear majority and appears once
+    if let (Some(net_oui), false) = (&net.oui, net.is_locally_administered) {
+        let mut oui_counts: HashMap<&str, usize> = HashMap::new();
+        for oui in std::iter::once(net)
+            .chain(others.iter().copied())
+            .filter_map(|n| n.oui.as_deref())
+        {
+            *oui_counts.entry(oui).or_insert(0) += 1;
+        }
+        if let Some((common_oui, common_count)) = oui_counts.iter().max_by_key(|(_, count)| **count) {
+            if *common_oui != net_oui
+                && *common_count as f64 / group_size as f64 > 0.5
+                && oui_counts.get(net_oui.as_str()) == Some(&1)
+            {
+                let common = others
+                    .iter()
+                    .find(|o| o.oui.as_deref() == Some(*common_oui))
+                    .map_or_else(|| common_oui.to_string(), |o| vendor_name(o));
+                found.soft.push(format!(
+                    "made by {} while the others are {}",
+                    vendor_name(net),
+                    common
+                ));
+            }
+        }
+    }
+
+    // Soft Indicator 3: Beacon interval far from the usual one
+    if let Some(net_beacon) = net.beacon_interval {
+        let intervals: Vec<u16> = others.iter().filter_map(|o| o.beacon_interval).collect();
+        if let Some(common) = most_common(&intervals) {
+            if (i32::from(net_beacon) - i32::from(common)).abs() > BEACON_INTERVAL_DELTA_TU {
+                found.soft.push(format!(
+                    "beacon interval {} TU instead of {} TU",
+                    net_beacon, common
+                ));
+            }
+        }
+    }
+
+    // Soft Indicator 4: Radio type mismatch (normal in heterogeneous networks)
+    if let Some(net_radio) = net.radio_type {
+        let radios: Vec<_> = others.iter().filter_map(|o| o.radio_type).collect();
+        if let Some(common) = most_common(&radios) {
+            if net_radio != common {
+                found.soft.push(format!("{} radio while the others use {}", net_radio.as_str(), common.as_str()));
             }
+        }
+    }
+
+    // Soft Indicator 5: Channel width mismatch
+    if let Some(net_width) = net.channel_width {
+        let widths: Vec<u8> = others.iter().filter_map(|o| o.channel_width).collect();
+        if let Some(common) = most_common(&widths) {
+            if net_width != common {
+                found.soft.push(format!("{} MHz wide channel instead of {} MHz", net_width, common));
+            }
+        }
+    }
+
+    // Soft Indicator 6: PMF mismatch (normal in mixed environments)
+    let others_pmf: Vec<bool> = others.iter().filter_map(|o| o.pmf_required).collect();
+    if !others_pmf.is_empty() {
+        let common_pmf = others_pmf.iter().filter(|&&p| p).count() > others_pmf.len() / 2;
+        if net.pmf_required.unwrap_or(false) != common_pmf {
+            found.soft.push(if common_pmf {
+                "does not require PMF unlike the others".to_string()
+            } else {
+                "requires PMF unlike the others".to_string()
+            });
+        }
+    }
+
+    // Soft Indicator 7: Enterprise/personal mismatch
+    let others_enterprise: Vec<bool> = others
+        .iter()
+        .filter(|o| auth_tier(&o.authentication).is_some())
+        .map(|o| o.authentication.is_enterprise())
+        .collect();
+    if let (Some(common), true) = (most_common(&others_enterprise), auth_tier(&net.authentication).is_some()) {
+        if net.authentication.is_enterprise() != common {
+            found.soft.push(if common {
+                "personal authentication while the others use enterprise".to_string()
+            } else {
+                "enterprise authentication while the others use personal".to_string()
+            });
+        }
+    }
+
+    // Soft Indicator 8: Cipher family mismatch (CCMP vs TKIP is normal for compatibility)
+    if uses_strong_cipher(net) != others.iter().any(|o| uses_strong_cipher(o)) {
+        found.soft.push("different cipher from the others".to_string());
+    }
+
+    found
+}
+
+fn flag(net: &mut WifiNetwork, grade: RiskGrade, reasons: String, policy: &RiskPolicy) {
+    net.is_evil_twin = true;
+    net.escalate_risk(
+        grade,
+        policy.score_floor(grade),
+        RiskFactorKind::EvilTwin,
+        format!("Possible evil twin: {}", reasons),
+    );
+}
+
+/// Flag access points that look like clones of another one advertising the
+/// same SSID. APs are compared within their band, so the radios of one
+/// dual-band or mesh system are not held against each other, except that a
+/// weaker security setup on any band still counts.
+pub fn mark_evil_twins(networks: &mut Vec<WifiNetwork>, policy: &RiskPolicy) {
+    // Step 0: Deduplicate scan artifacts (same SSID + BSSID + channel)
+    let mut unique: HashSet<(String, String, Option<u32>)> = HashSet::new();
+    networks.retain(|net| {
+        if net.ssid.trim().is_empty() {
+            return true;
+        }
+        unique.insert((net.ssid.clone(), net.bssid.to_ascii_lowercase(), net.channel))
+    });
+
+    // Step 1: Group networks by radio group (SSID + band)
+    let mut radio_groups: HashMap<RadioGroup, Vec<usize>> = HashMap::new();
+    for (idx, net) in networks.iter().enumerate() {
+        if net.ssid.trim().is_empty() {
+            continue;
+        }
+        radio_groups.entry(RadioGroup::from_network(net)).or_default().push(idx);
+    }
 
-            if suspicion_score >= 3 {
-                net.is_evil_twin = true;
-                net.risk = "C".to_string();
-            } else if suspicion_score == 2 {
-                net.is_evil_twin = true;
-                net.risk = "H".to_string();
+    // Step 2: Analyse each AP against the rest of its radio group
+    let mut findings = Vec::new();
+    for indices in radio_groups.values() {
+        if indices.len() < 2 {
+            continue;
+        }
+        for &idx in indices {
+            let others: Vec<&WifiNetwork> =
+                indices.iter().filter(|&&i| i != idx).map(|&i| &networks[i]).collect();
+            let found = analyse(&networks[idx], &others, indices.len());
+            if let Some(grade) = found.grade() {
+                findings.push((idx, grade, found.reasons()));
             }
         }
     }
+    for (idx, grade, reasons) in findings {
+        flag(&mut networks[idx], grade, reasons, policy);
+    }
+
+    // Step 3: Cross-band security downgrade detection. Catches cases like an
+    // open 6 GHz BSS next to WPA2 on 2.4 GHz under the same SSID, but not
+    // the WPA3 that 6 GHz requires next to WPA2 on the other bands.
+    let mut ssid_groups: HashMap<&str, Vec<usize>> = HashMap::new();
+    for (idx, net) in networks.iter().enumerate() {
+        if !net.ssid.trim().is_empty() {
+            ssid_groups.entry(net.ssid.as_str()).or_default().push(idx);
+        }
+    }
+
+    let mut findings = Vec::new();
+    for indices in ssid_groups.values() {
+        if indices.len() < 2 {
+            continue;
+        }
+        for &idx in indices {
+            let net = &networks[idx];
+            if net.is_evil_twin && net.risk == RiskGrade::Critical.as_str() {
+                continue;
+            }
+            let others: Vec<&WifiNetwork> =
+                indices.iter().filter(|&&i| i != idx).map(|&i| &networks[i]).collect();
+            let mut found = Indicators::default();
+            found.check_downgrade(net, &others);
+            if found.security_downgrade {
+                findings.push((idx, RiskGrade::Critical, found.reasons()));
+            }
+        }
+    }
+    for (idx, grade, reasons) in findings {
+        flag(&mut networks[idx], grade, reasons, policy);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::mark_evil_twins;
+    use crate::structures::{AuthSuite, Band, Cipher, RadioType, Signal, WifiNetwork};
+    use crate::wifi_functions::RiskPolicy;
+
+    #[allow(clippy::too_many_arguments)]
+    fn create_test_network(
+        ssid: &str,
+        bssid: &str,
+        channel: Option<u32>,
+        auth: &str,
+        encryption: &str,
+        signal: &str,
+        is_locally_administered: bool,
+        oui: Option<&str>,
+    ) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            channel,
+            authentication: AuthSuite::parse(auth),
+            encryption: Cipher::parse(encryption),
+            signal: Signal::parse(signal),
+            risk: "L".to_string(),
+            is_evil_twin: false,
+            radio_type: Some(RadioType::Dot11ac),
+            oui: oui.map(|o| o.to_string()),
+            is_locally_administered,
+            beacon_interval: Some(100),
+            pmf_required: None,
+            ht_capabilities: Some(true),
+            vht_capabilities: Some(true),
+            he_capabilities: Some(false),
+            channel_width: Some(80),
+            ..Default::default()
+        }
+    }
+
+    fn mark(networks: &mut Vec<WifiNetwork>) {
+        mark_evil_twins(networks, &RiskPolicy::default());
+    }
+
+    #[test]
+    fn test_legitimate_multiband_should_not_flag_critical() {
+        // One router's 5 GHz and 2.4 GHz radios are in different bands, so
+        // they are not compared.
+        let mut networks = vec![
+            create_test_network(
+                "#Telia-0585CA",
+                "A2:B5:3C:05:25:D2",
+                Some(88),
+                "WPA2-Personal",
+                "CCMP",
+                "50%",
+                true,
+                Some("A2B53C"),
+            ),
+            create_test_network(
+                "#Telia-0585CA",
+                "A2:B5:3C:05:25:CA",
+                Some(1),
+                "WPA2-Personal",
+                "CCMP",
+                "40%",
+                true,
+                Some("A2B53C"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        assert!(!networks.iter().any(|n| n.risk == "C"));
+        assert!(!networks.iter().any(|n| n.is_evil_twin));
+    }
+
+    #[test]
+    fn test_security_downgrade_should_flag_critical() {
+        let mut networks = vec![
+            create_test_network(
+                "Company-WiFi",
+                "00:11:22:33:44:55",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "80%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Company-WiFi",
+                "AA:BB:CC:DD:EE:FF",
+                Some(6),
+                "Open",
+                "None",
+                "90%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        let open_network = networks.iter().find(|n| n.authentication == "Open").unwrap();
+        assert_eq!(open_network.risk, "C");
+        assert!(open_network.is_evil_twin);
+        let genuine = networks.iter().find(|n| n.authentication != "Open").unwrap();
+        assert!(!genuine.is_evil_twin);
+    }
+
+    #[test]
+    fn test_duplicate_bssid_on_another_channel_should_flag() {
+        // Same BSSID on two channels of one band: one of them is a clone.
+        let mut networks = vec![
+            create_test_network(
+                "Test-Network",
+                "AA:BB:CC:DD:EE:FF",
+                Some(11),
+                "WPA2-Personal",
+                "CCMP",
+                "70%",
+                false,
+                Some("AABBCC"),
+            ),
+            create_test_network(
+                "Test-Network",
+                "AA:BB:CC:DD:EE:FF",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "75%",
+                false,
+                Some("112233"),
+            ),
+        ];
+
+        mark(&mut networks);
+        assert!(networks.iter().all(|n| n.is_evil_twin && n.risk == "M"));
+
+        // With three soft indicators on top the clone becomes High.
+        let clone = networks.iter_mut().find(|n| n.channel == Some(6)).unwrap();
+        clone.is_locally_administered = true;
+        clone.beacon_interval = Some(200);
+        clone.channel_width = Some(40);
+
+        mark(&mut networks);
+
+        let clone = networks.iter().find(|n| n.channel == Some(6)).unwrap();
+        assert!(clone.is_evil_twin);
+        assert_eq!(clone.risk, "H");
+    }
+
+    #[test]
+    fn test_deduplication_removes_duplicates() {
+        let mut networks = vec![
+            create_test_network(
+                "Test-Network",
+                "AA:BB:CC:DD:EE:FF",
+                Some(11),
+                "WPA2-Personal",
+                "CCMP",
+                "70%",
+                false,
+                Some("AABBCC"),
+            ),
+            create_test_network(
+                "Test-Network",
+                "aa:bb:cc:dd:ee:ff",
+                Some(11),
+                "WPA2-Personal",
+                "CCMP",
+                "70%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        assert_eq!(networks.len(), 1);
+        assert!(!networks[0].is_evil_twin);
+    }
+
+    #[test]
+    fn test_signal_delta_hard_indicator() {
+        // avg = 52.5%, so 95% is 42.5 points above it.
+        let mut networks = vec![
+            create_test_network(
+                "Company-WiFi",
+                "00:11:22:33:44:55",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "10%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Company-WiFi",
+                "AA:BB:CC:DD:EE:FF",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "95%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        let strong = networks.iter().find(|n| n.bssid == "AA:BB:CC:DD:EE:FF").unwrap();
+        assert!(strong.is_evil_twin);
+        assert_eq!(strong.risk, "M");
+        assert!(!networks[0].is_evil_twin);
+
+        // 90% against 10% is exactly 40 points: not above the threshold.
+        let mut networks = vec![
+            create_test_network("Company-WiFi", "00:11:22:33:44:55", Some(6), "WPA2-Personal", "CCMP", "10%", false, Some("001122")),
+            create_test_network("Company-WiFi", "AA:BB:CC:DD:EE:FF", Some(6), "WPA2-Personal", "CCMP", "90%", false, Some("AABBCC")),
+        ];
+        mark(&mut networks);
+        assert!(!networks.iter().any(|n| n.is_evil_twin));
+    }
+
+    #[test]
+    fn test_signal_delta_with_soft_indicators_is_high() {
+        // avg = 33.75%, so 90% is 56 points above it.
+        let mut networks = vec![
+            create_test_network("Company-WiFi", "00:11:22:33:44:55", Some(6), "WPA2-Personal", "CCMP", "10%", false, Some("001122")),
+            create_test_network("Company-WiFi", "00:11:22:33:44:56", Some(6), "WPA2-Personal", "CCMP", "15%", false, Some("001122")),
+            create_test_network("Company-WiFi", "00:11:22:33:44:57", Some(6), "WPA2-Personal", "CCMP", "20%", false, Some("001122")),
+            create_test_network("Company-WiFi", "AA:BB:CC:DD:EE:FF", Some(6), "WPA2-Personal", "CCMP", "90%", false, Some("AABBCC")),
+        ];
+        networks[3].beacon_interval = Some(200);
+        networks[3].channel_width = Some(40);
+
+        mark(&mut networks);
+
+        let strong = &networks[3];
+        assert!(strong.is_evil_twin);
+        assert_eq!(strong.risk, "H");
+        assert!(networks[..3].iter().all(|n| !n.is_evil_twin));
+    }
+
+    #[test]
+    fn test_locally_administered_mac_soft_indicator() {
+        let mut networks = vec![
+            create_test_network(
+                "Test-Network",
+                "02:11:22:33:44:55",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "50%",
+                true,
+                Some("021122"),
+            ),
+            create_test_network(
+                "Test-Network",
+                "00:AA:BB:CC:DD:EE",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "45%",
+                false,
+                Some("00AABB"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        // A single soft indicator is normal variance.
+        let laa_network = networks.iter().find(|n| n.is_locally_administered).unwrap();
+        assert_ne!(laa_network.risk, "C");
+        assert!(!laa_network.is_evil_twin);
+    }
+
+    #[test]
+    fn test_wpa3_to_wpa2_downgrade() {
+        let mut networks = vec![
+            create_test_network(
+                "Secure-Network",
+                "00:11:22:33:44:55",
+                Some(36),
+                "WPA3-Personal",
+                "CCMP",
+                "70%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Secure-Network",
+                "AA:BB:CC:DD:EE:FF",
+                Some(36),
+                "WPA2-Personal",
+                "CCMP",
+                "75%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        let wpa2_network = networks
+            .iter()
+            .find(|n| n.authentication == AuthSuite::Wpa2Personal)
+            .unwrap();
+        assert_eq!(wpa2_network.risk, "C");
+        assert!(wpa2_network.is_evil_twin);
+    }
+
+    #[test]
+    fn test_encryption_downgrade_ccmp_to_tkip() {
+        let mut networks = vec![
+            create_test_network(
+                "Company-WiFi",
+                "00:11:22:33:44:55",
+                Some(11),
+                "WPA2-Personal",
+                "CCMP",
+                "60%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Company-WiFi",
+                "AA:BB:CC:DD:EE:FF",
+                Some(11),
+                "WPA2-Personal",
+                "TKIP",
+                "65%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        let tkip_network = networks.iter().find(|n| n.encryption == Cipher::Tkip).unwrap();
+        assert_eq!(tkip_network.risk, "C");
+        assert!(tkip_network.is_evil_twin);
+    }
+
+    #[test]
+    fn test_high_risk_one_hard_plus_soft() {
+        let mut networks = vec![
+            create_test_network(
+                "Company-WiFi",
+                "00:11:22:33:44:55",
+                Some(6),
+                "WPA2-Personal",
+                "CCMP",
+                "50%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Company-WiFi",
+                "AA:BB:CC:DD:EE:FF",
+                Some(6),
+                "Open",
+                "None",
+                "85%",
+                true,
+                Some("112233"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        let flagged = networks.iter().find(|n| n.authentication == "Open").unwrap();
+        assert!(flagged.is_evil_twin);
+        assert_eq!(flagged.risk, "C");
+    }
+
+    #[test]
+    fn test_medium_risk_soft_only() {
+        let mut networks = vec![
+            create_test_network("Test-Network", "00:11:22:33:44:55", Some(6), "WPA2-Personal", "CCMP", "50%", false, Some("001122")),
+            create_test_network("Test-Network", "00:11:22:33:44:56", Some(6), "WPA2-Personal", "CCMP", "50%", false, Some("001122")),
+            create_test_network("Test-Network", "00:11:22:33:44:57", Some(6), "WPA2-Personal", "CCMP", "50%", false, Some("001122")),
+            create_test_network("Test-Network", "AA:BB:CC:DD:EE:FF", Some(6), "WPA2-Personal", "CCMP", "50%", true, Some("112233")),
+        ];
+        networks[3].beacon_interval = Some(200);
+        networks[3].channel_width = Some(40);
+        networks[3].radio_type = Some(RadioType::Dot11n);
+
+        mark(&mut networks);
+
+        // LAA MAC, beacon interval, channel width and radio type.
+        let flagged = networks.iter().find(|n| n.bssid == "AA:BB:CC:DD:EE:FF").unwrap();
+        assert!(flagged.is_evil_twin);
+        assert_eq!(flagged.risk, "M");
+        assert!(networks[..3].iter().all(|n| !n.is_evil_twin));
+    }
+
+    #[test]
+    fn test_vendor_mismatch_names_vendors() {
+        let mut networks = vec![
+            create_test_network("Office", "f4:92:bf:00:00:01", Some(36), "WPA2-Personal", "CCMP", "10%", false, Some("F492BF")),
+            create_test_network("Office", "f4:92:bf:00:00:02", Some(36), "WPA2-Personal", "CCMP", "10%", false, Some("F492BF")),
+            create_test_network("Office", "a0:40:a0:00:00:03", Some(40), "WPA2-Personal", "CCMP", "95%", false, Some("A040A0")),
+        ];
+        let policy = RiskPolicy::default();
+        for net in networks.iter_mut() {
+            let assessment = crate::wifi_functions::calculate_risk(
+                &policy,
+                &net.authentication,
+                &net.encryption,
+                net.signal,
+                &net.ssid,
+            );
+            net.set_risk(assessment);
+        }
+
+        mark(&mut networks);
+
+        let factor = networks[2].risk_assessment.as_ref().unwrap().factors.last().unwrap();
+        assert!(networks[2].is_evil_twin);
+        assert!(factor.reason.contains("signal 57 points above"), "{}", factor.reason);
+        assert!(factor.reason.contains("made by NETGEAR while the others are Ubiquiti Inc"), "{}", factor.reason);
+    }
+
+    #[test]
+    fn test_cross_band_downgrade_is_critical() {
+        let mut networks = vec![
+            create_test_network("Home", "00:11:22:33:44:55", Some(1), "WPA2-Personal", "CCMP", "60%", false, Some("001122")),
+            create_test_network("Home", "00:11:22:33:44:56", Some(36), "WPA2-Personal", "CCMP", "50%", false, Some("001122")),
+            create_test_network("Home", "AA:BB:CC:DD:EE:FF", Some(197), "Open", "None", "70%", false, Some("AABBCC")),
+        ];
+
+        mark(&mut networks);
+
+        assert!(networks[2].is_evil_twin);
+        assert_eq!(networks[2].risk, "C");
+        assert!(!networks[0].is_evil_twin && !networks[1].is_evil_twin);
+    }
+
+    #[test]
+    fn test_tri_band_router_with_wpa3_only_6ghz_is_not_a_twin() {
+        let mut networks = vec![
+            create_test_network("Home", "f4:92:bf:12:34:50", Some(6), "WPA2-Personal", "CCMP", "70%", false, Some("F492BF")),
+            create_test_network("Home", "f4:92:bf:12:34:51", Some(36), "WPA2-Personal", "CCMP", "60%", false, Some("F492BF")),
+            create_test_network("Home", "f4:92:bf:12:34:52", Some(37), "WPA3-Personal", "GCMP", "40%", false, Some("F492BF")),
+        ];
+        networks[0].band = Some(Band::Ghz2_4);
+        networks[1].band = Some(Band::Ghz5);
+        networks[2].band = Some(Band::Ghz6);
+
+        mark(&mut networks);
+        assert!(!networks.iter().any(|n| n.is_evil_twin));
+
+        networks[0].authentication = AuthSuite::Wpa3Transition;
+        networks[1].authentication = AuthSuite::Wpa3Transition;
+        mark(&mut networks);
+        assert!(!networks.iter().any(|n| n.is_evil_twin));
+
+        // An open radio is still weaker than what 6 GHz stands for
+        networks[0].authentication = AuthSuite::Open;
+        networks[0].encryption = Cipher::None;
+        mark(&mut networks);
+        assert!(networks[0].is_evil_twin);
+        assert_eq!(networks[0].risk, "C");
+        assert!(!networks[1].is_evil_twin && !networks[2].is_evil_twin);
+    }
+
+    #[test]
+    fn test_multicast_bssid_is_hard_indicator() {
+        let mut networks = vec![
+            create_test_network("Cafe", "00:11:22:33:44:55", Some(6), "WPA2-Personal", "CCMP", "50%", false, Some("001122")),
+            create_test_network("Cafe", "01:11:22:33:44:56", Some(6), "WPA2-Personal", "CCMP", "50%", false, Some("011122")),
+        ];
+
+        mark(&mut networks);
+
+        assert!(!networks[0].is_evil_twin);
+        assert!(networks[1].is_evil_twin);
+        assert_eq!(networks[1].risk, "M");
+    }
+
+    #[test]
+    fn test_same_band_different_ssid_not_compared() {
+        let mut networks = vec![
+            create_test_network(
+                "Network-A",
+                "00:11:22:33:44:55",
+                Some(6),
+                "Open",
+                "None",
+                "50%",
+                false,
+                Some("001122"),
+            ),
+            create_test_network(
+                "Network-B",
+                "AA:BB:CC:DD:EE:FF",
+                Some(6),
+                "Open",
+                "None",
+                "50%",
+                false,
+                Some("AABBCC"),
+            ),
+        ];
+
+        mark(&mut networks);
+
+        assert!(!networks.iter().any(|n| n.is_evil_twin));
+    }
+
+    #[test]
+    fn test_single_network_not_flagged() {
+        let mut networks = vec![create_test_network(
+            "Lone-Network",
+            "00:11:22:33:44:55",
+            Some(6),
+            "Open",
+            "None",
+            "50%",
+            true,
+            Some("001122"),
+        )];
+
+        mark(&mut networks);
+
+        assert!(!networks[0].is_evil_twin);
+    }
 }
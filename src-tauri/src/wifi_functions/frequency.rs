@@ -0,0 +1,46 @@
+use crate::structures::Band;
+
+/// Channel number for a centre frequency in MHz (2.4, 5 and 6 GHz bands).
+pub fn channel_from_frequency(mhz: u32) -> Option<u32> {
+    match mhz {
+        2484 => Some(14),
+        2412..=2472 => Some((mhz - 2407) / 5),
+        5955..=7115 => Some((mhz - 5950) / 5),
+        5150..=5895 => Some((mhz - 5000) / 5),
+        _ => None,
+    }
+}
+
+pub fn band_from_frequency(mhz: u32) -> Option<Band> {
+    match mhz {
+        2400..=2500 => Some(Band::Ghz2_4),
+        5150..=5895 => Some(Band::Ghz5),
+        5925..=7125 => Some(Band::Ghz6),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{band_from_frequency, channel_from_frequency};
+    use crate::structures::Band;
+
+    #[test]
+    fn test_channel_from_frequency() {
+        assert_eq!(channel_from_frequency(2412), Some(1));
+        assert_eq!(channel_from_frequency(2484), Some(14));
+        assert_eq!(channel_from_frequency(5180), Some(36));
+        assert_eq!(channel_from_frequency(5825), Some(165));
+        assert_eq!(channel_from_frequency(5955), Some(1));
+        assert_eq!(channel_from_frequency(6115), Some(33));
+        assert_eq!(channel_from_frequency(900), None);
+    }
+
+    #[test]
+    fn test_band_from_frequency() {
+        assert_eq!(band_from_frequency(2437), Some(Band::Ghz2_4));
+        assert_eq!(band_from_frequency(5180), Some(Band::Ghz5));
+        assert_eq!(band_from_frequency(6115), Some(Band::Ghz6));
+        assert_eq!(band_from_frequency(900), None);
+    }
+}
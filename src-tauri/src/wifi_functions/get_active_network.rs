@@ -1,24 +1,36 @@
+use crate::backends::SharedBackend;
+use crate::baseline::SharedBaseline;
+use crate::rules::SharedRuleSet;
 use crate::structures::WifiNetwork;
-use crate::wifi_functions::parse_active_interface::parse_active_interface;
-use serde::Serialize;
-use std::process::Command;
+use crate::wifi_functions::calculate_risk::calculate_risk;
+use crate::wifi_functions::lookalike_detection::mark_lookalikes;
+use crate::wifi_functions::SharedRiskPolicy;
+use tauri::State;
 
-#[derive(Serialize)]
-pub struct ActiveNetwork {
-    ssid: String,
-    bssid: String,
-    signal: String,
-    state: String,
-}
 #[tauri::command]
-pub fn get_active_network() -> Vec<WifiNetwork> {
-    let output = Command::new("netsh")
-        .args(["wlan", "show", "interfaces"])
-        .output()
-        .expect("Failed to execute command");
+pub fn get_active_network(
+    whitelisted_ssids: Option<Vec<String>>,
+    backend: State<'_, SharedBackend>,
+    policy: State<'_, SharedRiskPolicy>,
+    rules: State<'_, SharedRuleSet>,
+    baseline: State<'_, SharedBaseline>,
+) -> Result<Vec<WifiNetwork>, String> {
+    let mut networks = backend.active_network()?;
+
+    for net in networks.iter_mut() {
+        net.fill_from_bssid();
+        let assessment = calculate_risk(&policy, &net.authentication, &net.encryption, net.signal, &net.ssid);
+        net.set_risk(assessment);
+    }
+    let baseline = baseline.lock().unwrap_or_else(|e| e.into_inner());
+    baseline.check(&mut networks, &policy);
+    let protected = baseline
+        .ssids()
+        .map(str::to_string)
+        .chain(whitelisted_ssids.unwrap_or_default())
+        .collect();
+    mark_lookalikes(&mut networks, &protected, &policy);
+    rules.apply(&mut networks);
 
-    let result = String::from_utf8_lossy(&output.stdout);
-    println!("{}", result);
-    let res = parse_active_interface(&result);
-    res
+    Ok(networks)
 }
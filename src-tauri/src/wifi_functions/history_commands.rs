@@ -0,0 +1,53 @@
+use crate::history::{BssidHistory, ConnectionAttempt, SharedHistory};
+use crate::structures::DetectedThreat;
+use tauri::State;
+
+/// Rows returned when the caller gives no limit.
+const DEFAULT_LIMIT: usize = 100;
+
+/// When `bssid` was first and last seen, and in how many scans; `None` if it
+/// never was.
+#[tauri::command]
+pub fn get_bssid_history(
+    bssid: String,
+    history: State<'_, SharedHistory>,
+) -> Result<Option<BssidHistory>, String> {
+    history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .bssid_history(&bssid)
+}
+
+/// Access points seen most recently first.
+#[tauri::command]
+pub fn list_bssid_history(
+    limit: Option<usize>,
+    history: State<'_, SharedHistory>,
+) -> Result<Vec<BssidHistory>, String> {
+    history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .bssid_histories(limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+#[tauri::command]
+pub fn get_threat_history(
+    limit: Option<usize>,
+    history: State<'_, SharedHistory>,
+) -> Result<Vec<DetectedThreat>, String> {
+    history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .threats(limit.unwrap_or(DEFAULT_LIMIT))
+}
+
+#[tauri::command]
+pub fn get_connection_history(
+    limit: Option<usize>,
+    history: State<'_, SharedHistory>,
+) -> Result<Vec<ConnectionAttempt>, String> {
+    history
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .connection_attempts(limit.unwrap_or(DEFAULT_LIMIT))
+}
@@ -0,0 +1,38 @@
+use crate::history::SharedHistory;
+use crate::incidents::{self, Incident, IncidentGroup};
+use chrono::Utc;
+use tauri::State;
+
+/// Incidents listed when the caller gives no limit.
+const DEFAULT_LIMIT: usize = 200;
+
+/// Incidents in groups of related ones, most recently seen first; resolved
+/// and expired ones only with `include_closed`.
+#[tauri::command]
+pub fn list_incidents(
+    include_closed: Option<bool>,
+    limit: Option<usize>,
+    history: State<'_, SharedHistory>,
+) -> Result<Vec<IncidentGroup>, String> {
+    let history = history.lock().unwrap_or_else(|e| e.into_inner());
+    let incidents = history.incidents(
+        include_closed.unwrap_or(false),
+        limit.unwrap_or(DEFAULT_LIMIT),
+    )?;
+    Ok(incidents::group(incidents))
+}
+
+#[tauri::command]
+pub fn acknowledge_incident(
+    id: i64,
+    history: State<'_, SharedHistory>,
+) -> Result<Incident, String> {
+    let mut history = history.lock().unwrap_or_else(|e| e.into_inner());
+    incidents::acknowledge(&mut history, id, Utc::now())
+}
+
+#[tauri::command]
+pub fn resolve_incident(id: i64, history: State<'_, SharedHistory>) -> Result<Incident, String> {
+    let mut history = history.lock().unwrap_or_else(|e| e.into_inner());
+    incidents::resolve(&mut history, id, Utc::now())
+}
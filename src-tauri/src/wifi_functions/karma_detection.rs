@@ -0,0 +1,382 @@
+//! KARMA/MANA rogue access points: one radio answering probe requests for
+//! whatever SSID a client asks for, so over a few scans it shows up under
+//! many different names.
+//!
+//! A single scan rarely catches it, so every scan is added to a sliding
+//! window of BSSID→SSID sightings. BSSIDs that differ from the first of a
+//! run only in their last byte, or sit a few addresses past it, are counted
+//! as one radio: tools that rotate the BSSID per answered SSID just step
+//! through neighbouring MACs.
+
+use crate::structures::{DetectedThreat, RiskFactorKind, RiskGrade, WifiNetwork};
+use crate::wifi_functions::oui_database::parse_mac;
+use crate::wifi_functions::RiskPolicy;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, Mutex};
+
+/// Distinct SSIDs one radio may advertise within the window. Enterprise APs
+/// commonly carry four or five (staff, guest, IoT, voice, eduroam).
+pub const DEFAULT_MAX_SSIDS: usize = 5;
+pub const DEFAULT_WINDOW_MINUTES: i64 = 10;
+/// BSSIDs at most this far apart still count as the same radio, so runs of
+/// adjacent MACs crossing a byte boundary are grouped too.
+const ADJACENT_SPAN: u64 = 16;
+/// Names listed in an explanation before the rest are only counted.
+const LISTED_SSIDS: usize = 8;
+
+pub type SharedKarmaDetector = Arc<Mutex<KarmaDetector>>;
+
+#[derive(Debug)]
+pub struct KarmaDetector {
+    window: Duration,
+    max_ssids: usize,
+    /// BSSID as a number → SSID → when it was last seen with it.
+    sightings: BTreeMap<u64, BTreeMap<String, DateTime<Utc>>>,
+}
+
+impl Default for KarmaDetector {
+    fn default() -> Self {
+        Self::new(Duration::minutes(DEFAULT_WINDOW_MINUTES), DEFAULT_MAX_SSIDS)
+    }
+}
+
+/// A radio, or run of adjacent BSSIDs, seen with too many SSIDs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct KarmaFinding {
+    /// Lowercase BSSIDs in the run, in address order.
+    pub bssids: Vec<String>,
+    pub ssids: BTreeSet<String>,
+    /// One BSSID alone went over the limit.
+    pub single_radio: bool,
+    pub window: Duration,
+}
+
+impl KarmaDetector {
+    pub fn new(window: Duration, max_ssids: usize) -> Self {
+        Self {
+            window,
+            max_ssids,
+            sightings: BTreeMap::new(),
+        }
+    }
+
+    /// Add one scan taken at `now` and report every radio over the limit
+    /// within the window.
+    pub fn observe(&mut self, networks: &[WifiNetwork], now: DateTime<Utc>) -> Vec<KarmaFinding> {
+        for net in networks {
+            if net.ssid.trim().is_empty() {
+                continue;
+            }
+            let Some(octets) = parse_mac(&net.bssid) else {
+                continue;
+            };
+            self.sightings
+                .entry(mac_to_u64(octets))
+                .or_default()
+                .insert(net.ssid.clone(), now);
+        }
+
+        let oldest = now - self.window;
+        self.sightings.retain(|_, ssids| {
+            ssids.retain(|_, seen| *seen >= oldest);
+            !ssids.is_empty()
+        });
+
+        self.findings()
+    }
+
+    /// Radios over the limit as of the last scan observed.
+    pub fn findings(&self) -> Vec<KarmaFinding> {
+        self.runs()
+            .into_iter()
+            .filter_map(|run| self.finding(&run))
+            .collect()
+    }
+
+    /// Group the BSSIDs in the window into runs of adjacent addresses. Each
+    /// is compared with the run's first BSSID, so a chain of unrelated APs a
+    /// few addresses apart does not grow into one radio.
+    fn runs(&self) -> Vec<Vec<u64>> {
+        let mut runs: Vec<Vec<u64>> = Vec::new();
+        for &bssid in self.sightings.keys() {
+            match runs.last_mut() {
+                Some(run) if is_adjacent(run[0], bssid) => run.push(bssid),
+                _ => runs.push(vec![bssid]),
+            }
+        }
+        runs
+    }
+
+    fn finding(&self, run: &[u64]) -> Option<KarmaFinding> {
+        let ssids: BTreeSet<String> = run
+            .iter()
+            .flat_map(|bssid| self.sightings[bssid].keys().cloned())
+            .collect();
+        if ssids.len() <= self.max_ssids {
+            return None;
+        }
+        Some(KarmaFinding {
+            bssids: run.iter().map(|&bssid| format_mac(bssid)).collect(),
+            ssids,
+            single_radio: run
+                .iter()
+                .any(|bssid| self.sightings[bssid].len() > self.max_ssids),
+            window: self.window,
+        })
+    }
+}
+
+impl KarmaFinding {
+    /// Critical: one BSSID alone answers for too many SSIDs
+    /// High:     a run of adjacent BSSIDs does between them
+    pub fn grade(&self) -> RiskGrade {
+        if self.single_radio {
+            RiskGrade::Critical
+        } else {
+            RiskGrade::High
+        }
+    }
+
+    pub fn reason(&self) -> String {
+        let source = match self.bssids.as_slice() {
+            [one] => format!("BSSID {}", one),
+            [first, .., last] => format!(
+                "{} adjacent BSSIDs {} to {}",
+                self.bssids.len(),
+                first,
+                last
+            ),
+            [] => "no BSSID".to_string(),
+        };
+        let mut names: Vec<String> = self
+            .ssids
+            .iter()
+            .take(LISTED_SSIDS)
+            .map(|ssid| format!("\"{}\"", ssid))
+            .collect();
+        if self.ssids.len() > LISTED_SSIDS {
+            names.push(format!("{} more", self.ssids.len() - LISTED_SSIDS));
+        }
+        format!(
+            "{} advertised {} different SSIDs in the last {} minutes ({}), typical of a KARMA rogue access point",
+            source,
+            self.ssids.len(),
+            self.window.num_minutes(),
+            names.join(", ")
+        )
+    }
+
+    pub fn threat(&self, now: DateTime<Utc>) -> DetectedThreat {
+        DetectedThreat {
+            threat_type: "karma_ap".to_string(),
+            severity: self.grade().severity().to_string(),
+            network_ssid: "Multiple".to_string(),
+            network_bssid: self.bssids.first().cloned().unwrap_or_default(),
+            details: self.reason(),
+            timestamp: now,
+        }
+    }
+}
+
+/// Raise every network whose BSSID is part of a finding.
+pub fn mark_karma(networks: &mut [WifiNetwork], findings: &[KarmaFinding], policy: &RiskPolicy) {
+    for finding in findings {
+        let grade = finding.grade();
+        for net in networks.iter_mut() {
+            if finding.bssids.contains(&net.bssid.to_ascii_lowercase()) {
+                net.escalate_risk(
+                    grade,
+                    policy.score_floor(grade),
+                    RiskFactorKind::Karma,
+                    finding.reason(),
+                );
+            }
+        }
+    }
+}
+
+fn is_adjacent(a: u64, b: u64) -> bool {
+    a >> 8 == b >> 8 || b.abs_diff(a) <= ADJACENT_SPAN
+}
+
+fn mac_to_u64(octets: [u8; 6]) -> u64 {
+    octets
+        .iter()
+        .fold(0, |acc, &octet| (acc << 8) | u64::from(octet))
+}
+
+fn format_mac(bssid: u64) -> String {
+    let octets = bssid.to_be_bytes();
+    octets[2..]
+        .iter()
+        .map(|octet| format!("{:02x}", octet))
+        .collect::<Vec<_>>()
+        .join(":")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{mark_karma, KarmaDetector};
+    use crate::structures::{DetectedThreat, RiskFactorKind, WifiNetwork};
+    use crate::wifi_functions::RiskPolicy;
+    use chrono::{DateTime, Duration, TimeZone, Utc};
+
+    fn net(ssid: &str, bssid: &str) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            bssid: bssid.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn at(minutes: i64) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, 2, 12, 0, 0).unwrap() + Duration::minutes(minutes)
+    }
+
+    /// Probe-answering radio: a few names per scan, different each time.
+    const PROBED: [&str; 8] = [
+        "eduroam",
+        "Starbucks WiFi",
+        "attwifi",
+        "HomeNet",
+        "xfinitywifi",
+        "Airport_Free",
+        "Hilton Honors",
+        "linksys",
+    ];
+
+    #[test]
+    fn test_one_radio_answering_for_many_ssids_across_scans() {
+        let mut detector = KarmaDetector::default();
+
+        for (minute, names) in PROBED.chunks(2).enumerate() {
+            let scan: Vec<WifiNetwork> = names
+                .iter()
+                .map(|ssid| net(ssid, "00:13:37:A5:00:01"))
+                .collect();
+            let findings = detector.observe(&scan, at(minute as i64));
+            // Nothing to see in any single scan, nor until the sixth name.
+            assert_eq!(findings.is_empty(), minute < 2, "scan {}", minute);
+        }
+
+        let findings = detector.observe(&[], at(4));
+        assert_eq!(findings.len(), 1);
+        assert!(findings[0].single_radio);
+        assert_eq!(findings[0].bssids, ["00:13:37:a5:00:01"]);
+        assert_eq!(findings[0].ssids.len(), 8);
+
+        let threat: DetectedThreat = findings[0].threat(at(4));
+        assert_eq!(threat.threat_type, "karma_ap");
+        assert_eq!(threat.severity, "Critical");
+        assert_eq!(threat.network_bssid, "00:13:37:a5:00:01");
+        assert!(threat.details.starts_with(
+            "BSSID 00:13:37:a5:00:01 advertised 8 different SSIDs in the last 10 minutes"
+        ));
+    }
+
+    #[test]
+    fn test_bssids_differing_in_last_byte_count_as_one_radio() {
+        let mut detector = KarmaDetector::default();
+        // MANA-style: a fresh BSSID per answered SSID, last byte stepping up
+        // and across a byte boundary.
+        let scan: Vec<WifiNetwork> = PROBED
+            .iter()
+            .zip(
+                ["fd", "fe", "ff"]
+                    .iter()
+                    .chain(["00", "01", "02", "03", "04"].iter()),
+            )
+            .enumerate()
+            .map(|(i, (ssid, last))| {
+                let fifth = if i < 3 { "00" } else { "01" };
+                net(ssid, &format!("00:13:37:a5:{}:{}", fifth, last))
+            })
+            .collect();
+
+        let findings = detector.observe(&scan, at(0));
+
+        assert_eq!(findings.len(), 1);
+        assert!(!findings[0].single_radio);
+        assert_eq!(findings[0].bssids.len(), 8);
+        assert_eq!(findings[0].threat(at(0)).severity, "High");
+        assert!(findings[0]
+            .reason()
+            .starts_with("8 adjacent BSSIDs 00:13:37:a5:00:fd to 00:13:37:a5:01:04"));
+    }
+
+    #[test]
+    fn test_enterprise_aps_and_old_sightings_are_not_karma() {
+        let mut detector = KarmaDetector::new(Duration::minutes(10), 5);
+        // Two neighbouring APs from one batch with the same four SSIDs each.
+        let mut scan = Vec::new();
+        for (ssid, last) in [("Corp", 0), ("Guest", 1), ("IoT", 2), ("Voice", 3)] {
+            scan.push(net(ssid, &format!("f4:92:bf:10:00:{:02x}", last)));
+            scan.push(net(ssid, &format!("f4:92:bf:10:00:{:02x}", last + 8)));
+        }
+        assert!(detector.observe(&scan, at(0)).is_empty());
+
+        // Names seen long ago leave the window.
+        for (minute, ssid) in PROBED.iter().enumerate() {
+            let found = detector.observe(&[net(ssid, "02:00:00:00:99:01")], at(minute as i64 * 4));
+            assert!(found.is_empty(), "{}", ssid);
+        }
+    }
+
+    #[test]
+    fn test_chain_of_neighbouring_aps_is_not_one_radio() {
+        let mut detector = KarmaDetector::default();
+        // A dense office of one vendor's APs, each with its own name and
+        // each adjacent to the one before: 00:f8, 01:08, 01:f8, 02:08, ...
+        let scan: Vec<WifiNetwork> = PROBED
+            .iter()
+            .enumerate()
+            .map(|(i, ssid)| {
+                let (fifth, last) = if i % 2 == 0 {
+                    (i / 2, 0xf8)
+                } else {
+                    (i / 2 + 1, 0x08)
+                };
+                net(ssid, &format!("f4:92:bf:10:{:02x}:{:02x}", fifth, last))
+            })
+            .collect();
+
+        assert!(detector.observe(&scan, at(0)).is_empty());
+    }
+
+    #[test]
+    fn test_marks_every_bssid_of_the_run() {
+        let mut detector = KarmaDetector::default();
+        let mut scan: Vec<WifiNetwork> = PROBED
+            .iter()
+            .enumerate()
+            .map(|(i, ssid)| net(ssid, &format!("00:13:37:A5:00:{:02X}", i)))
+            .collect();
+        scan.push(net("Neighbour", "a0:40:a0:00:00:01"));
+
+        let findings = detector.observe(&scan, at(0));
+        mark_karma(&mut scan, &findings, &RiskPolicy::default());
+
+        assert!(scan[..8].iter().all(|n| n.risk == "H"));
+        assert_eq!(scan[8].risk, "");
+
+        let mut scored = net("eduroam", "00:13:37:a5:00:00");
+        scored.set_risk(crate::wifi_functions::calculate_risk(
+            &RiskPolicy::default(),
+            &scored.authentication,
+            &scored.encryption,
+            scored.signal,
+            &scored.ssid,
+        ));
+        mark_karma(
+            std::slice::from_mut(&mut scored),
+            &findings,
+            &RiskPolicy::default(),
+        );
+        let assessment = scored.risk_assessment.unwrap();
+        assert_eq!(
+            assessment.factors.last().unwrap().kind,
+            RiskFactorKind::Karma
+        );
+    }
+}
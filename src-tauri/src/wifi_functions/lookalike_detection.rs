@@ -0,0 +1,434 @@
+//! Look-alike SSIDs: networks whose name imitates a whitelisted or trusted
+//! SSID closely enough to be picked by mistake.
+//!
+//! A candidate is compared with every protected name after removing what a
+//! user cannot see, folding Unicode confusables (UTS #39 skeletons, so
+//! Cyrillic `о`, fullwidth `ｏ` and digit `0` all fold to `o`), stripping the usual band and
+//! guest affixes, and finally by edit distance.
+
+use crate::structures::{RiskFactorKind, RiskGrade, WifiNetwork};
+use crate::wifi_functions::RiskPolicy;
+use std::collections::BTreeSet;
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
+
+/// Words routers and impostors append to an SSID, compared case-insensitively.
+const SUFFIXES: &[&str] = &[
+    "5g", "5ghz", "2.4g", "2.4ghz", "24g", "2g", "6g", "6ghz", "guest", "guests", "ext",
+    "extender", "repeater", "mesh", "plus", "free", "wifi", "wi-fi", "new", "secure", "backup",
+    "hotspot", "public", "visitor", "visitors",
+];
+
+/// Words put in front of an SSID, compared case-insensitively.
+const PREFIXES: &[&str] = &["free", "guest", "public", "open", "new", "real", "official"];
+
+const SEPARATORS: &[char] = &[' ', '_', '-', '.'];
+
+/// How a name imitates a protected one, from most to least deliberate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Trick {
+    /// Zero-width, control or padding characters around the real name.
+    Invisible,
+    /// Characters from another script or digits standing in for letters.
+    Homoglyph,
+    /// The real name with a band, guest or similar affix.
+    Affix,
+    /// Only the capitalisation differs.
+    Case,
+    /// A character or two added, removed or swapped.
+    Typo,
+}
+
+/// A near-match of one protected SSID.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Lookalike {
+    pub imitates: String,
+    /// Every trick found; the first is the most deliberate.
+    pub tricks: Vec<Trick>,
+    pub reasons: Vec<String>,
+}
+
+impl Lookalike {
+    /// Critical: hidden or look-alike characters
+    /// High:     an affix or a capitalisation change
+    /// Medium:   a typo
+    pub fn grade(&self) -> RiskGrade {
+        match self.tricks.first() {
+            Some(Trick::Invisible | Trick::Homoglyph) => RiskGrade::Critical,
+            Some(Trick::Affix | Trick::Case) => RiskGrade::High,
+            _ => RiskGrade::Medium,
+        }
+    }
+}
+
+/// Flag every network whose SSID imitates one of `protected` without being
+/// one of them.
+pub fn mark_lookalikes(
+    networks: &mut [WifiNetwork],
+    protected: &BTreeSet<String>,
+    policy: &RiskPolicy,
+) {
+    if protected.is_empty() {
+        return;
+    }
+    for net in networks.iter_mut() {
+        if net.ssid.is_empty() || protected.contains(&net.ssid) {
+            continue;
+        }
+        let Some(found) = protected
+            .iter()
+            .filter_map(|name| compare(&net.ssid, name))
+            .max_by_key(|found| found.grade())
+        else {
+            continue;
+        };
+
+        let grade = found.grade();
+        net.escalate_risk(
+            grade,
+            policy.score_floor(grade),
+            RiskFactorKind::Lookalike,
+            format!(
+                "Name imitates \"{}\": {}",
+                found.imitates,
+                found.reasons.join(", ")
+            ),
+        );
+        net.imitates = Some(found.imitates);
+    }
+}
+
+/// How `ssid` imitates `protected`, if it does.
+pub fn compare(ssid: &str, protected: &str) -> Option<Lookalike> {
+    if ssid == protected || protected.trim().is_empty() {
+        return None;
+    }
+    let mut tricks = Vec::new();
+    let mut reasons = Vec::new();
+
+    let visible = strip_invisible(ssid, &mut reasons);
+    if !reasons.is_empty() {
+        tricks.push(Trick::Invisible);
+    }
+    if visible == protected {
+        return found(protected, tricks, reasons);
+    }
+
+    let mut name = visible.as_str();
+    if let Some((base, affix)) = strip_affix(name, protected) {
+        tricks.push(Trick::Affix);
+        reasons.push(format!("adds \"{}\"", affix));
+        if base == protected {
+            return found(protected, tricks, reasons);
+        }
+        name = base;
+    }
+
+    if name.to_lowercase() == protected.to_lowercase() {
+        tricks.push(Trick::Case);
+        reasons.push("different capitalisation".to_string());
+        return found(protected, tricks, reasons);
+    }
+    if fold(name) == fold(protected) {
+        tricks.push(Trick::Homoglyph);
+        reasons.push(describe_substitutions(name, protected));
+        return found(protected, tricks, reasons);
+    }
+    if tricks.contains(&Trick::Affix) {
+        // The affix was only stripped because the base folds to `protected`.
+        return found(protected, tricks, reasons);
+    }
+
+    // Hidden characters alone say nothing about which network a name
+    // imitates, so the rest must still be close. Names differing only in
+    // digits are siblings ("Office-1", "Office-2"), not typos.
+    let (a, b) = (name.to_lowercase(), protected.to_lowercase());
+    let distance = strsim::damerau_levenshtein(&a, &b);
+    if distance > typo_limit(b.chars().count()) || without_digits(name) == without_digits(protected)
+    {
+        return None;
+    }
+    tricks.push(Trick::Typo);
+    reasons.push(format!(
+        "{} character{} away",
+        distance,
+        if distance == 1 { "" } else { "s" }
+    ));
+    found(protected, tricks, reasons)
+}
+
+fn found(protected: &str, mut tricks: Vec<Trick>, reasons: Vec<String>) -> Option<Lookalike> {
+    if tricks.is_empty() {
+        return None;
+    }
+    tricks.sort();
+    Some(Lookalike {
+        imitates: protected.to_string(),
+        tricks,
+        reasons,
+    })
+}
+
+/// Edits allowed for a protected name of `len` characters. Short names are
+/// left alone: "Home" is one letter from too many real networks.
+fn typo_limit(len: usize) -> usize {
+    match len {
+        0..=4 => 0,
+        5..=11 => 1,
+        _ => 2,
+    }
+}
+
+fn is_invisible(c: char) -> bool {
+    matches!(
+        c,
+        '\u{00AD}'
+            | '\u{034F}'
+            | '\u{061C}'
+            | '\u{115F}'
+            | '\u{1160}'
+            | '\u{17B4}'
+            | '\u{17B5}'
+            | '\u{180E}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{E0000}'..='\u{E007F}'
+    ) || c.is_control()
+}
+
+/// Drop characters nobody can see, turn exotic spaces into plain ones and
+/// trim, noting each change in `reasons`.
+fn strip_invisible(ssid: &str, reasons: &mut Vec<String>) -> String {
+    let mut hidden = BTreeSet::new();
+    let mut spaces = BTreeSet::new();
+    let mut visible = String::with_capacity(ssid.len());
+    for c in ssid.chars() {
+        if is_invisible(c) {
+            hidden.insert(c);
+        } else if c.is_whitespace() && c != ' ' {
+            spaces.insert(c);
+            visible.push(' ');
+        } else {
+            visible.push(c);
+        }
+    }
+    if !hidden.is_empty() {
+        reasons.push(format!("hidden characters {}", code_points(&hidden)));
+    }
+    if !spaces.is_empty() {
+        reasons.push(format!("unusual spaces {}", code_points(&spaces)));
+    }
+
+    let trimmed = visible.trim_matches(' ');
+    if trimmed.len() != visible.len() && !trimmed.is_empty() {
+        let end = if visible.starts_with(' ') && visible.ends_with(' ') {
+            "at both ends"
+        } else if visible.starts_with(' ') {
+            "in front"
+        } else {
+            "at the end"
+        };
+        reasons.push(format!("extra spaces {}", end));
+        return trimmed.to_string();
+    }
+    visible
+}
+
+fn code_points(chars: &BTreeSet<char>) -> String {
+    chars
+        .iter()
+        .map(|c| format!("U+{:04X}", u32::from(*c)))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Split `name` into a base that folds to `protected` and the affix around
+/// it, e.g. `"CorpNet_5G"` into `("CorpNet", "_5G")`.
+fn strip_affix<'a>(name: &'a str, protected: &str) -> Option<(&'a str, &'a str)> {
+    let target = fold(protected);
+    // ASCII lowercasing keeps byte offsets valid in `name`.
+    let lower = name.to_ascii_lowercase();
+
+    for suffix in SUFFIXES {
+        let Some(rest) = lower.strip_suffix(suffix) else {
+            continue;
+        };
+        let base = name[..rest.len()].trim_end_matches(SEPARATORS);
+        if !base.is_empty() && fold(base) == target {
+            return Some((base, &name[base.len()..]));
+        }
+    }
+    for prefix in PREFIXES {
+        if !lower.starts_with(prefix) {
+            continue;
+        }
+        let base = name[prefix.len()..].trim_start_matches(SEPARATORS);
+        if !base.is_empty() && fold(base) == target {
+            return Some((base, &name[..name.len() - base.len()]));
+        }
+    }
+
+    // A trailing number after a separator: "CorpNet-2", "CorpNet_01".
+    let base = name.trim_end_matches(|c: char| c.is_ascii_digit());
+    let digits = name.len() - base.len();
+    if (1..=2).contains(&digits) && base.ends_with(SEPARATORS) {
+        let base = base.trim_end_matches(SEPARATORS);
+        if !base.is_empty() && fold(base) == target {
+            return Some((base, &name[base.len()..]));
+        }
+    }
+    None
+}
+
+fn without_digits(s: &str) -> String {
+    s.chars().filter(|c| !c.is_ascii_digit()).collect()
+}
+
+/// Case-insensitive confusable skeleton. NFKC first, as UTS #39 expects, so
+/// fullwidth letters and ligatures fold too; the skeleton is taken before
+/// lowercasing as well, or a capital `I` would no longer match an `l`.
+fn fold(s: &str) -> String {
+    let compatible: String = s.nfkc().collect();
+    let lower = skeleton(&compatible).collect::<String>().to_lowercase();
+    skeleton(&lower).collect()
+}
+
+/// Name the characters of `name` that stand in for those of `protected`.
+fn describe_substitutions(name: &str, protected: &str) -> String {
+    let (a, b): (Vec<char>, Vec<char>) = (name.chars().collect(), protected.chars().collect());
+    let pairs: Vec<String> = if a.len() == b.len() {
+        a.iter()
+            .zip(&b)
+            .filter(|(x, y)| x != y && !x.eq_ignore_ascii_case(y))
+            .map(|(x, y)| format!("{} for '{}'", describe_char(*x), y))
+            .collect()
+    } else {
+        a.iter()
+            .filter(|c| !c.is_ascii())
+            .map(|c| describe_char(*c))
+            .collect()
+    };
+    if pairs.is_empty() {
+        "look-alike characters".to_string()
+    } else {
+        format!("look-alike characters {}", pairs.join(", "))
+    }
+}
+
+fn describe_char(c: char) -> String {
+    if c.is_ascii() {
+        format!("'{}'", c)
+    } else {
+        format!("'{}' (U+{:04X})", c, u32::from(c))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare, mark_lookalikes, Trick};
+    use crate::structures::{RiskFactorKind, WifiNetwork};
+    use crate::wifi_functions::RiskPolicy;
+    use serde::Deserialize;
+    use std::collections::BTreeSet;
+
+    #[derive(Deserialize)]
+    struct Case {
+        protected: String,
+        ssid: String,
+        /// Tricks expected, most deliberate first; empty for a legitimate name.
+        tricks: Vec<String>,
+        note: String,
+    }
+
+    fn name(trick: &Trick) -> &'static str {
+        match trick {
+            Trick::Invisible => "invisible",
+            Trick::Homoglyph => "homoglyph",
+            Trick::Affix => "affix",
+            Trick::Case => "case",
+            Trick::Typo => "typo",
+        }
+    }
+
+    #[test]
+    fn test_corpus() {
+        let corpus: Vec<Case> =
+            serde_json::from_str(include_str!("../../fixtures/lookalike/corpus.json")).unwrap();
+        assert!(corpus.len() >= 30);
+
+        for case in corpus {
+            let tricks: Vec<&str> = compare(&case.ssid, &case.protected)
+                .map(|found| found.tricks.iter().map(name).collect())
+                .unwrap_or_default();
+            assert_eq!(
+                tricks, case.tricks,
+                "{:?} against {:?} ({})",
+                case.ssid, case.protected, case.note
+            );
+        }
+    }
+
+    #[test]
+    fn test_explanations() {
+        let reasons = |ssid: &str| compare(ssid, "CorpNet").unwrap().reasons.join(", ");
+
+        assert_eq!(
+            reasons("\u{0421}orpNet"),
+            "look-alike characters '\u{0421}' (U+0421) for 'C'"
+        );
+        assert_eq!(reasons("C0rpNet"), "look-alike characters '0' for 'o'");
+        assert_eq!(reasons("Corp\u{200B}Net"), "hidden characters U+200B");
+        assert_eq!(reasons("CorpNet "), "extra spaces at the end");
+        assert_eq!(reasons("CorpNet_5G"), "adds \"_5G\"");
+        assert_eq!(reasons("Free CorpNet"), "adds \"Free \"");
+        assert_eq!(reasons("CorpNte"), "1 character away");
+    }
+
+    #[test]
+    fn test_marks_only_imitations() {
+        let net = |ssid: &str| WifiNetwork {
+            ssid: ssid.to_string(),
+            ..Default::default()
+        };
+        let protected: BTreeSet<String> = ["CorpNet".to_string(), "CorpNet_5G".to_string()].into();
+        let mut networks = vec![
+            net("CorpNet"),
+            net("CorpNet_5G"),
+            net("C\u{043E}rpNet"),
+            net("CorpNet-EXT"),
+            net(""),
+        ];
+
+        mark_lookalikes(&mut networks, &protected, &RiskPolicy::default());
+
+        let risks: Vec<&str> = networks.iter().map(|n| n.risk.as_str()).collect();
+        assert_eq!(risks, ["", "", "C", "H", ""]);
+        assert_eq!(networks[2].imitates.as_deref(), Some("CorpNet"));
+        assert!(networks[0].imitates.is_none());
+
+        let mut scored = net("C\u{043E}rpNet");
+        scored.set_risk(crate::wifi_functions::calculate_risk(
+            &RiskPolicy::default(),
+            &scored.authentication,
+            &scored.encryption,
+            scored.signal,
+            &scored.ssid,
+        ));
+        mark_lookalikes(
+            std::slice::from_mut(&mut scored),
+            &protected,
+            &RiskPolicy::default(),
+        );
+        let factor = scored.risk_assessment.unwrap().factors.pop().unwrap();
+        assert_eq!(factor.kind, RiskFactorKind::Lookalike);
+        assert_eq!(
+            factor.reason,
+            "Name imitates \"CorpNet\": look-alike characters '\u{043E}' (U+043E) for 'o'"
+        );
+    }
+}
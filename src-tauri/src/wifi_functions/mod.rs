@@ -1,15 +1,57 @@
+mod alert_commands;
+mod baseline_commands;
 mod calculate_risk;
 mod connect_wifi;
+mod continuous_monitor;
 mod disconnect_wifi;
 mod evil_twin_detection;
+mod frequency;
 mod get_active_network;
-mod get_stored_profile_auth_encryption;
+mod history_commands;
+mod incident_commands;
+mod karma_detection;
+mod lookalike_detection;
+mod monitoring_commands;
+pub(crate) mod netsh_labels;
+pub(crate) mod oui_database;
 mod parse_active_interface;
+mod parse_iw_scan;
 mod parse_network_scan;
+mod parse_nmcli_scan;
+mod refresh_oui_database;
+mod risk_policy;
 mod scan_wifi;
-mod trigger_scan;
 
+pub use alert_commands::{get_alert_queue_status, set_alert_credentials};
+pub use baseline_commands::{get_baseline, learn_baseline, reset_baseline};
 pub use connect_wifi::connect_wifi;
+pub use continuous_monitor::{MonitorConfig, MonitorHandle, MonitoringStatus, ScanContext};
 pub use disconnect_wifi::disconnect_wifi;
 pub use get_active_network::get_active_network;
+pub use history_commands::{
+    get_bssid_history, get_connection_history, get_threat_history, list_bssid_history,
+};
+pub use incident_commands::{acknowledge_incident, list_incidents, resolve_incident};
+pub use karma_detection::SharedKarmaDetector;
+pub use monitoring_commands::{
+    get_monitoring_status, list_detectors, start_monitoring, stop_monitoring,
+};
+pub use oui_database::load_oui_database;
+pub use refresh_oui_database::refresh_oui_database;
+pub use risk_policy::{load_risk_policy, RiskPolicy, SharedRiskPolicy};
 pub use scan_wifi::scan_wifi;
+
+#[cfg_attr(not(windows), allow(unused_imports))]
+pub(crate) use frequency::{band_from_frequency, channel_from_frequency};
+pub(crate) use parse_active_interface::{parse_active_interface, parse_interface_status};
+#[cfg_attr(not(windows), allow(unused_imports))]
+pub(crate) use parse_iw_scan::iw_suites_to_auth_encryption;
+pub(crate) use parse_iw_scan::{parse_iw_associated, parse_iw_scan};
+pub(crate) use parse_network_scan::parse_network_scan;
+pub(crate) use parse_nmcli_scan::{parse_nmcli_active, parse_nmcli_scan, NMCLI_SCAN_FIELDS};
+#[cfg(test)]
+pub(crate) use calculate_risk::calculate_risk;
+#[cfg(test)]
+pub(crate) use karma_detection::KarmaDetector;
+pub(crate) use karma_detection::KarmaFinding;
+pub(crate) use scan_wifi::{score_scan, ScoringContext};
@@ -1,62 +1,119 @@
-// use crate::wifi_functions::{MonitorConfig, MonitorHandle};
-// use std::sync::Arc;
-// use tauri::AppHandle;
+use crate::alerts::SharedAlertQueue;
+use crate::backends::SharedBackend;
+use crate::baseline::SharedBaseline;
+use crate::detectors::{DetectorInfo, DetectorRegistry, EnabledThreatTypes};
+use crate::history::{SharedHistory, DEFAULT_RETENTION_DAYS};
+use crate::incidents::DEFAULT_EXPIRY_MINUTES;
+use crate::link_events;
+use crate::rules::SharedRuleSet;
+use crate::structures::DetectedThreat;
+use crate::wifi_functions::{
+    MonitorConfig, MonitorHandle, MonitoringStatus, ScanContext, SharedKarmaDetector,
+    SharedRiskPolicy,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
 
-// // Global monitoring handle
-// lazy_static::lazy_static! {
-//     static ref MONITOR_HANDLE: Arc<MonitorHandle> = Arc::new(MonitorHandle::new());
-// }
+#[derive(Deserialize, Debug, Clone)]
+pub struct MonitorOptions {
+    #[serde(default)]
+    pub server_url: String,
+    #[serde(default)]
+    pub auth_token: String,
+    pub interval_seconds: u64,
+    /// Detector ids, or a map from id to that detector's settings.
+    #[serde(default)]
+    pub enabled_threat_types: EnabledThreatTypes,
+    /// Whitelisted BSSIDs.
+    #[serde(default)]
+    pub whitelist: Vec<String>,
+    /// Blacklisted BSSIDs.
+    #[serde(default)]
+    pub blacklist: Vec<String>,
+    #[serde(default)]
+    pub whitelisted_ssids: Vec<String>,
+    /// Minutes without a sighting before an incident expires.
+    #[serde(default)]
+    pub incident_expiry_minutes: Option<u64>,
+    /// Days of scans and threats kept in the history.
+    #[serde(default)]
+    pub history_retention_days: Option<u64>,
+}
 
-// fn get_monitor_handle() -> Arc<MonitorHandle> {
-//     MONITOR_HANDLE.clone()
-// }
+/// Scan every `interval_seconds` in the background. A threat that opens or
+/// escalates an incident is queued for the server and sent to the UI as a
+/// `threat-detected` event; repeats only update the incident.
+#[tauri::command]
+pub async fn start_monitoring(
+    app: AppHandle,
+    options: MonitorOptions,
+    monitor: State<'_, MonitorHandle>,
+) -> Result<String, String> {
+    let alerts =
+        (!options.server_url.is_empty()).then(|| app.state::<SharedAlertQueue>().inner().clone());
+    let detectors = DetectorRegistry::from_settings(&options.enabled_threat_types)?;
+    let config = MonitorConfig {
+        alerts: alerts.clone(),
+        interval: Duration::from_secs(options.interval_seconds),
+        link_events: detectors
+            .ids()
+            .contains(&"deauth_attack")
+            .then(link_events::default_source)
+            .flatten(),
+        detectors,
+        whitelist: options.whitelist.iter().map(|b| b.to_lowercase()).collect(),
+        blacklist: options.blacklist.iter().map(|b| b.to_lowercase()).collect(),
+        whitelisted_ssids: options.whitelisted_ssids,
+        incident_expiry: Duration::from_secs(
+            60 * options
+                .incident_expiry_minutes
+                .unwrap_or(DEFAULT_EXPIRY_MINUTES),
+        ),
+        history_retention: Duration::from_secs(
+            86400
+                * options
+                    .history_retention_days
+                    .unwrap_or(DEFAULT_RETENTION_DAYS),
+        ),
+    };
+    let context = ScanContext {
+        backend: app.state::<SharedBackend>().inner().clone(),
+        policy: app.state::<SharedRiskPolicy>().inner().clone(),
+        rules: app.state::<SharedRuleSet>().inner().clone(),
+        baseline: app.state::<SharedBaseline>().inner().clone(),
+        karma: app.state::<SharedKarmaDetector>().inner().clone(),
+        history: app.state::<SharedHistory>().inner().clone(),
+    };
+    let sink = Arc::new(move |threat: &DetectedThreat| {
+        if let Err(e) = app.emit("threat-detected", threat) {
+            eprintln!("Failed to emit threat-detected: {}", e);
+        }
+    });
 
-// #[tauri::command]
-// pub async fn start_monitoring(
-//     app: AppHandle,
-//     serverUrl: String,
-//     authToken: String,
-//     intervalSeconds: u64,
-//     enabledThreatTypes: Vec<String>,
-//     whitelist: Vec<String>,
-//     blacklist: Vec<String>,
-// ) -> Result<String, String> {
-//     let handle = get_monitor_handle();
-    
-//     // Check if already running
-//     if handle.is_running().await {
-//         return Err("Monitoring is already running".to_string());
-//     }
+    monitor.start(config, context, sink)?;
+    if let Some(alerts) = alerts {
+        alerts.set_credentials(&options.server_url, &options.auth_token);
+    }
+    Ok("Monitoring started successfully".to_string())
+}
 
-//     let config = MonitorConfig {
-//         server_url: serverUrl,
-//         auth_token: authToken,
-//         interval_seconds: intervalSeconds,
-//         enabled_threat_types: enabledThreatTypes.into_iter().collect(),
-//         app_handle: Some(app),
-//     };
+#[tauri::command]
+pub async fn stop_monitoring(monitor: State<'_, MonitorHandle>) -> Result<String, String> {
+    monitor.stop().await?;
+    Ok("Monitoring stopped successfully".to_string())
+}
 
-//     handle.start(config, whitelist, blacklist).await;
-
-//     Ok("Monitoring started successfully".to_string())
-// }
-
-// #[tauri::command]
-// pub async fn stop_monitoring() -> Result<String, String> {
-//     let handle = get_monitor_handle();
-    
-//     if !handle.is_running().await {
-//         return Err("Monitoring is not running".to_string());
-//     }
-
-//     handle.stop().await;
-
-//     Ok("Monitoring stopped successfully".to_string())
-// }
-
-// #[tauri::command]
-// pub async fn get_monitoring_status() -> Result<bool, String> {
-//     let handle = get_monitor_handle();
-//     Ok(handle.is_running().await)
-// }
+#[tauri::command]
+pub fn get_monitoring_status(
+    monitor: State<'_, MonitorHandle>,
+) -> Result<MonitoringStatus, String> {
+    Ok(monitor.status())
+}
 
+/// The detectors `enabled_threat_types` can switch on, with their settings.
+#[tauri::command]
+pub fn list_detectors() -> Vec<DetectorInfo> {
+    DetectorRegistry::available()
+}
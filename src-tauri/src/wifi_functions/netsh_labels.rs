@@ -0,0 +1,150 @@
+/// Fields netsh prints, independent of the display language.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetshField {
+    Ssid,
+    Bssid,
+    ApBssid,
+    NetworkType,
+    Authentication,
+    Encryption,
+    Cipher,
+    Signal,
+    RadioType,
+    Band,
+    Channel,
+    BasicRates,
+    OtherRates,
+    State,
+    RadioStatus,
+    Guid,
+}
+
+// Labels as printed by English, German, Spanish, Ukrainian, Russian and
+// French builds of Windows, lowercased. "SSID", "BSSID" and "GUID" are not
+// translated; numbered headers ("SSID 1", "BSSID 2") are matched without the
+// number.
+const LABELS: &[(NetshField, &[&str])] = &[
+    (NetshField::Ssid, &["ssid"]),
+    (NetshField::Bssid, &["bssid"]),
+    (NetshField::ApBssid, &["ap bssid", "ap-bssid", "bssid del punto de acceso", "bssid точки доступу", "bssid точки доступа", "bssid de point d'accès"]),
+    (NetshField::NetworkType, &["network type", "netzwerktyp", "tipo de red", "тип мережі", "тип сети", "type de réseau"]),
+    (NetshField::Authentication, &["authentication", "authentifizierung", "autenticación", "перевірка автентичності", "перевірка справжності", "проверка подлинности", "authentification"]),
+    (NetshField::Encryption, &["encryption", "verschlüsselung", "cifrado", "шифрування", "шифрование", "chiffrement"]),
+    (NetshField::Cipher, &["cipher", "verschlüsselungsverfahren", "шифр"]),
+    (NetshField::Signal, &["signal", "señal", "сигнал"]),
+    (NetshField::RadioType, &["radio type", "funktyp", "tipo de radio", "тип радіомодуля", "тип радио", "тип радиомодуля", "type de radio"]),
+    (NetshField::Band, &["band", "banda", "діапазон", "диапазон", "bande"]),
+    (NetshField::Channel, &["channel", "kanal", "canal", "канал"]),
+    (NetshField::BasicRates, &["basic rates (mbps)", "basisraten (mbit/s)", "velocidades básicas (mbps)", "базові швидкості (мбіт/с)", "базовые скорости (мбит/с)", "taux de base (mbits/s)"]),
+    (NetshField::OtherRates, &["other rates (mbps)", "andere raten (mbit/s)", "otras velocidades (mbps)", "інші швидкості (мбіт/с)", "другие скорости (мбит/с)", "autres taux (mbits/s)"]),
+    (NetshField::State, &["state", "status", "estado", "стан", "состояние", "état"]),
+    (NetshField::RadioStatus, &["radio status", "funkstatus", "estado de radio", "стан радіомодуля", "состояние радио", "état de la radio"]),
+    (NetshField::Guid, &["guid"]),
+];
+
+const CONNECTED: &[&str] = &["connected", "verbunden", "conectado", "підключено", "подключено", "connecté"];
+
+const SOFTWARE_OFF: &[&str] = &["software off", "software aus", "software apagado", "програмне забезпечення вимк", "программное обеспечение откл", "logiciel désactivé"];
+
+const OPEN: &[&str] = &["open", "offen", "abierta", "відкрита", "відкритий", "открытая", "открыть", "ouvrir", "ouverte", "otwarte"];
+
+const NONE: &[&str] = &["none", "keine", "ninguna", "ninguno", "немає", "нет", "aucun", "aucune", "brak"];
+
+const AD_HOC: &[&str] = &["adhoc", "ad hoc", "ad-hoc"];
+
+/// Split a `label : value` line. netsh pads labels and puts a space before
+/// the colon, so splitting there keeps the colons inside BSSIDs intact.
+pub fn split_line(line: &str) -> Option<(&str, &str)> {
+    let (key, value) = line.split_once(" :").or_else(|| line.split_once(':'))?;
+    Some((key.trim(), value.trim_start_matches(':').trim()))
+}
+
+/// Resolve a label in any supported language. Labels this table does not
+/// know come back as `None`; the parsers then fall back to field position.
+pub fn field_of(label: &str) -> Option<NetshField> {
+    let label = label.trim().to_lowercase();
+    let label = strip_index(&label);
+
+    LABELS
+        .iter()
+        .find(|(_, names)| names.contains(&label))
+        .map(|(field, _)| *field)
+}
+
+/// Drop the running number from `SSID 1` / `BSSID 12`.
+fn strip_index(label: &str) -> &str {
+    match label.rsplit_once(' ') {
+        Some((head, tail)) if tail.chars().all(|c| c.is_ascii_digit()) => head,
+        _ => label,
+    }
+}
+
+/// Whether `label` is a numbered block header (`SSID 1`, `BSSID 3`).
+pub fn is_numbered(label: &str) -> bool {
+    strip_index(label.trim()).len() != label.trim().len()
+}
+
+pub fn is_connected(value: &str) -> bool {
+    CONNECTED.contains(&value.trim().to_lowercase().as_str())
+}
+
+pub fn is_software_off(line: &str) -> bool {
+    let line = line.to_lowercase();
+    SOFTWARE_OFF.iter().any(|s| line.contains(s))
+}
+
+pub fn is_ad_hoc(value: &str) -> bool {
+    AD_HOC.contains(&value.trim().to_lowercase().as_str())
+}
+
+/// Translate localized "Open" / "None" back into the vocabulary the
+/// risk scoring and connect logic expect; other values (WPA2-Personal,
+/// CCMP, ...) are printed untranslated.
+pub fn normalize_security(value: &str) -> String {
+    let lower = value.trim().to_lowercase();
+    if OPEN.contains(&lower.as_str()) {
+        "Open".to_string()
+    } else if NONE.contains(&lower.as_str()) {
+        "None".to_string()
+    } else {
+        value.trim().to_string()
+    }
+}
+
+/// Decimal commas ("2,4 GHz", "5,5") become points.
+pub fn normalize_number(value: &str) -> String {
+    value.replace(',', ".")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{field_of, is_connected, normalize_security, split_line, NetshField};
+
+    #[test]
+    fn test_labels_in_several_languages() {
+        assert_eq!(field_of("Authentication"), Some(NetshField::Authentication));
+        assert_eq!(field_of("Authentifizierung"), Some(NetshField::Authentication));
+        assert_eq!(field_of("Autenticación"), Some(NetshField::Authentication));
+        assert_eq!(field_of("Перевірка справжності"), Some(NetshField::Authentication));
+        assert_eq!(field_of("BSSID 12"), Some(NetshField::Bssid));
+        assert_eq!(field_of("Channel Utilization"), None);
+    }
+
+    #[test]
+    fn test_split_keeps_bssid_colons() {
+        assert_eq!(
+            split_line("    BSSID 1                 : f4:92:bf:12:34:56"),
+            Some(("BSSID 1", "f4:92:bf:12:34:56"))
+        );
+        assert_eq!(split_line("Bss Load:"), Some(("Bss Load", "")));
+    }
+
+    #[test]
+    fn test_values_are_normalized() {
+        assert_eq!(normalize_security("Offen"), "Open");
+        assert_eq!(normalize_security("Немає"), "None");
+        assert_eq!(normalize_security("WPA2-Personal"), "WPA2-Personal");
+        assert!(is_connected("Verbunden"));
+        assert!(!is_connected("getrennt"));
+    }
+}
@@ -0,0 +1,378 @@
+//! MAC address vendor lookup against the IEEE MA-L, MA-M and MA-S registries.
+//!
+//! The built-in table (`oui_vendors.csv`) is generated from the registries'
+//! CSV exports (`oui.csv`, `mam.csv`, `oui36.csv`, `iab.csv`) by
+//! `scripts/update_oui_vendors.mjs`; run `npm run update-oui` to rebuild it.
+//! Newer exports can also be imported at runtime with `refresh_oui_database`;
+//! the merged table is kept as `oui.csv` in the app data dir and used from
+//! the next start on.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock};
+
+pub const OUI_FILE: &str = "oui.csv";
+
+const EMBEDDED: &str = include_str!("oui_vendors.csv");
+
+/// IEEE assignment block, from the largest prefix to the smallest.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum Registry {
+    /// 24-bit prefix (the classic OUI).
+    MaL,
+    /// 28-bit prefix.
+    MaM,
+    /// 36-bit prefix, including the older IAB assignments.
+    MaS,
+}
+
+impl Registry {
+    pub fn bits(self) -> u32 {
+        match self {
+            Self::MaL => 24,
+            Self::MaM => 28,
+            Self::MaS => 36,
+        }
+    }
+
+    fn hex_digits(self) -> usize {
+        self.bits() as usize / 4
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::MaL => "MA-L",
+            Self::MaM => "MA-M",
+            Self::MaS => "MA-S",
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim() {
+            "MA-L" => Some(Self::MaL),
+            "MA-M" => Some(Self::MaM),
+            "MA-S" | "IAB" => Some(Self::MaS),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct OuiDatabase {
+    entries: HashMap<(Registry, u64), String>,
+}
+
+impl OuiDatabase {
+    /// The table compiled into the binary.
+    pub fn embedded() -> Self {
+        Self::from_ieee_csv(EMBEDDED).expect("embedded OUI table is valid")
+    }
+
+    /// Parse an IEEE registry export (`Registry,Assignment,Organization
+    /// Name,Organization Address`). The address column is optional and rows
+    /// from other registries, such as CIDs, are skipped.
+    pub fn from_ieee_csv(content: &str) -> Result<Self, String> {
+        let mut db = Self::default();
+        for (n, line) in content.lines().enumerate() {
+            let line = line.trim_start_matches('\u{feff}');
+            if line.trim().is_empty() {
+                continue;
+            }
+            let fields = split_csv_line(line).map_err(|e| format!("line {}: {}", n + 1, e))?;
+            if n == 0 && fields[0] == "Registry" {
+                continue;
+            }
+            if fields.len() < 3 {
+                return Err(format!("line {}: expected at least 3 columns", n + 1));
+            }
+            let Some(registry) = Registry::parse(&fields[0]) else {
+                continue;
+            };
+            let assignment = fields[1].trim();
+            if assignment.len() != registry.hex_digits() {
+                return Err(format!(
+                    "line {}: {} assignment '{}' should have {} hex digits",
+                    n + 1,
+                    registry.as_str(),
+                    assignment,
+                    registry.hex_digits()
+                ));
+            }
+            let prefix = u64::from_str_radix(assignment, 16)
+                .map_err(|_| format!("line {}: '{}' is not hexadecimal", n + 1, assignment))?;
+            db.entries
+                .insert((registry, prefix), fields[2].trim().to_string());
+        }
+        Ok(db)
+    }
+
+    /// Read and merge several registry exports.
+    pub fn from_files<P: AsRef<Path>>(paths: &[P]) -> Result<Self, String> {
+        let mut db = Self::default();
+        for path in paths {
+            let path = path.as_ref();
+            let content = std::fs::read_to_string(path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let part =
+                Self::from_ieee_csv(&content).map_err(|e| format!("{}: {}", path.display(), e))?;
+            db.merge(part);
+        }
+        Ok(db)
+    }
+
+    /// Add `other`'s entries, replacing any with the same prefix.
+    pub fn merge(&mut self, other: Self) {
+        self.entries.extend(other.entries);
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Vendor of `mac`, preferring the longest registered prefix.
+    pub fn lookup(&self, mac: [u8; 6]) -> Option<&str> {
+        let value = mac.iter().fold(0u64, |acc, &b| acc << 8 | u64::from(b));
+        [Registry::MaS, Registry::MaM, Registry::MaL]
+            .into_iter()
+            .find_map(|registry| {
+                let prefix = value >> (48 - registry.bits());
+                self.entries.get(&(registry, prefix))
+            })
+            .map(String::as_str)
+    }
+
+    /// Write the table back out in the IEEE column layout, without
+    /// addresses.
+    pub fn to_csv(&self) -> String {
+        let mut rows: Vec<_> = self.entries.iter().collect();
+        rows.sort();
+        let mut out = String::from("Registry,Assignment,Organization Name\n");
+        for ((registry, prefix), name) in rows {
+            let name = if name.contains([',', '"']) {
+                format!("\"{}\"", name.replace('"', "\"\""))
+            } else {
+                name.clone()
+            };
+            out.push_str(&format!(
+                "{},{:0width$X},{}\n",
+                registry.as_str(),
+                prefix,
+                name,
+                width = registry.hex_digits()
+            ));
+        }
+        out
+    }
+}
+
+fn split_csv_line(line: &str) -> Result<Vec<String>, String> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = line.chars().peekable();
+    let mut quoted = false;
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' if quoted => quoted = false,
+            '"' if field.trim().is_empty() => {
+                field.clear();
+                quoted = true;
+            }
+            ',' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    if quoted {
+        return Err("unterminated quoted field".to_string());
+    }
+    fields.push(field);
+    Ok(fields)
+}
+
+static DATABASE: OnceLock<RwLock<Arc<OuiDatabase>>> = OnceLock::new();
+
+fn slot() -> &'static RwLock<Arc<OuiDatabase>> {
+    DATABASE.get_or_init(|| RwLock::new(Arc::new(OuiDatabase::embedded())))
+}
+
+/// The table lookups currently go through.
+pub fn database() -> Arc<OuiDatabase> {
+    slot()
+        .read()
+        .map(|db| db.clone())
+        .unwrap_or_else(|e| e.into_inner().clone())
+}
+
+pub fn install(db: OuiDatabase) {
+    let mut current = slot().write().unwrap_or_else(|e| e.into_inner());
+    *current = Arc::new(db);
+}
+
+/// Use the refreshed table from `dir` if there is one; otherwise, or if it
+/// cannot be read, the built-in table stays in place.
+pub fn load_oui_database(dir: &Path) {
+    let path = dir.join(OUI_FILE);
+    if !path.exists() {
+        return;
+    }
+    match OuiDatabase::from_files(&[&path]) {
+        Ok(db) => {
+            println!(
+                "Loaded {} OUI assignments from {}",
+                db.len(),
+                path.display()
+            );
+            install(db);
+        }
+        Err(e) => eprintln!("{}; using the built-in OUI table", e),
+    }
+}
+
+pub fn parse_mac(mac: &str) -> Option<[u8; 6]> {
+    let parts: Vec<&str> = mac.trim().split([':', '-']).collect();
+    if parts.len() != 6 {
+        return None;
+    }
+    let mut octets = [0u8; 6];
+    for (octet, part) in octets.iter_mut().zip(parts) {
+        if part.len() != 2 {
+            return None;
+        }
+        *octet = u8::from_str_radix(part, 16).ok()?;
+    }
+    Some(octets)
+}
+
+/// OUI of `bssid` as six uppercase hex digits, e.g. `"F492BF"`.
+pub fn extract_oui(bssid: &str) -> Option<String> {
+    parse_mac(bssid).map(|o| format!("{:02X}{:02X}{:02X}", o[0], o[1], o[2]))
+}
+
+/// Whether the U/L bit is set, i.e. the address was not assigned by the
+/// vendor. Mesh nodes and phone hotspots use these as much as rogue APs do.
+pub fn is_locally_administered(bssid: &str) -> bool {
+    parse_mac(bssid).is_some_and(|o| o[0] & 0x02 != 0)
+}
+
+/// Whether the I/G bit is set. No access point may transmit from a group
+/// address, so a BSSID with it set is forged or corrupt.
+pub fn is_multicast(bssid: &str) -> bool {
+    parse_mac(bssid).is_some_and(|o| o[0] & 0x01 != 0)
+}
+
+/// Vendor that registered `bssid`'s prefix. Locally administered addresses
+/// carry no vendor.
+pub fn lookup_vendor(bssid: &str) -> Option<String> {
+    let mac = parse_mac(bssid)?;
+    if mac[0] & 0x02 != 0 {
+        return None;
+    }
+    database().lookup(mac).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        extract_oui, is_locally_administered, is_multicast, lookup_vendor, parse_mac, OuiDatabase,
+    };
+
+    const REGISTRIES: &str = "\
+Registry,Assignment,Organization Name,Organization Address
+MA-L,70B3D5,IEEE Registration Authority,\"445 Hoes Lane Piscataway NJ US 08554 \"
+MA-M,70B3D51,\"Block Vendor, Inc.\",Somewhere
+MA-S,70B3D5123,\"Small \"\"Quoted\"\" Vendor\",Elsewhere
+CID,FA1234,Company ID,ignored
+";
+
+    #[test]
+    fn test_mac_helpers() {
+        assert_eq!(extract_oui("f4:92:bf:12:34:56").as_deref(), Some("F492BF"));
+        assert_eq!(extract_oui("A0-40-A0-10-20-30").as_deref(), Some("A040A0"));
+        assert_eq!(extract_oui("f4:92:bf"), None);
+        assert_eq!(parse_mac("zz:92:bf:12:34:56"), None);
+
+        assert!(is_locally_administered("02:13:37:00:00:01"));
+        assert!(!is_locally_administered("f4:92:bf:12:34:56"));
+        assert!(!is_locally_administered(""));
+        assert!(is_multicast("01:00:5e:00:00:fb"));
+        assert!(!is_multicast("02:13:37:00:00:01"));
+    }
+
+    #[test]
+    fn test_longest_prefix_wins() {
+        let db = OuiDatabase::from_ieee_csv(REGISTRIES).unwrap();
+        assert_eq!(db.len(), 3);
+
+        let lookup = |mac| db.lookup(parse_mac(mac).unwrap());
+        assert_eq!(lookup("70:b3:d5:12:34:56"), Some("Small \"Quoted\" Vendor"));
+        assert_eq!(lookup("70:b3:d5:1f:00:00"), Some("Block Vendor, Inc."));
+        assert_eq!(
+            lookup("70:b3:d5:ff:00:00"),
+            Some("IEEE Registration Authority")
+        );
+        assert_eq!(lookup("fa:12:34:00:00:00"), None);
+    }
+
+    #[test]
+    fn test_csv_round_trip_and_errors() {
+        let db = OuiDatabase::from_ieee_csv(REGISTRIES).unwrap();
+        let again = OuiDatabase::from_ieee_csv(&db.to_csv()).unwrap();
+        assert_eq!(again.entries, db.entries);
+
+        let err = OuiDatabase::from_ieee_csv("MA-M,70B3D5,Too short\n").unwrap_err();
+        assert!(
+            err.contains("line 1") && err.contains("7 hex digits"),
+            "{}",
+            err
+        );
+        assert!(OuiDatabase::from_ieee_csv("MA-L,\"F492BF,x\n").is_err());
+    }
+
+    #[test]
+    fn test_embedded_table() {
+        let db = OuiDatabase::embedded();
+        assert!(db.len() > 200);
+        assert_eq!(
+            lookup_vendor("f4:92:bf:12:34:56").as_deref(),
+            Some("Ubiquiti Inc")
+        );
+        assert_eq!(
+            lookup_vendor("A0-40-A0-10-20-30").as_deref(),
+            Some("NETGEAR")
+        );
+        assert_eq!(
+            lookup_vendor("00:03:93:12:34:56").as_deref(),
+            Some("Apple, Inc.")
+        );
+        assert_eq!(lookup_vendor("02:13:37:00:00:01"), None);
+        assert_eq!(lookup_vendor("f4:92:bf"), None);
+    }
+
+    #[test]
+    fn test_from_files_merges() {
+        let dir = std::env::temp_dir().join(format!("wids-oui-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mal = dir.join("oui.csv");
+        let mas = dir.join("oui36.csv");
+        std::fs::write(
+            &mal,
+            "Registry,Assignment,Organization Name\nMA-L,F492BF,Ubiquiti Inc\n",
+        )
+        .unwrap();
+        std::fs::write(&mas, "MA-S,70B3D5123,Small Vendor\n").unwrap();
+
+        let db = OuiDatabase::from_files(&[&mal, &mas]).unwrap();
+        assert_eq!(db.len(), 2);
+        let err = OuiDatabase::from_files(&[dir.join("missing.csv")]).unwrap_err();
+        assert!(err.contains("missing.csv"));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
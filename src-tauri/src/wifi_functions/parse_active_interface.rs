@@ -1,61 +1,129 @@
-use crate::structures::WifiNetwork;
-use crate::wifi_functions::calculate_risk::calculate_risk;
+use crate::structures::{AuthSuite, Cipher, Signal, WifiNetwork};
+use crate::wifi_functions::netsh_labels::{
+    field_of, is_connected, is_software_off, normalize_security, split_line, NetshField,
+};
+
+/// What `netsh wlan show interfaces` says about the first adapter.
+#[derive(Debug, Default)]
+pub struct InterfaceStatus {
+    pub present: bool,
+    pub radio_off: bool,
+    pub connected: bool,
+    pub ssid: String,
+}
+
+pub fn parse_interface_status(output: &str) -> InterfaceStatus {
+    let mut status = InterfaceStatus::default();
+
+    for line in output.lines() {
+        // The radio state sits on a continuation line without a label.
+        if is_software_off(line) {
+            status.radio_off = true;
+        }
+
+        let Some((key, value)) = split_line(line) else {
+            continue;
+        };
+
+        match field_of(key) {
+            // "GUID" is printed untranslated for every adapter.
+            Some(NetshField::Guid) => status.present = true,
+            Some(NetshField::State) => {
+                status.present = true;
+                status.connected = is_connected(value);
+            }
+            Some(NetshField::Ssid) => status.ssid = value.to_string(),
+            _ => {}
+        }
+    }
+
+    status
+}
 
 pub fn parse_active_interface(output: &str) -> Vec<WifiNetwork> {
     let mut ssid = String::new();
     let mut bssid = String::new();
-    let mut auth = String::new();
-    let mut encryption = String::new();
-    let mut signal = String::new();
+    let mut auth = AuthSuite::default();
+    let mut encryption = Cipher::default();
+    let mut signal = None;
 
     for line in output.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("SSID") && trimmed.contains(":") {
-            ssid = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("AP BSSID") {
-            bssid = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("Authentication") {
-            auth = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("Cipher") {
-            encryption = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("Signal") {
-            signal = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
+        let Some((key, value)) = split_line(line) else {
+            continue;
+        };
+
+        match field_of(key) {
+            Some(NetshField::Ssid) => ssid = value.to_string(),
+            Some(NetshField::ApBssid) => bssid = value.to_string(),
+            Some(NetshField::Authentication) => auth = AuthSuite::parse(&normalize_security(value)),
+            Some(NetshField::Cipher) | Some(NetshField::Encryption) => {
+                encryption = Cipher::parse(&normalize_security(value))
+            }
+            Some(NetshField::Signal) => signal = Signal::parse(value),
+            _ => {}
         }
     }
 
-    let risk = calculate_risk(&auth, &encryption, &signal, &ssid);
     vec![WifiNetwork {
         ssid,
         authentication: auth,
         encryption,
         bssid,
         signal,
-        risk,
+        risk: String::new(),
         is_evil_twin: false,
+        ..Default::default()
     }]
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_active_interface, parse_interface_status};
+
+    const INTERFACES: [(&str, &str); 4] = [
+        ("en", include_str!("../../fixtures/netsh/interfaces_en.txt")),
+        ("de", include_str!("../../fixtures/netsh/interfaces_de.txt")),
+        ("es", include_str!("../../fixtures/netsh/interfaces_es.txt")),
+        ("uk", include_str!("../../fixtures/netsh/interfaces_uk.txt")),
+    ];
+
+    #[test]
+    fn test_active_interface_in_every_locale() {
+        for (locale, output) in INTERFACES {
+            let active = &parse_active_interface(output)[0];
+
+            assert_eq!(active.ssid, "HomeNet", "{}", locale);
+            assert_eq!(active.bssid, "f4:92:bf:12:34:57", "{}", locale);
+            assert_eq!(active.authentication, "WPA2-Personal", "{}", locale);
+            assert_eq!(active.encryption, "CCMP", "{}", locale);
+            assert_eq!(active.signal.map(|s| s.percent), Some(70), "{}", locale);
+        }
+    }
+
+    #[test]
+    fn test_interface_status_in_every_locale() {
+        for (locale, output) in INTERFACES {
+            let status = parse_interface_status(output);
+
+            assert!(status.present, "{}", locale);
+            assert!(status.connected, "{}", locale);
+            assert!(!status.radio_off, "{}", locale);
+            assert_eq!(status.ssid, "HomeNet", "{}", locale);
+        }
+    }
+
+    #[test]
+    fn test_radio_off_is_detected() {
+        let status = parse_interface_status(include_str!("../../fixtures/netsh/interfaces_de_off.txt"));
+
+        assert!(status.present);
+        assert!(status.radio_off);
+        assert!(!status.connected);
+    }
+
+    #[test]
+    fn test_no_adapter() {
+        let status = parse_interface_status("There is 0 interface on the system:\n");
+        assert!(!status.present);
+    }
+}
@@ -0,0 +1,431 @@
+use crate::structures::{AuthSuite, Cipher, CipherSuites, RadioType, Signal, WifiNetwork};
+use crate::wifi_functions::frequency::{band_from_frequency, channel_from_frequency};
+
+/// Parse `iw dev <if> scan dump` output, one entry per BSS.
+pub fn parse_iw_scan(output: &str) -> Vec<WifiNetwork> {
+    parse_iw_entries(output)
+        .into_iter()
+        .filter_map(|entry| entry.into_network())
+        .collect()
+}
+
+/// The BSS marked `-- associated` in a scan dump, if any.
+pub fn parse_iw_associated(output: &str) -> Vec<WifiNetwork> {
+    parse_iw_entries(output)
+        .into_iter()
+        .filter(|entry| entry.associated)
+        .filter_map(|entry| entry.into_network())
+        .collect()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Section {
+    None,
+    Rsn,
+    Wpa,
+    HtOperation,
+    VhtOperation,
+}
+
+#[derive(Default)]
+struct IwEntry {
+    bssid: String,
+    associated: bool,
+    ssid: Option<String>,
+    freq: Option<u32>,
+    signal_dbm: Option<i32>,
+    beacon_interval: Option<u16>,
+    capability: Option<u16>,
+    ds_channel: Option<u32>,
+    ht_primary_channel: Option<u32>,
+    ht_secondary_offset: bool,
+    ht_any_width: bool,
+    ht_operation: bool,
+    vht_width: Option<u8>,
+    rsn: Option<CipherSuites>,
+    wpa: Option<CipherSuites>,
+    pmf_capable: Option<bool>,
+    pmf_required: Option<bool>,
+    wps: bool,
+    ht: bool,
+    vht: bool,
+    he: bool,
+    country: Option<String>,
+}
+
+fn parse_iw_entries(output: &str) -> Vec<IwEntry> {
+    let mut entries = Vec::new();
+    let mut current: Option<IwEntry> = None;
+    let mut section = Section::None;
+
+    for line in output.lines() {
+        if let Some(rest) = line.strip_prefix("BSS ") {
+            if let Some(entry) = current.take() {
+                entries.push(entry);
+            }
+            let bssid = rest.split('(').next().unwrap_or("").trim().to_lowercase();
+            current = Some(IwEntry {
+                bssid,
+                associated: rest.contains("-- associated"),
+                ..Default::default()
+            });
+            section = Section::None;
+            continue;
+        }
+
+        let entry = match current.as_mut() {
+            Some(entry) => entry,
+            None => continue,
+        };
+
+        let depth = line.chars().take_while(|c| *c == '\t').count();
+        if depth == 0 {
+            continue;
+        }
+
+        if depth == 1 {
+            let body = &line[1..];
+            let (key, value) = match body.split_once(':') {
+                Some((key, value)) => (key.trim(), value),
+                None => (body.trim(), ""),
+            };
+
+            section = Section::None;
+            match key {
+                "SSID" => entry.ssid = Some(value.strip_prefix(' ').unwrap_or(value).to_string()),
+                "freq" => entry.freq = value.trim().parse::<f32>().ok().map(|f| f.round() as u32),
+                "signal" => {
+                    entry.signal_dbm = value
+                        .split_whitespace()
+                        .next()
+                        .and_then(|v| v.parse::<f32>().ok())
+                        .map(|v| v.round() as i32)
+                }
+                "beacon interval" => {
+                    entry.beacon_interval = value.split_whitespace().next().and_then(|v| v.parse().ok())
+                }
+                "capability" => entry.capability = parse_hex_in_parens(value),
+                "DS Parameter set" => {
+                    entry.ds_channel = value.trim().strip_prefix("channel").and_then(|c| c.trim().parse().ok())
+                }
+                "Country" => entry.country = value.split_whitespace().next().map(|c| c.to_string()),
+                "RSN" => {
+                    entry.rsn = Some(CipherSuites::default());
+                    section = Section::Rsn;
+                }
+                "WPA" => {
+                    entry.wpa = Some(CipherSuites::default());
+                    section = Section::Wpa;
+                }
+                "HT capabilities" => entry.ht = true,
+                "VHT capabilities" => entry.vht = true,
+                "HE capabilities" => entry.he = true,
+                "HT operation" => {
+                    entry.ht_operation = true;
+                    section = Section::HtOperation;
+                }
+                "VHT operation" => section = Section::VhtOperation,
+                "WPS" => entry.wps = true,
+                _ => {}
+            }
+            continue;
+        }
+
+        let item = line.trim().trim_start_matches('*').trim();
+        let (key, value) = match item.split_once(':') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => continue,
+        };
+
+        match section {
+            Section::Rsn | Section::Wpa => {
+                let suites = if section == Section::Rsn {
+                    entry.rsn.get_or_insert_with(Default::default)
+                } else {
+                    entry.wpa.get_or_insert_with(Default::default)
+                };
+                match key {
+                    "Group cipher" => suites.group_cipher = Some(value.to_string()),
+                    "Pairwise ciphers" => {
+                        suites.pairwise_ciphers = value.split_whitespace().map(|c| c.to_string()).collect()
+                    }
+                    "Authentication suites" => suites.akm_suites = split_akm_suites(value),
+                    "Capabilities" if section == Section::Rsn => {
+                        entry.pmf_capable = Some(value.contains("MFP-capable"));
+                        entry.pmf_required = Some(value.contains("MFP-required"));
+                    }
+                    _ => {}
+                }
+            }
+            Section::HtOperation => match key {
+                "primary channel" => entry.ht_primary_channel = value.parse().ok(),
+                "secondary channel offset" => entry.ht_secondary_offset = value != "no secondary",
+                "STA channel width" => entry.ht_any_width = value == "any",
+                _ => {}
+            },
+            Section::VhtOperation => {
+                if key == "channel width" {
+                    entry.vht_width = value
+                        .split_once('(')
+                        .and_then(|(_, w)| w.split_whitespace().next())
+                        .and_then(|w| w.parse().ok());
+                }
+            }
+            Section::None => {}
+        }
+    }
+
+    if let Some(entry) = current {
+        entries.push(entry);
+    }
+
+    entries
+}
+
+impl IwEntry {
+    fn into_network(self) -> Option<WifiNetwork> {
+        let ssid = self.ssid.clone().unwrap_or_default();
+        if ssid.trim().is_empty() || self.bssid.is_empty() {
+            return None;
+        }
+
+        let privacy = self.capability.map(|c| c & 0x0010 != 0).unwrap_or(false);
+        let (authentication, encryption) = iw_suites_to_auth_encryption(self.rsn.as_ref(), self.wpa.as_ref(), privacy);
+
+        let channel = self
+            .ds_channel
+            .or(self.ht_primary_channel)
+            .or_else(|| self.freq.and_then(channel_from_frequency));
+
+        let channel_width = match self.vht_width {
+            Some(width) => Some(width),
+            None if self.ht_any_width && self.ht_secondary_offset => Some(40),
+            None if self.ht_operation => Some(20),
+            None => None,
+        };
+
+        let radio_type = if self.he {
+            Some(RadioType::Dot11ax)
+        } else if self.vht {
+            Some(RadioType::Dot11ac)
+        } else if self.ht {
+            Some(RadioType::Dot11n)
+        } else {
+            None
+        };
+
+        Some(WifiNetwork {
+            ssid,
+            authentication,
+            encryption,
+            bssid: self.bssid,
+            signal: self.signal_dbm.map(Signal::from_dbm),
+            risk: String::new(),
+            is_evil_twin: false,
+            frequency_mhz: self.freq,
+            channel,
+            beacon_interval: self.beacon_interval,
+            capability: self.capability,
+            rsn: self.rsn,
+            wpa: self.wpa,
+            pmf_capable: self.pmf_capable,
+            pmf_required: self.pmf_required,
+            wps: Some(self.wps),
+            ht_capabilities: Some(self.ht),
+            vht_capabilities: Some(self.vht),
+            he_capabilities: Some(self.he),
+            channel_width,
+            country: self.country,
+            band: self.freq.and_then(band_from_frequency),
+            radio_type,
+            ..Default::default()
+        })
+    }
+}
+
+/// Extract the hex value iw prints in parentheses, e.g. `ESS Privacy (0x0411)`.
+fn parse_hex_in_parens(value: &str) -> Option<u16> {
+    let start = value.rfind("(0x")? + 3;
+    let end = value[start..].find(')')? + start;
+    u16::from_str_radix(&value[start..end], 16).ok()
+}
+
+/// iw separates suites with spaces but prints 802.1X as `IEEE 802.1X`.
+fn split_akm_suites(value: &str) -> Vec<String> {
+    let mut suites: Vec<String> = Vec::new();
+    let mut pending_ieee = false;
+
+    for token in value.split_whitespace() {
+        if token.ends_with("IEEE") {
+            pending_ieee = true;
+            suites.push(token.to_string());
+        } else if pending_ieee {
+            pending_ieee = false;
+            if let Some(last) = suites.last_mut() {
+                last.push(' ');
+                last.push_str(token);
+            }
+        } else {
+            suites.push(token.to_string());
+        }
+    }
+
+    suites
+}
+
+/// Map RSN/WPA suites onto the authentication/cipher names netsh uses.
+pub fn iw_suites_to_auth_encryption(
+    rsn: Option<&CipherSuites>,
+    wpa: Option<&CipherSuites>,
+    privacy: bool,
+) -> (AuthSuite, Cipher) {
+    let has_akm = |suites: Option<&CipherSuites>, needle: &str| {
+        suites
+            .map(|s| s.akm_suites.iter().any(|a| a.contains(needle)))
+            .unwrap_or(false)
+    };
+
+    let authentication = if has_akm(rsn, "SUITE-B") {
+        AuthSuite::Wpa3Enterprise
+    } else if has_akm(rsn, "SAE") && has_akm(rsn, "PSK") {
+        AuthSuite::Wpa3Transition
+    } else if has_akm(rsn, "SAE") {
+        AuthSuite::Wpa3Personal
+    } else if has_akm(rsn, "OWE") {
+        AuthSuite::Owe
+    } else if has_akm(rsn, "802.1X") {
+        AuthSuite::Wpa2Enterprise
+    } else if has_akm(rsn, "PSK") {
+        AuthSuite::Wpa2Personal
+    } else if has_akm(wpa, "802.1X") {
+        AuthSuite::WpaEnterprise
+    } else if has_akm(wpa, "PSK") {
+        AuthSuite::WpaPersonal
+    } else {
+        AuthSuite::Open
+    };
+
+    let pairwise: Vec<&String> = rsn
+        .into_iter()
+        .chain(wpa)
+        .flat_map(|s| s.pairwise_ciphers.iter())
+        .collect();
+
+    let encryption = if pairwise.iter().any(|c| c.contains("GCMP")) {
+        Cipher::Gcmp
+    } else if pairwise.iter().any(|c| c.contains("CCMP")) {
+        Cipher::Ccmp
+    } else if pairwise.iter().any(|c| c.contains("TKIP")) {
+        Cipher::Tkip
+    } else if privacy && rsn.is_none() && wpa.is_none() {
+        Cipher::Wep
+    } else {
+        Cipher::None
+    };
+
+    (authentication, encryption)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_iw_associated, parse_iw_scan, split_akm_suites};
+    use crate::structures::{Band, RadioType, Signal};
+
+    const SCAN_DUMP: &str = include_str!("../../fixtures/iw/scan_dump.txt");
+
+    #[test]
+    fn test_parses_recorded_dump() {
+        let networks = parse_iw_scan(SCAN_DUMP);
+
+        // The trailing hidden BSS is skipped.
+        assert_eq!(networks.len(), 7);
+
+        let home_5g = &networks[0];
+        assert_eq!(home_5g.ssid, "HomeNet");
+        assert_eq!(home_5g.bssid, "f4:92:bf:12:34:57");
+        assert_eq!(home_5g.frequency_mhz, Some(5180));
+        assert_eq!(home_5g.channel, Some(36));
+        assert_eq!(home_5g.band, Some(Band::Ghz5));
+        assert_eq!(home_5g.radio_type, Some(RadioType::Dot11ax));
+        assert_eq!(home_5g.signal, Some(Signal::new(90, -55)));
+        assert_eq!(home_5g.beacon_interval, Some(100));
+        assert_eq!(home_5g.capability, Some(0x0111));
+        assert_eq!(home_5g.country.as_deref(), Some("DE"));
+        assert_eq!(home_5g.channel_width, Some(80));
+        assert_eq!(home_5g.ht_capabilities, Some(true));
+        assert_eq!(home_5g.vht_capabilities, Some(true));
+        assert_eq!(home_5g.he_capabilities, Some(true));
+        assert_eq!(home_5g.wps, Some(true));
+        assert_eq!(home_5g.pmf_capable, Some(true));
+        assert_eq!(home_5g.pmf_required, Some(false));
+        assert_eq!(home_5g.authentication, "WPA2-Personal");
+        assert_eq!(home_5g.encryption, "CCMP");
+    }
+
+    #[test]
+    fn test_rsn_suites() {
+        let networks = parse_iw_scan(SCAN_DUMP);
+        let rsn = networks[0].rsn.as_ref().unwrap();
+
+        assert_eq!(rsn.group_cipher.as_deref(), Some("CCMP"));
+        assert_eq!(rsn.pairwise_ciphers, vec!["CCMP"]);
+        assert_eq!(rsn.akm_suites, vec!["PSK"]);
+
+        let home_24 = &networks[1];
+        assert_eq!(home_24.frequency_mhz, Some(2437));
+        assert_eq!(home_24.channel_width, Some(20));
+        assert_eq!(home_24.rsn.as_ref().unwrap().akm_suites, vec!["PSK", "SAE"]);
+        assert_eq!(home_24.wps, Some(false));
+    }
+
+    #[test]
+    fn test_security_mapping() {
+        let networks = parse_iw_scan(SCAN_DUMP);
+        let by_ssid = |ssid: &str| networks.iter().find(|n| n.ssid == ssid).unwrap();
+
+        let modern = by_ssid("Modern");
+        assert_eq!(modern.authentication, "WPA3-Personal");
+        assert_eq!(modern.pmf_required, Some(true));
+        assert_eq!(modern.channel, Some(44));
+
+        let office = by_ssid("Office");
+        assert_eq!(office.authentication, "WPA2-Enterprise");
+        assert_eq!(office.beacon_interval, Some(102));
+
+        let legacy = by_ssid("Legacy");
+        assert_eq!(legacy.authentication, "WPA-Personal");
+        assert_eq!(legacy.encryption, "TKIP");
+        assert!(legacy.rsn.is_none());
+
+        let printer = by_ssid("Printer");
+        assert_eq!(printer.authentication, "Open");
+        assert_eq!(printer.encryption, "WEP");
+
+        let airport = by_ssid("Free Airport WiFi");
+        assert_eq!(airport.authentication, "Open");
+        assert_eq!(airport.encryption, "None");
+    }
+
+    #[test]
+    fn test_associated_entry() {
+        let active = parse_iw_associated(SCAN_DUMP);
+
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].bssid, "f4:92:bf:12:34:57");
+    }
+
+    #[test]
+    fn test_split_akm_suites_keeps_ieee_8021x_together() {
+        assert_eq!(
+            split_akm_suites("IEEE 802.1X FT/IEEE 802.1X PSK"),
+            vec!["IEEE 802.1X", "FT/IEEE 802.1X", "PSK"]
+        );
+    }
+
+    #[test]
+    fn test_truncated_dump() {
+        assert!(parse_iw_scan("").is_empty());
+        assert!(parse_iw_scan("BSS 00:11:22:33:44:55(on wlan0)\n\tfreq:").is_empty());
+        assert!(parse_iw_scan("\tSSID: orphan\n").is_empty());
+    }
+}
@@ -1,79 +1,107 @@
-use crate::structures::WifiNetwork;
-use crate::wifi_functions::calculate_risk::calculate_risk;
+use crate::structures::{AuthSuite, Band, Cipher, NetworkType, RadioType, Signal, WifiNetwork};
+use crate::wifi_functions::netsh_labels::{
+    field_of, is_ad_hoc, is_numbered, normalize_number, normalize_security, split_line, NetshField,
+};
 
+/// Parse `netsh wlan show networks mode=bssid` in any display language.
+///
+/// Labels are looked up in the per-locale tables; a label the tables do not
+/// know is resolved by its position in the block (network type,
+/// authentication and encryption always open an SSID block) or by the shape
+/// of its value (signal in percent, radio type as `802.11x`).
 pub fn parse_network_scan(output: &str) -> Vec<WifiNetwork> {
     let mut networks = Vec::new();
 
     let mut current_ssid = String::new();
-    let mut current_auth = String::new();
-    let mut current_encryption = String::new();
+    let mut current_auth = AuthSuite::default();
+    let mut current_encryption = Cipher::default();
+    let mut current_network_type = None;
     let mut current_bssid: Option<WifiNetwork> = None;
+    let mut ssid_fields = 0;
 
     for line in output.lines() {
-        let trimmed = line.trim();
-        if trimmed.starts_with("SSID") && trimmed.contains(":") {
-            if let Some(network) = current_bssid.take() {
-                networks.push(network);
-            }
-            
-            let ssid_part = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            
-            if !ssid_part.is_empty() {
-                current_ssid = ssid_part;
-                current_auth = String::new();
-                current_encryption = String::new();
-            }
-        } else if trimmed.starts_with("Authentication") && trimmed.contains(":") {
-            current_auth = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("Encryption") && trimmed.contains(":") {
-            current_encryption = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-        } else if trimmed.starts_with("BSSID") && trimmed.contains(":") {
-            if let Some(network) = current_bssid.take() {
-                networks.push(network);
-            }
+        let Some((key, value)) = split_line(line) else {
+            continue;
+        };
+        let field = field_of(key);
 
-            let bssid = trimmed
-                .splitn(2, ':')
-                .nth(1)
-                .unwrap_or("")
-                .trim()
-                .to_string();
-            
-            if !current_ssid.is_empty() && !bssid.is_empty() {
-                current_bssid = Some(WifiNetwork {
-                    ssid: current_ssid.clone(),
-                    authentication: current_auth.clone(),
-                    encryption: current_encryption.clone(),
-                    bssid,
-                    signal: String::new(),
-                    risk: String::new(),
-                    is_evil_twin: false,
-                });
+        match field {
+            Some(NetshField::Ssid) if is_numbered(key) => {
+                if let Some(network) = current_bssid.take() {
+                    networks.push(network);
+                }
+
+                // Hidden networks print an empty SSID; their BSSIDs are skipped.
+                current_ssid = value.to_string();
+                current_auth = AuthSuite::default();
+                current_encryption = Cipher::default();
+                current_network_type = None;
+                ssid_fields = 0;
             }
-        } else if trimmed.starts_with("Signal") && trimmed.contains(":") {
-            if let Some(network) = current_bssid.as_mut() {
-                network.signal = trimmed
-                    .splitn(2, ':')
-                    .nth(1)
-                    .unwrap_or("")
-                    .trim()
-                    .to_string();
+            Some(NetshField::Bssid) if is_numbered(key) => {
+                if let Some(network) = current_bssid.take() {
+                    networks.push(network);
+                }
+
+                if !current_ssid.is_empty() && !value.is_empty() {
+                    current_bssid = Some(WifiNetwork {
+                        ssid: current_ssid.clone(),
+                        authentication: current_auth.clone(),
+                        encryption: current_encryption.clone(),
+                        bssid: value.to_string(),
+                        signal: None,
+                        risk: String::new(),
+                        is_evil_twin: false,
+                        network_type: current_network_type,
+                        ..Default::default()
+                    });
+                }
             }
+            _ if current_ssid.is_empty() => {}
+            _ => match current_bssid.as_mut() {
+                None => {
+                    let field = field.unwrap_or(match ssid_fields {
+                        0 => NetshField::NetworkType,
+                        1 => NetshField::Authentication,
+                        _ => NetshField::Encryption,
+                    });
+                    ssid_fields += 1;
+
+                    match field {
+                        NetshField::NetworkType => current_network_type = parse_network_type(value),
+                        NetshField::Authentication => {
+                            current_auth = AuthSuite::parse(&normalize_security(value))
+                        }
+                        NetshField::Encryption => {
+                            current_encryption = Cipher::parse(&normalize_security(value))
+                        }
+                        _ => {}
+                    }
+                }
+                Some(network) => {
+                    let field = field.or_else(|| {
+                        if network.signal.is_none() && value.ends_with('%') {
+                            Some(NetshField::Signal)
+                        } else if value.starts_with("802.11") {
+                            Some(NetshField::RadioType)
+                        } else {
+                            None
+                        }
+                    });
+
+                    // Whole-label matching keeps the "Channel Utilization"
+                    // line of a Bss Load block from being taken for the channel.
+                    match field {
+                        Some(NetshField::Signal) => network.signal = Signal::parse(value),
+                        Some(NetshField::RadioType) => network.radio_type = RadioType::parse(value),
+                        Some(NetshField::Band) => network.band = parse_band(value),
+                        Some(NetshField::Channel) => network.channel = value.parse().ok(),
+                        Some(NetshField::BasicRates) => network.basic_rates = parse_rates(value),
+                        Some(NetshField::OtherRates) => network.other_rates = parse_rates(value),
+                        _ => {}
+                    }
+                }
+            },
         }
     }
 
@@ -81,9 +109,144 @@ pub fn parse_network_scan(output: &str) -> Vec<WifiNetwork> {
         networks.push(network);
     }
 
-    for net in networks.iter_mut() {
-        net.risk = calculate_risk(&net.authentication, &net.encryption, &net.signal, &net.ssid);
+    // Older netsh builds print no Band line; channel numbers above 14 are
+    // then taken as 5 GHz, since those builds predate 6 GHz support.
+    for network in networks.iter_mut() {
+        if network.band.is_none() {
+            network.band = network.channel.map(|c| if c <= 14 { Band::Ghz2_4 } else { Band::Ghz5 });
+        }
     }
 
     networks
 }
+
+fn parse_band(value: &str) -> Option<Band> {
+    let number = normalize_number(value.split_whitespace().next().unwrap_or(""));
+    match number.as_str() {
+        "2.4" => Some(Band::Ghz2_4),
+        "5" => Some(Band::Ghz5),
+        "6" => Some(Band::Ghz6),
+        _ => None,
+    }
+}
+
+fn parse_network_type(value: &str) -> Option<NetworkType> {
+    if value.is_empty() {
+        None
+    } else if is_ad_hoc(value) {
+        Some(NetworkType::AdHoc)
+    } else {
+        Some(NetworkType::Infrastructure)
+    }
+}
+
+fn parse_rates(value: &str) -> Vec<f32> {
+    value
+        .split_whitespace()
+        .filter_map(|rate| normalize_number(rate).parse().ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_network_scan;
+    use crate::structures::{Band, NetworkType, RadioType};
+
+    const NETWORKS_EN: &str = include_str!("../../fixtures/netsh/networks_en.txt");
+
+    const NETWORKS_LOCALIZED: [(&str, &str); 3] = [
+        ("de", include_str!("../../fixtures/netsh/networks_de.txt")),
+        ("es", include_str!("../../fixtures/netsh/networks_es.txt")),
+        ("uk", include_str!("../../fixtures/netsh/networks_uk.txt")),
+    ];
+
+    #[test]
+    fn test_parses_radio_details() {
+        let networks = parse_network_scan(NETWORKS_EN);
+        assert_eq!(networks.len(), 4);
+
+        let home_24 = &networks[0];
+        assert_eq!(home_24.ssid, "HomeNet");
+        assert_eq!(home_24.signal.map(|s| s.percent), Some(82));
+        assert_eq!(home_24.network_type, Some(NetworkType::Infrastructure));
+        assert_eq!(home_24.radio_type, Some(RadioType::Dot11n));
+        assert_eq!(home_24.band, Some(Band::Ghz2_4));
+        assert_eq!(home_24.channel, Some(6));
+        assert_eq!(home_24.basic_rates, vec![1.0, 2.0, 5.5, 11.0]);
+        assert_eq!(home_24.other_rates.len(), 8);
+
+        let office = &networks[3];
+        assert_eq!(office.radio_type, Some(RadioType::Dot11ac));
+        assert_eq!(office.band, Some(Band::Ghz5));
+        assert_eq!(office.channel, Some(44));
+    }
+
+    #[test]
+    fn test_bss_load_does_not_override_channel() {
+        let networks = parse_network_scan(NETWORKS_EN);
+        let home_5g = &networks[1];
+
+        assert_eq!(home_5g.bssid, "f4:92:bf:12:34:57");
+        assert_eq!(home_5g.channel, Some(36));
+        assert_eq!(home_5g.radio_type, Some(RadioType::Dot11ax));
+        assert_eq!(home_5g.basic_rates, vec![6.0, 12.0, 24.0]);
+    }
+
+    #[test]
+    fn test_band_falls_back_to_channel() {
+        let output = "SSID 1 : Legacy\n    Authentication : Open\n    Encryption : None\n    BSSID 1 : 00:11:22:33:44:55\n         Signal : 40%\n         Channel : 149\n";
+        let networks = parse_network_scan(output);
+
+        assert_eq!(networks[0].band, Some(Band::Ghz5));
+        assert_eq!(networks[0].radio_type, None);
+        assert!(networks[0].basic_rates.is_empty());
+    }
+
+    #[test]
+    fn test_localized_output_matches_english() {
+        let english = parse_network_scan(NETWORKS_EN);
+
+        for (locale, output) in NETWORKS_LOCALIZED {
+            let networks = parse_network_scan(output);
+            assert_eq!(networks.len(), english.len(), "{}", locale);
+
+            for (net, en) in networks.iter().zip(&english) {
+                assert_eq!(net.ssid, en.ssid, "{}", locale);
+                assert_eq!(net.bssid, en.bssid, "{}", locale);
+                assert_eq!(net.authentication, en.authentication, "{}", locale);
+                assert_eq!(net.encryption, en.encryption, "{}", locale);
+                assert_eq!(net.signal, en.signal, "{}", locale);
+                assert_eq!(net.network_type, en.network_type, "{}", locale);
+                assert_eq!(net.radio_type, en.radio_type, "{}", locale);
+                assert_eq!(net.band, en.band, "{}", locale);
+                assert_eq!(net.channel, en.channel, "{}", locale);
+                assert_eq!(net.basic_rates, en.basic_rates, "{}", locale);
+                assert_eq!(net.other_rates, en.other_rates, "{}", locale);
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_locale_falls_back_to_position() {
+        // No Polish labels are in the tables.
+        let networks = parse_network_scan(include_str!("../../fixtures/netsh/networks_pl.txt"));
+        assert_eq!(networks.len(), 4);
+
+        let airport = &networks[2];
+        assert_eq!(airport.ssid, "Free Airport WiFi");
+        assert_eq!(airport.authentication, "Open");
+        assert_eq!(airport.encryption, "None");
+        assert_eq!(airport.network_type, Some(NetworkType::Infrastructure));
+        assert_eq!(airport.signal.map(|s| s.percent), Some(90));
+        assert_eq!(airport.radio_type, Some(RadioType::Dot11g));
+
+        // The Bss Load utilisation percentage does not replace the signal.
+        assert_eq!(networks[1].signal.map(|s| s.percent), Some(70));
+    }
+
+    #[test]
+    fn test_hidden_ssid_is_skipped() {
+        let output = "SSID 1 : \n    Authentication : WPA2-Personal\n    BSSID 1 : 00:11:22:33:44:55\n         Signal : 40%\n";
+        assert!(parse_network_scan(output).is_empty());
+    }
+}
@@ -0,0 +1,207 @@
+use crate::structures::{AuthSuite, Cipher, Signal, WifiNetwork};
+use crate::wifi_functions::frequency::band_from_frequency;
+
+/// Field list the nmcli backend asks for, in the order the parser expects.
+pub const NMCLI_SCAN_FIELDS: &str = "SSID,BSSID,CHAN,FREQ,SIGNAL,SECURITY,RSN-FLAGS,WPA-FLAGS";
+
+/// Parse `nmcli -t -f SSID,BSSID,CHAN,FREQ,SIGNAL,SECURITY,RSN-FLAGS,WPA-FLAGS dev wifi list`.
+pub fn parse_nmcli_scan(output: &str) -> Vec<WifiNetwork> {
+    output
+        .lines()
+        .filter_map(|line| network_from_fields(&split_terse_line(line)))
+        .collect()
+}
+
+/// Same as [`parse_nmcli_scan`], with a leading `ACTIVE` field; keeps only the
+/// entry the adapter is associated with.
+pub fn parse_nmcli_active(output: &str) -> Vec<WifiNetwork> {
+    output
+        .lines()
+        .map(split_terse_line)
+        .filter(|fields| fields.first().map(|f| f == "yes").unwrap_or(false))
+        .filter_map(|fields| network_from_fields(&fields[1..]))
+        .collect()
+}
+
+/// Split a terse-mode line on `:`, honouring nmcli's `\:` and `\\` escapes.
+fn split_terse_line(line: &str) -> Vec<String> {
+    let mut fields = Vec::new();
+    let mut current = String::new();
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    current.push(escaped);
+                }
+            }
+            ':' => fields.push(std::mem::take(&mut current)),
+            _ => current.push(c),
+        }
+    }
+    fields.push(current);
+
+    fields
+}
+
+fn network_from_fields(fields: &[String]) -> Option<WifiNetwork> {
+    if fields.len() < 8 {
+        return None;
+    }
+
+    let ssid = fields[0].trim();
+    let bssid = fields[1].trim();
+    if ssid.is_empty() || ssid == "--" || bssid.is_empty() {
+        return None;
+    }
+
+    let (authentication, encryption) = nm_security_to_auth_encryption(&fields[5], &fields[6], &fields[7]);
+
+    let signal = fields[4].trim().parse::<u8>().ok().map(Signal::from_percent);
+
+    let frequency_mhz = fields[3].trim().trim_end_matches("MHz").trim().parse().ok();
+
+    Some(WifiNetwork {
+        ssid: ssid.to_string(),
+        authentication,
+        encryption,
+        bssid: bssid.to_lowercase(),
+        signal,
+        risk: String::new(),
+        is_evil_twin: false,
+        channel: fields[2].trim().parse().ok(),
+        frequency_mhz,
+        band: frequency_mhz.and_then(band_from_frequency),
+        ..Default::default()
+    })
+}
+
+/// Map NetworkManager's SECURITY column and RSN/WPA flags onto the
+/// authentication/cipher names netsh uses.
+pub fn nm_security_to_auth_encryption(security: &str, rsn_flags: &str, wpa_flags: &str) -> (AuthSuite, Cipher) {
+    let rsn: Vec<&str> = flag_words(rsn_flags);
+    let wpa: Vec<&str> = flag_words(wpa_flags);
+    let has = |flags: &[&str], flag: &str| flags.iter().any(|f| f.eq_ignore_ascii_case(flag));
+
+    let authentication = if has(&rsn, "eap_suite_b_192") {
+        AuthSuite::Wpa3Enterprise
+    } else if has(&rsn, "sae") && has(&rsn, "psk") {
+        AuthSuite::Wpa3Transition
+    } else if has(&rsn, "sae") {
+        AuthSuite::Wpa3Personal
+    } else if has(&rsn, "owe") || has(&rsn, "owe_tm") {
+        AuthSuite::Owe
+    } else if has(&rsn, "802.1X") {
+        AuthSuite::Wpa2Enterprise
+    } else if has(&rsn, "psk") {
+        AuthSuite::Wpa2Personal
+    } else if has(&wpa, "802.1X") {
+        AuthSuite::WpaEnterprise
+    } else if has(&wpa, "psk") {
+        AuthSuite::WpaPersonal
+    } else {
+        AuthSuite::Open
+    };
+
+    let pairwise: Vec<&str> = rsn.iter().chain(wpa.iter()).copied().collect();
+    let encryption = if has(&pairwise, "pair_ccmp") {
+        Cipher::Ccmp
+    } else if has(&pairwise, "pair_tkip") {
+        Cipher::Tkip
+    } else if has(&pairwise, "pair_wep104")
+        || has(&pairwise, "pair_wep40")
+        || security.split_whitespace().any(|s| s.eq_ignore_ascii_case("WEP"))
+    {
+        Cipher::Wep
+    } else {
+        Cipher::None
+    };
+
+    (authentication, encryption)
+}
+
+fn flag_words(flags: &str) -> Vec<&str> {
+    flags
+        .split_whitespace()
+        .filter(|f| *f != "(none)" && *f != "--")
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{nm_security_to_auth_encryption, parse_nmcli_active, parse_nmcli_scan};
+    use crate::structures::Band;
+
+    const DEV_WIFI_LIST: &str = include_str!("../../fixtures/nmcli/dev_wifi_list.txt");
+    const DEV_WIFI_LIST_ACTIVE: &str = include_str!("../../fixtures/nmcli/dev_wifi_list_active.txt");
+
+    #[test]
+    fn test_parses_captured_list() {
+        let networks = parse_nmcli_scan(DEV_WIFI_LIST);
+
+        // The hidden network on the last line is skipped, like the netsh parser does.
+        assert_eq!(networks.len(), 10);
+        assert_eq!(networks[0].ssid, "HomeNet");
+        assert_eq!(networks[0].bssid, "f4:92:bf:12:34:56");
+        assert_eq!(networks[0].signal.map(|s| s.percent), Some(82));
+        assert_eq!(networks[0].authentication, "WPA2-Personal");
+        assert_eq!(networks[0].encryption, "CCMP");
+        assert_eq!(networks[0].channel, Some(6));
+        assert_eq!(networks[0].frequency_mhz, Some(2437));
+        assert_eq!(networks[0].band, Some(Band::Ghz2_4));
+    }
+
+    #[test]
+    fn test_escaped_colon_in_ssid() {
+        let networks = parse_nmcli_scan(DEV_WIFI_LIST);
+        let cafe = networks.iter().find(|n| n.bssid == "22:33:44:55:66:77").unwrap();
+
+        assert_eq!(cafe.ssid, "Cafe:Guest");
+        assert_eq!(cafe.authentication, "OWE");
+    }
+
+    #[test]
+    fn test_security_mapping_from_fixture() {
+        let networks = parse_nmcli_scan(DEV_WIFI_LIST);
+        let by_ssid = |ssid: &str| networks.iter().find(|n| n.ssid == ssid).unwrap();
+
+        assert_eq!(by_ssid("Office").authentication, "WPA2-Enterprise");
+        assert_eq!(by_ssid("Modern").authentication, "WPA3-Personal");
+        assert_eq!(by_ssid("Mixed").authentication, "WPA2/WPA3-Personal");
+        assert_eq!(by_ssid("Legacy").authentication, "WPA2-Personal");
+        assert_eq!(by_ssid("Legacy").encryption, "CCMP");
+        assert_eq!(by_ssid("OldWpa").authentication, "WPA-Personal");
+        assert_eq!(by_ssid("OldWpa").encryption, "TKIP");
+        assert_eq!(by_ssid("Free Airport WiFi").authentication, "Open");
+        assert_eq!(by_ssid("Free Airport WiFi").encryption, "None");
+        assert_eq!(by_ssid("Printer").authentication, "Open");
+        assert_eq!(by_ssid("Printer").encryption, "WEP");
+    }
+
+    #[test]
+    fn test_enterprise_suite_b() {
+        let (auth, enc) = nm_security_to_auth_encryption(
+            "WPA3 802.1X",
+            "pair_ccmp group_ccmp eap_suite_b_192",
+            "(none)",
+        );
+        assert_eq!(auth, "WPA3-Enterprise");
+        assert_eq!(enc, "CCMP");
+    }
+
+    #[test]
+    fn test_active_keeps_only_associated_bssid() {
+        let active = parse_nmcli_active(DEV_WIFI_LIST_ACTIVE);
+
+        assert_eq!(active.len(), 1);
+        assert_eq!(active[0].bssid, "f4:92:bf:12:34:57");
+    }
+
+    #[test]
+    fn test_malformed_lines_are_ignored() {
+        assert!(parse_nmcli_scan("").is_empty());
+        assert!(parse_nmcli_scan("Error: Wi-Fi radio is off\n").is_empty());
+        assert!(parse_nmcli_scan("OnlySsid:AA\\:BB").is_empty());
+    }
+}
@@ -0,0 +1,30 @@
+use crate::wifi_functions::oui_database::{self, OuiDatabase, OUI_FILE};
+use tauri::{AppHandle, Manager};
+
+/// Replace the vendor table with IEEE registry exports (`oui.csv`,
+/// `mam.csv`, `oui36.csv`) and keep it for later runs. Returns the number of
+/// assignments loaded.
+#[tauri::command]
+pub fn refresh_oui_database(app: AppHandle, paths: Vec<String>) -> Result<usize, String> {
+    if paths.is_empty() {
+        return Err("No OUI registry files given".to_string());
+    }
+    let db = OuiDatabase::from_files(&paths)?;
+    if db.is_empty() {
+        return Err("The files contain no MA-L, MA-M or MA-S assignments".to_string());
+    }
+
+    let dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| format!("Failed to locate the app data dir: {}", e))?;
+    std::fs::create_dir_all(&dir)
+        .map_err(|e| format!("Failed to create {}: {}", dir.display(), e))?;
+    let path = dir.join(OUI_FILE);
+    std::fs::write(&path, db.to_csv())
+        .map_err(|e| format!("Failed to write {}: {}", path.display(), e))?;
+
+    let count = db.len();
+    oui_database::install(db);
+    Ok(count)
+}
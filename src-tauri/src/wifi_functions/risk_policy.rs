@@ -0,0 +1,401 @@
+use crate::structures::{AuthSuite, Cipher, RiskGrade};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
+
+/// File names looked up in the app data dir, in order.
+pub const POLICY_FILES: [&str; 2] = ["risk_policy.toml", "risk_policy.json"];
+
+/// Weights and thresholds `calculate_risk` scores networks with.
+///
+/// Every section is optional in a policy file; missing values keep the
+/// built-in defaults.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+#[serde(default, deny_unknown_fields)]
+pub struct RiskPolicy {
+    pub auth: AuthWeights,
+    pub cipher: CipherWeights,
+    pub signal: SignalWeights,
+    pub ssid_keywords: KeywordWeights,
+    pub grades: GradeThresholds,
+    /// Exact weight for an authentication suite, e.g. `"WPA2-Enterprise" = 0`.
+    pub auth_overrides: HashMap<AuthSuite, u32>,
+    /// Exact weight for a cipher, e.g. `"TKIP" = 40`.
+    pub cipher_overrides: HashMap<Cipher, u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct AuthWeights {
+    pub wpa3: u32,
+    pub wpa2: u32,
+    pub wpa: u32,
+    pub open: u32,
+    /// OWE, shared-key WEP and anything unrecognised.
+    pub other: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct CipherWeights {
+    /// CCMP and GCMP.
+    pub strong: u32,
+    pub tkip: u32,
+    pub wep: u32,
+    /// No cipher, or none reported.
+    pub none: u32,
+    pub other: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignalWeights {
+    /// Link quality in percent above which `strong_weight` applies.
+    pub strong_above: u8,
+    pub strong_weight: u32,
+    pub medium_above: u8,
+    pub medium_weight: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeywordWeights {
+    pub weight: u32,
+    /// Matched case-insensitively anywhere in the SSID.
+    pub keywords: Vec<String>,
+}
+
+/// Lowest score of each grade above "L".
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[serde(default, deny_unknown_fields)]
+pub struct GradeThresholds {
+    pub medium: u32,
+    pub high: u32,
+    pub critical: u32,
+}
+
+pub type SharedRiskPolicy = Arc<RiskPolicy>;
+
+impl Default for AuthWeights {
+    fn default() -> Self {
+        Self {
+            wpa3: 0,
+            wpa2: 10,
+            wpa: 20,
+            open: 50,
+            other: 30,
+        }
+    }
+}
+
+impl Default for CipherWeights {
+    fn default() -> Self {
+        Self {
+            strong: 0,
+            tkip: 20,
+            wep: 50,
+            none: 50,
+            other: 10,
+        }
+    }
+}
+
+impl Default for SignalWeights {
+    fn default() -> Self {
+        Self {
+            strong_above: 80,
+            strong_weight: 20,
+            medium_above: 50,
+            medium_weight: 10,
+        }
+    }
+}
+
+impl Default for KeywordWeights {
+    fn default() -> Self {
+        Self {
+            weight: 30,
+            keywords: vec![
+                "free".to_string(),
+                "xfinity".to_string(),
+                "wifi".to_string(),
+            ],
+        }
+    }
+}
+
+impl Default for GradeThresholds {
+    fn default() -> Self {
+        Self {
+            medium: 40,
+            high: 70,
+            critical: 90,
+        }
+    }
+}
+
+impl RiskPolicy {
+    pub fn from_toml(content: &str) -> Result<Self, String> {
+        let policy: Self =
+            toml::from_str(content).map_err(|e| format!("Invalid risk policy: {}", e))?;
+        policy.validated()
+    }
+
+    pub fn from_json(content: &str) -> Result<Self, String> {
+        let policy: Self =
+            serde_json::from_str(content).map_err(|e| format!("Invalid risk policy: {}", e))?;
+        policy.validated()
+    }
+
+    /// Load the first policy file found in `dir`, or the built-in policy when
+    /// there is none.
+    pub fn load(dir: &Path) -> Result<Self, String> {
+        for name in POLICY_FILES {
+            let path = dir.join(name);
+            if !path.exists() {
+                continue;
+            }
+
+            let content = std::fs::read_to_string(&path)
+                .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+            let policy = if name.ends_with(".json") {
+                Self::from_json(&content)
+            } else {
+                Self::from_toml(&content)
+            };
+            return policy.map_err(|e| format!("{}: {}", path.display(), e));
+        }
+
+        Ok(Self::default())
+    }
+
+    pub fn auth_weight(&self, authentication: &AuthSuite) -> u32 {
+        if let Some(weight) = self.auth_overrides.get(authentication) {
+            return *weight;
+        }
+        match authentication {
+            AuthSuite::Wpa3Personal | AuthSuite::Wpa3Enterprise | AuthSuite::Wpa3Transition => {
+                self.auth.wpa3
+            }
+            AuthSuite::Wpa2Personal | AuthSuite::Wpa2Enterprise => self.auth.wpa2,
+            AuthSuite::WpaPersonal | AuthSuite::WpaEnterprise => self.auth.wpa,
+            AuthSuite::Open => self.auth.open,
+            _ => self.auth.other,
+        }
+    }
+
+    pub fn cipher_weight(&self, encryption: &Cipher) -> u32 {
+        if let Some(weight) = self.cipher_overrides.get(encryption) {
+            return *weight;
+        }
+        match encryption {
+            Cipher::Ccmp | Cipher::Gcmp => self.cipher.strong,
+            Cipher::Tkip => self.cipher.tkip,
+            Cipher::Wep => self.cipher.wep,
+            Cipher::None => self.cipher.none,
+            Cipher::Other(e) if e.is_empty() => self.cipher.none,
+            Cipher::Other(_) => self.cipher.other,
+        }
+    }
+
+    pub fn signal_weight(&self, percent: u8) -> u32 {
+        if percent > self.signal.strong_above {
+            self.signal.strong_weight
+        } else if percent > self.signal.medium_above {
+            self.signal.medium_weight
+        } else {
+            0
+        }
+    }
+
+    /// The first configured keyword contained in `ssid`.
+    pub fn ssid_keyword(&self, ssid: &str) -> Option<&str> {
+        let ssid = ssid.to_lowercase();
+        self.ssid_keywords
+            .keywords
+            .iter()
+            .find(|k| ssid.contains(k.as_str()))
+            .map(String::as_str)
+    }
+
+    pub fn grade(&self, score: u32) -> RiskGrade {
+        if score >= self.grades.critical {
+            RiskGrade::Critical
+        } else if score >= self.grades.high {
+            RiskGrade::High
+        } else if score >= self.grades.medium {
+            RiskGrade::Medium
+        } else {
+            RiskGrade::Low
+        }
+    }
+
+    /// Lowest score that earns `grade`.
+    pub fn score_floor(&self, grade: RiskGrade) -> u32 {
+        match grade {
+            RiskGrade::Low => 0,
+            RiskGrade::Medium => self.grades.medium,
+            RiskGrade::High => self.grades.high,
+            RiskGrade::Critical => self.grades.critical,
+        }
+    }
+
+    fn validated(mut self) -> Result<Self, String> {
+        let mut errors = Vec::new();
+
+        let g = &self.grades;
+        if !(0 < g.medium && g.medium < g.high && g.high < g.critical) {
+            errors.push(format!(
+                "grades must satisfy 0 < medium < high < critical (got {}, {}, {})",
+                g.medium, g.high, g.critical
+            ));
+        }
+
+        let s = &self.signal;
+        if s.strong_above > 100 || s.medium_above > 100 {
+            errors.push("signal thresholds are percentages and must not exceed 100".to_string());
+        }
+        if s.medium_above >= s.strong_above {
+            errors.push(format!(
+                "signal.medium_above ({}) must be below signal.strong_above ({})",
+                s.medium_above, s.strong_above
+            ));
+        }
+
+        for keyword in self.ssid_keywords.keywords.iter_mut() {
+            *keyword = keyword.trim().to_lowercase();
+        }
+        if self.ssid_keywords.keywords.iter().any(String::is_empty) {
+            errors.push("ssid_keywords.keywords must not contain empty entries".to_string());
+        }
+
+        for auth in self.auth_overrides.keys() {
+            if let AuthSuite::Other(name) = auth {
+                errors.push(format!("auth_overrides: unknown authentication '{}'", name));
+            }
+        }
+        for cipher in self.cipher_overrides.keys() {
+            if let Cipher::Other(name) = cipher {
+                errors.push(format!("cipher_overrides: unknown cipher '{}'", name));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(format!("Invalid risk policy: {}", errors.join("; ")))
+        }
+    }
+}
+
+/// Load the policy for this run from `dir`, falling back to the built-in
+/// policy when the file is missing or invalid.
+pub fn load_risk_policy(dir: &Path) -> SharedRiskPolicy {
+    match RiskPolicy::load(dir) {
+        Ok(policy) => Arc::new(policy),
+        Err(e) => {
+            eprintln!("{}; using the built-in risk policy", e);
+            Arc::new(RiskPolicy::default())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RiskPolicy;
+    use crate::structures::{AuthSuite, Cipher, RiskGrade};
+
+    #[test]
+    fn test_partial_toml_keeps_defaults() {
+        let policy = RiskPolicy::from_toml(
+            r#"
+            [auth]
+            open = 80
+
+            [ssid_keywords]
+            keywords = ["Guest", "hotspot"]
+
+            [auth_overrides]
+            "WPA2-Enterprise" = 0
+
+            [cipher_overrides]
+            TKIP = 45
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(policy.auth_weight(&AuthSuite::Open), 80);
+        assert_eq!(policy.auth_weight(&AuthSuite::Wpa2Personal), 10);
+        assert_eq!(policy.auth_weight(&AuthSuite::Wpa2Enterprise), 0);
+        assert_eq!(policy.cipher_weight(&Cipher::Tkip), 45);
+        assert_eq!(policy.ssid_keyword("My GUEST net"), Some("guest"));
+        assert_eq!(policy.ssid_keyword("Free WiFi"), None);
+        assert_eq!(policy.grades, RiskPolicy::default().grades);
+    }
+
+    #[test]
+    fn test_json_policy() {
+        let policy =
+            RiskPolicy::from_json(r#"{"grades": {"medium": 30, "high": 60, "critical": 80}}"#)
+                .unwrap();
+
+        assert_eq!(policy.grade(29), RiskGrade::Low);
+        assert_eq!(policy.grade(30), RiskGrade::Medium);
+        assert_eq!(policy.grade(80), RiskGrade::Critical);
+        assert_eq!(policy.score_floor(RiskGrade::High), 60);
+    }
+
+    #[test]
+    fn test_default_matches_original_cutoffs() {
+        let policy = RiskPolicy::default();
+
+        assert_eq!(policy.grade(39), RiskGrade::Low);
+        assert_eq!(policy.grade(40), RiskGrade::Medium);
+        assert_eq!(policy.grade(69), RiskGrade::Medium);
+        assert_eq!(policy.grade(89), RiskGrade::High);
+        assert_eq!(policy.grade(90), RiskGrade::Critical);
+        assert_eq!(policy.signal_weight(81), 20);
+        assert_eq!(policy.signal_weight(80), 10);
+        assert_eq!(policy.signal_weight(50), 0);
+    }
+
+    #[test]
+    fn test_validation_errors() {
+        let err = RiskPolicy::from_toml("[grades]\nmedium = 70\nhigh = 40\n").unwrap_err();
+        assert!(err.contains("medium < high"), "{}", err);
+
+        let err = RiskPolicy::from_toml("[auth_overrides]\n\"WPA9\" = 5\n").unwrap_err();
+        assert!(err.contains("unknown authentication 'WPA9'"), "{}", err);
+
+        let err = RiskPolicy::from_toml("[signal]\nmedium_above = 90\n").unwrap_err();
+        assert!(err.contains("medium_above"), "{}", err);
+
+        // Typos are reported rather than silently ignored.
+        let err = RiskPolicy::from_toml("[auth]\nopn = 10\n").unwrap_err();
+        assert!(err.contains("opn"), "{}", err);
+
+        assert!(RiskPolicy::from_json("{\"grades\": 5}").is_err());
+    }
+
+    #[test]
+    fn test_load_from_dir() {
+        let dir = std::env::temp_dir().join(format!("wids-policy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(RiskPolicy::load(&dir).unwrap(), RiskPolicy::default());
+
+        std::fs::write(dir.join("risk_policy.json"), r#"{"auth": {"wpa2": 15}}"#).unwrap();
+        assert_eq!(RiskPolicy::load(&dir).unwrap().auth.wpa2, 15);
+
+        // TOML wins when both exist.
+        std::fs::write(dir.join("risk_policy.toml"), "[auth]\nwpa2 = 25\n").unwrap();
+        assert_eq!(RiskPolicy::load(&dir).unwrap().auth.wpa2, 25);
+
+        std::fs::write(dir.join("risk_policy.toml"), "[grades]\ncritical = 1\n").unwrap();
+        let err = RiskPolicy::load(&dir).unwrap_err();
+        assert!(err.contains("risk_policy.toml"), "{}", err);
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
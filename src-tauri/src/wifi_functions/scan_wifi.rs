@@ -1,73 +1,203 @@
+use crate::backends::SharedBackend;
+use crate::baseline::{BaselineStore, SharedBaseline};
+use crate::history::{ScanSource, SharedHistory};
+use crate::rules::{RuleSet, SharedRuleSet};
 use crate::structures::WifiNetwork;
-use crate::wifi_functions::trigger_scan::trigger_scan;
-use crate::wifi_functions::{
-    evil_twin_detection::mark_evil_twins, parse_network_scan::parse_network_scan,
-};
-use std::{process::Command, thread, time::Duration};
+use crate::wifi_functions::{calculate_risk::calculate_risk, evil_twin_detection::mark_evil_twins};
+use crate::wifi_functions::karma_detection::{mark_karma, KarmaDetector};
+use crate::wifi_functions::lookalike_detection::mark_lookalikes;
+use crate::wifi_functions::{RiskPolicy, SharedKarmaDetector, SharedRiskPolicy};
+use tauri::State;
 
+/// `whitelisted_ssids` are the user's whitelisted names; together with the
+/// trusted baseline they are the names look-alikes are checked against.
 #[tauri::command]
-pub fn scan_wifi() -> Result<Vec<WifiNetwork>, String> {
-    let interface_output = Command::new("netsh")
-        .args(["wlan", "show", "interfaces"])
-        .output()
-        .map_err(|e| format!("Failed to check WiFi adapter state: {}", e))?;
-
-    let interface_result = String::from_utf8_lossy(&interface_output.stdout);
-    
-    let mut has_interface = false;
-    let mut radio_software_off = false;
-    
-    let lines: Vec<&str> = interface_result.lines().collect();
-    for (i, line) in lines.iter().enumerate() {
-        let trimmed = line.trim();
-        
-        if trimmed.starts_with("State") {
-            has_interface = true;
-        }
-        
-        if trimmed.contains("Radio status") {
-            for j in (i + 1)..std::cmp::min(i + 5, lines.len()) {
-                if lines[j].contains("Software Off") {
-                    radio_software_off = true;
-                    break;
-                }
-            }
-        }
-        
-        if trimmed.contains("Software Off") {
-            radio_software_off = true;
+pub fn scan_wifi(
+    whitelisted_ssids: Option<Vec<String>>,
+    backend: State<'_, SharedBackend>,
+    policy: State<'_, SharedRiskPolicy>,
+    rules: State<'_, SharedRuleSet>,
+    baseline: State<'_, SharedBaseline>,
+    karma: State<'_, SharedKarmaDetector>,
+    history: State<'_, SharedHistory>,
+) -> Result<Vec<WifiNetwork>, String> {
+    // The platform scan can take seconds; nothing is locked while it runs
+    let networks = backend.scan()?;
+    let networks = {
+        let baseline = baseline.lock().unwrap_or_else(|e| e.into_inner());
+        let mut karma = karma.lock().unwrap_or_else(|e| e.into_inner());
+        let context = ScoringContext {
+            policy: &policy,
+            rules: &rules,
+            baseline: &baseline,
+            whitelisted: &whitelisted_ssids.unwrap_or_default(),
+        };
+        score_scan(networks, &context, &mut karma)
+    };
+
+    let mut history = history.lock().unwrap_or_else(|e| e.into_inner());
+    if let Err(e) = history.record_scan(&networks, ScanSource::Manual, chrono::Utc::now()) {
+        eprintln!("Failed to record scan: {}", e);
+    }
+    Ok(networks)
+}
+
+/// What the networks of a scan are judged against.
+pub struct ScoringContext<'a> {
+    pub policy: &'a RiskPolicy,
+    pub rules: &'a RuleSet,
+    pub baseline: &'a BaselineStore,
+    /// The user's whitelisted SSIDs, which look-alikes are checked against
+    /// along with the baseline's.
+    pub whitelisted: &'a [String],
+}
+
+/// Score the `networks` of a scan under the context's policy, compare them
+/// with the trusted baseline, flag names imitating a trusted or whitelisted
+/// SSID, add the scan to the `karma` window and let the detection rules have
+/// the last word.
+pub fn score_scan(
+    mut networks: Vec<WifiNetwork>,
+    context: &ScoringContext,
+    karma: &mut KarmaDetector,
+) -> Vec<WifiNetwork> {
+    let ScoringContext {
+        policy,
+        rules,
+        baseline,
+        whitelisted,
+    } = *context;
+    for net in networks.iter_mut() {
+        net.fill_from_bssid();
+        let assessment = calculate_risk(policy, &net.authentication, &net.encryption, net.signal, &net.ssid);
+        net.set_risk(assessment);
+    }
+    mark_evil_twins(&mut networks, policy);
+    baseline.check(&mut networks, policy);
+    let protected = baseline
+        .ssids()
+        .map(str::to_string)
+        .chain(whitelisted.iter().cloned())
+        .collect();
+    mark_lookalikes(&mut networks, &protected, policy);
+    let findings = karma.observe(&networks, chrono::Utc::now());
+    mark_karma(&mut networks, &findings, policy);
+    rules.apply(&mut networks);
+
+    networks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{score_scan, ScoringContext};
+    use crate::baseline::BaselineStore;
+    use crate::rules::RuleSet;
+    use crate::wifi_functions::karma_detection::KarmaDetector;
+    use crate::wifi_functions::RiskPolicy;
+    use crate::structures::{AuthSuite, Cipher, RiskFactorKind, RiskGrade, Signal, WifiNetwork};
+
+    fn network(ssid: &str, bssid: &str, auth: &str, encryption: &str, signal: &str) -> WifiNetwork {
+        WifiNetwork {
+            ssid: ssid.to_string(),
+            authentication: AuthSuite::parse(auth),
+            encryption: Cipher::parse(encryption),
+            bssid: bssid.to_string(),
+            signal: Signal::parse(signal),
+            risk: String::new(),
+            is_evil_twin: false,
+            ..Default::default()
         }
     }
-    
-    if !has_interface {
-        return Err("No WiFi adapter found. Please ensure your WiFi adapter is installed and enabled.".to_string());
+
+    fn score(scan: Vec<WifiNetwork>, whitelisted: &[String]) -> Vec<WifiNetwork> {
+        let context = ScoringContext {
+            policy: &RiskPolicy::default(),
+            rules: &RuleSet::default(),
+            baseline: &BaselineStore::default(),
+            whitelisted,
+        };
+        score_scan(scan, &context, &mut KarmaDetector::default())
     }
-    
-    if radio_software_off {
-        return Err("WiFi adapter is turned off. Please enable WiFi in Windows Settings or use the WiFi toggle in the system tray.".to_string());
+
+    #[test]
+    fn test_scan_scores_backend_results() {
+        let scan = vec![
+            network("Home", "00:11:22:33:44:55", "WPA3-Personal", "CCMP", "40%"),
+            network("Free Cafe", "66:77:88:99:aa:bb", "Open", "None", "90%"),
+        ];
+
+        let networks = score(scan, &[]);
+
+        assert_eq!(networks[0].risk, "L");
+        assert_eq!(networks[1].risk, "C");
     }
 
-    trigger_scan();
-    thread::sleep(Duration::from_secs(2));
+    #[test]
+    fn test_evil_twin_is_explained() {
+        let scan = vec![
+            network("Office", "00:11:22:33:44:55", "WPA2-Personal", "CCMP", "50%"),
+            network("Office", "02:00:00:00:00:01", "Open", "None", "95%"),
+        ];
+
+        let networks = score(scan, &[]);
+        let twin = networks[1].risk_assessment.as_ref().unwrap();
 
-    let output = Command::new("netsh")
-        .args(["wlan", "show", "networks", "mode=bssid"])
-        .output()
-        .map_err(|e| format!("Failed to scan WiFi networks: {}", e))?;
+        assert_eq!(networks[1].risk, "C");
+        assert_eq!(twin.grade, RiskGrade::Critical);
+        let factor = twin.factors.last().unwrap();
+        assert_eq!(factor.kind, RiskFactorKind::EvilTwin);
+        assert!(factor.reason.contains("weaker security"));
+        assert!(twin.verdict.contains("possible evil twin"));
 
-    let result = String::from_utf8_lossy(&output.stdout);
-    println!("{}", result);
-    
-    if result.contains("The wireless local area network interface is powered down") {
-        return Err("WiFi adapter is powered down. Please enable WiFi in Windows Settings.".to_string());
+        let genuine = networks[0].risk_assessment.as_ref().unwrap();
+        assert!(genuine.factors.iter().all(|f| f.kind != RiskFactorKind::EvilTwin));
     }
-    
-    if result.contains("doesn't support the requested operation") {
-        return Err("WiFi adapter is not ready for scanning. Please enable WiFi in Windows Settings.".to_string());
+
+    #[test]
+    fn test_rules_cannot_clear_an_evil_twin() {
+        let scan = vec![
+            network("Office", "00:11:22:33:44:55", "WPA2-Personal", "CCMP", "50%"),
+            network("Office", "02:00:00:00:00:01", "Open", "None", "95%"),
+        ];
+        let rules = RuleSet::from_toml(
+            r#"
+            [[rule]]
+            name = "office"
+            when = 'ssid == "Office"'
+            grade = "L"
+            reason = "Our office network"
+            "#,
+        )
+        .unwrap();
+        let context = ScoringContext {
+            policy: &RiskPolicy::default(),
+            rules: &rules,
+            baseline: &BaselineStore::default(),
+            whitelisted: &[],
+        };
+
+        let networks = score_scan(scan, &context, &mut KarmaDetector::default());
+
+        assert_eq!(networks[0].risk, "L");
+        assert!(networks[1].is_evil_twin);
+        assert_eq!(networks[1].risk, "C");
+        let twin = networks[1].risk_assessment.as_ref().unwrap();
+        assert!(twin.factors.iter().all(|f| f.kind != RiskFactorKind::Rule));
     }
-    
-    let mut networks = parse_network_scan(&result);
-    mark_evil_twins(&mut networks);
 
-    Ok(networks)
+    #[test]
+    fn test_lookalike_of_whitelisted_ssid() {
+        let scan = vec![
+            network("CorpNet", "00:11:22:33:44:55", "WPA2-Personal", "CCMP", "50%"),
+            network("C0rpNet", "66:77:88:99:aa:bb", "WPA2-Personal", "CCMP", "50%"),
+        ];
+
+        let networks = score(scan, &["CorpNet".to_string()]);
+
+        assert_eq!(networks[0].risk, "L");
+        assert_eq!(networks[1].risk, "C");
+        assert_eq!(networks[1].imitates.as_deref(), Some("CorpNet"));
+        let factor = networks[1].risk_assessment.as_ref().unwrap().factors.last().unwrap();
+        assert_eq!(factor.kind, RiskFactorKind::Lookalike);
+    }
 }
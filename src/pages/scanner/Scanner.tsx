@@ -33,6 +33,7 @@ const Scanner: FC = () => {
   
   const effectiveBlacklist = isTempUser ? cachedNetworks.blacklist : blacklist
   const effectiveWhitelist = isTempUser ? cachedNetworks.whitelist : whitelist
+  const whitelistedSsids = effectiveWhitelist.map(wl => wl.ssid).filter(Boolean)
 
 
   const RISK_CHIPS = ['Critical', 'High', 'Medium', 'Low', 'Whitelisted']
@@ -114,7 +115,7 @@ const Scanner: FC = () => {
       if (!isTempUser) {
         addLog({ action: "SCAN_START", network_ssid: "-", details: "User started Wi-Fi scan" })
       }
-      const result = await invoke<WifiNetworkType[]>('scan_wifi')
+      const result = await invoke<WifiNetworkType[]>('scan_wifi', { whitelistedSsids })
       setNetworks(result)
       if (!isTempUser) {
         addLog({ action: "SCAN_SUCCESS", network_ssid: "-", details: `Found ${result.length} networks` })
@@ -136,7 +137,7 @@ const Scanner: FC = () => {
 
   const fetchActiveNetwork = async () => {
     try {
-      const result = await invoke<WifiNetworkType[] | null>('get_active_network')
+      const result = await invoke<WifiNetworkType[] | null>('get_active_network', { whitelistedSsids })
       let network = result?.[0] ?? null
 
       if (network && localWhitelist.includes(network.bssid.toLowerCase())) {
@@ -288,7 +289,7 @@ const Scanner: FC = () => {
             if (!hasPreferredAuth) return false
           }
 
-          const signalStrength = parseInt(item.signal?.replace('%', '') || '0')
+          const signalStrength = item.signal?.percent ?? 0
           if (signalStrength < effectiveMinSignal) return false
 
           const itemRiskValue = riskLevelValue[item.risk] || 4
@@ -362,7 +363,7 @@ const Scanner: FC = () => {
             <div className="bg-[rgb(70,8,118)] text-white p-4 small-laptop:p-5 rounded-b shadow absolute top-[auto] small-laptop:top-[72px] left-0 right-0 small-laptop:right-auto z-10"
                  data-tour="network-details">
               <p className="font-bold">BSSID: <span className="font-normal">{activeNetwork.bssid}</span></p>
-              <p className="font-bold">Signal: <span className="font-normal">{activeNetwork.signal}</span></p>
+              <p className="font-bold">Signal: <span className="font-normal">{activeNetwork.signal ? `${activeNetwork.signal.percent}% (${activeNetwork.signal.dbm} dBm)` : 'Unknown'}</span></p>
               <p className="font-bold">Risk: <Chip risk={activeNetwork.risk} /></p>
               <p className="font-bold">Authentication: <span
                 className="font-normal">{activeNetwork.authentication}</span>
@@ -448,14 +449,8 @@ const Scanner: FC = () => {
                 bValue = b.bssid || ''
                 break
               case 'Signal':
-                const parseSignal = (signal: string | undefined): number => {
-                  if (!signal) return 0
-                  const cleaned = signal.toString().replace(/[^0-9.]/g, '')
-                  const parsed = parseFloat(cleaned)
-                  return isNaN(parsed) ? 0 : parsed
-                }
-                aValue = parseSignal(a.signal)
-                bValue = parseSignal(b.signal)
+                aValue = a.signal?.dbm ?? -100
+                bValue = b.signal?.dbm ?? -100
                 break
               case 'Risk':
                 const riskOrder = { 'WL': 0, 'L': 1, 'M': 2, 'H': 3, 'C': 4 }
@@ -15,7 +15,7 @@ interface TableScannerProps {
 }
 
 const TableScanner: FC<TableScannerProps> = ({ data, isShowNetwork, onToggle, onFetchActiveNetwork, isTempUser = false }) => {
-  const { bssid, risk, signal, ssid, encryption, authentication, is_evil_twin } = data
+  const { bssid, risk, signal, ssid, encryption, authentication, is_evil_twin, imitates, vendor } = data
   const { isOpen, handleToggleIsOpenModal } = useIsModal()
   const [addBlacklist, { isLoading: isAdding }] = useAddBlacklistMutation()
   const [addWhitelist, { isLoading: isAddingWhitelist }] = useAddWhitelistMutation()
@@ -30,6 +30,10 @@ const TableScanner: FC<TableScannerProps> = ({ data, isShowNetwork, onToggle, on
     if (is_evil_twin) {
       threats.push('evil twin')
     }
+
+    if (imitates) {
+      threats.push(`imitates ${imitates}`)
+    }
     
     if (authentication && authentication.toLowerCase().includes('open')) {
       threats.push('open network')
@@ -53,7 +57,7 @@ const TableScanner: FC<TableScannerProps> = ({ data, isShowNetwork, onToggle, on
       }
     }
     
-    if ((risk === 'C' || risk === 'H') && !is_evil_twin) {
+    if ((risk === 'C' || risk === 'H') && !is_evil_twin && !imitates) {
       threats.push('rogue')
     }
     
@@ -178,13 +182,17 @@ const TableScanner: FC<TableScannerProps> = ({ data, isShowNetwork, onToggle, on
             <>
               {ssid}
               {is_evil_twin && risk !== 'WL' && <span className="ml-2 text-red-600 font-bold">(Evil Twin)</span>}
+              {imitates && risk !== 'WL' && <span className="ml-2 text-red-600 font-bold">(Imitates {imitates})</span>}
             </>
           )}
         </td>
         <td className="p-3" style={{ width: '15%' }}>{!authentication ? 'Hidden Network' : authentication}</td>
         <td className="p-3" style={{ width: '15%' }}>{!encryption ? 'Hidden Network' : encryption}</td>
-        <td className="p-3" style={{ width: '18%' }}>{!bssid ? 'Hidden Network' : bssid}</td>
-        <td className="p-3" style={{ width: '10%' }}>{!signal ? 'Hidden Network' : signal}</td>
+        <td className="p-3" style={{ width: '18%' }}>
+          {!bssid ? 'Hidden Network' : bssid}
+          {bssid && vendor && <div className="text-xs text-gray-500">{vendor}</div>}
+        </td>
+        <td className="p-3" style={{ width: '10%' }}>{!signal ? 'Hidden Network' : `${signal.percent}%`}</td>
         <td className="p-3" style={{ width: '12%' }}><Chip risk={risk} /></td>
 
 
@@ -1,9 +1,46 @@
+export type WifiBand = "2.4 GHz" | "5 GHz" | "6 GHz"
+
+export type WifiRadioType = "802.11b" | "802.11a" | "802.11g" | "802.11n" | "802.11ac" | "802.11ax" | "802.11be"
+
+export type WifiSignal = {
+  percent: number
+  dbm: number
+}
+
+export type RiskGrade = "L" | "M" | "H" | "C"
+
+export type RiskFactorKind = "authentication" | "cipher" | "signal" | "ssid_keyword" | "evil_twin" | "rule" | "baseline" | "lookalike" | "karma"
+
+export type RiskFactor = {
+  kind: RiskFactorKind
+  weight: number
+  reason: string
+}
+
+export type RiskAssessment = {
+  score: number
+  grade: RiskGrade
+  factors: RiskFactor[]
+  verdict: string
+}
+
 export type WifiNetworkType = {
   ssid: string
   authentication: string
   encryption: string
   bssid: string
-  signal: string
-  risk: "L" | "M" | "H" | "C" | "WL"
+  signal: WifiSignal | null
+  risk: RiskGrade | "WL"
+  risk_assessment?: RiskAssessment | null
   is_evil_twin: boolean
-}
\ No newline at end of file
+  imitates?: string | null
+  oui?: string | null
+  is_locally_administered?: boolean
+  vendor?: string | null
+  channel?: number | null
+  band?: WifiBand | null
+  radio_type?: WifiRadioType | null
+  network_type?: "infrastructure" | "ad_hoc" | null
+  basic_rates?: number[]
+  other_rates?: number[]
+}